@@ -70,5 +70,33 @@ pub $id: ::std::option::Option<::serde_json::Map<::std::string::String, ::serde_
         &mut ts,
     )?;
 
+    ts_replace(
+        "pub struct ToolInputSchema { $($field:tt)* }",
+        r#"
+pub struct ToolInputSchema {
+    $($field)*
+    ///Additional keywords of the JSON Schema, such as `$schema` and `$defs`
+    #[serde(flatten)]
+    pub extra: ::serde_json::Map<::std::string::String, ::serde_json::Value>,
+}
+        "#,
+        &mut ts,
+    )?;
+
+    // `Debug` and `PartialEq` are implemented in `schema_ext.rs` to redact secret arguments and to ignore `input_schema`.
+    ts_replace(
+        "#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)] pub struct CallToolRequestParams { $($field:tt)* }",
+        r#"
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Default)]
+pub struct CallToolRequestParams {
+    $($field)*
+    ///Input schema of the tool, used to redact values of `writeOnly` schemas from the `Debug` output
+    #[serde(skip)]
+    pub input_schema: ::std::option::Option<::std::sync::Arc<ToolInputSchema>>,
+}
+        "#,
+        &mut ts,
+    )?;
+
     Ok(ts)
 }
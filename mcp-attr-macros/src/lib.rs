@@ -19,12 +19,12 @@ use syn::{
 use uri_template_ex::UriTemplate;
 
 use syn_utils::{get_element, into_macro_output, is_path, is_type};
-use utils::{get_trait_path, is_defined};
+use utils::{get_trait_path, is_defined, is_defined_any};
 
 use crate::prompts::{PromptAttr, PromptEntry};
 use crate::resources::{ResourceAttr, ResourceEntry};
 use crate::tools::{ToolAttr, ToolEntry};
-use crate::utils::{build_if, drain_attr, get_doc};
+use crate::utils::{RenameRule, build_if, drain_attr, get_doc};
 
 #[macro_use]
 mod syn_utils;
@@ -53,6 +53,24 @@ pub fn mcp_server(
     .into()
 }
 
+#[proc_macro_attribute]
+pub fn mcp_tools(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let mut es = Vec::new();
+    match build_mcp_tools(attr.into(), item.into(), &mut es) {
+        Ok(mut s) => {
+            for e in es {
+                s.extend(e.to_compile_error());
+            }
+            s
+        }
+        Err(e) => e.to_compile_error(),
+    }
+    .into()
+}
+
 #[proc_macro]
 pub fn route(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     into_macro_output(build_route(item.into()))
@@ -106,7 +124,15 @@ fn build_mcp_server(
     }
     let is_defined_resources_list = is_defined(&item_impl.items, "resources_list");
     let impl_doc = get_doc(&item_impl.attrs);
-    let mut b = McpBuilder::new();
+    let parts = match &attr.tools {
+        Some(tools) => tools
+            .0
+            .iter()
+            .map(|name| name.parse::<Ident>())
+            .collect::<Result<Vec<_>>>()?,
+        None => Vec::new(),
+    };
+    let mut b = McpBuilder::new(RenameRule::from_attr(&attr.rename_all, None)?, None, parts);
     let mut items_trait = Vec::new();
     let mut items_type = Vec::new();
     for mut item in item_impl.items {
@@ -120,25 +146,125 @@ fn build_mcp_server(
             }
         }
     }
+    let client = if let Some(client) = &attr.client {
+        b.build_client(client, &item_impl.self_ty)?
+    } else {
+        quote!()
+    };
+    let locked = build_if(b.is_locked(), || b.build_tools_call_locked())?;
+    // `tools_list` checks the tool names in a `const` block, which `cargo check` does not evaluate.
+    // A free `const` is evaluated by `cargo check`, but it cannot refer to generic parameters.
+    let name_checks = if item_impl.generics.params.is_empty() {
+        b.build_tool_name_checks(&item_impl.self_ty.to_token_stream())
+    } else {
+        Vec::new()
+    };
+    let name_checks = if name_checks.is_empty() {
+        quote!()
+    } else {
+        quote!(const _: () = { #(#name_checks)* };)
+    };
     let (b, complete_fns) = b.build(&items_trait, &impl_doc)?;
-    let (impl_generics, ty_generics, where_clause) = item_impl.generics.split_for_impl();
+    let (impl_generics, _, where_clause) = item_impl.generics.split_for_impl();
 
     let self_ty = &item_impl.self_ty;
     let attrs = &item_impl.attrs;
+    let locked = if locked.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics ::mcp_attr::helpers::LockedMcpServer for #self_ty #where_clause {
+                #locked
+            }
+        }
+    };
     let ts = quote! {
         #[automatically_derived]
         #(#attrs)*
-        impl<#impl_generics> #trait_path for #self_ty #ty_generics #where_clause {
+        impl #impl_generics #trait_path for #self_ty #where_clause {
             #(#items_trait)*
             #b
         }
 
         #[automatically_derived]
         #(#attrs)*
-        impl<#impl_generics> #self_ty #ty_generics #where_clause {
+        impl #impl_generics #self_ty #where_clause {
             #(#items_type)*
             #(#complete_fns)*
         }
+
+        #locked
+        #name_checks
+        #client
+    };
+    if attr.dump {
+        dump_code(ts);
+    }
+    Ok(ts)
+}
+
+fn build_mcp_tools(
+    attr: TokenStream,
+    item: TokenStream,
+    es: &mut Vec<Error>,
+) -> Result<TokenStream> {
+    let item_impl: ItemImpl = parse2(item)?;
+    let attr: McpToolsAttr = parse2(attr)?;
+    if item_impl.trait_.is_some() {
+        bail!(
+            item_impl.span(),
+            "`#[mcp_tools]` can only be used on `impl T {{ ... }}`"
+        );
+    }
+    let part = attr.name.parse::<Ident>()?;
+    let mut b = McpBuilder::new(
+        RenameRule::from_attr(&attr.rename_all, None)?,
+        Some(part),
+        Vec::new(),
+    );
+    let mut items = Vec::new();
+    for mut item in item_impl.items {
+        match b.push(&mut item) {
+            Ok(ItemPlacement::Type | ItemPlacement::Trait) => items.push(item),
+            Ok(ItemPlacement::Exclude) => {}
+            Err(e) => {
+                items.push(item);
+                es.push(e);
+            }
+        }
+    }
+    if b.is_locked() {
+        bail!(
+            attr.name.span(),
+            "tools in `#[mcp_tools]` cannot take `&mut self`"
+        );
+    }
+    let client = if let Some(client) = &attr.client {
+        let client_ident = client.parse::<Ident>()?;
+        let methods = b.build_client_methods()?;
+        quote! {
+            impl #client_ident {
+                #(#methods)*
+            }
+        }
+    } else {
+        quote!()
+    };
+    let part_items = b.build_part()?;
+    let (impl_generics, _, where_clause) = item_impl.generics.split_for_impl();
+    let self_ty = &item_impl.self_ty;
+    let attrs = &item_impl.attrs;
+    let complete_fns = &b.complete_fns;
+    let ts = quote! {
+        #(#attrs)*
+        impl #impl_generics #self_ty #where_clause {
+            #(#items)*
+            #(#complete_fns)*
+            #part_items
+        }
+
+        #client
     };
     if attr.dump {
         dump_code(ts);
@@ -161,17 +287,39 @@ struct McpBuilder {
     resources: Vec<ResourceEntry>,
     tools: Vec<ToolEntry>,
     complete_fns: Vec<ImplItemFn>,
+    rename_all: Option<RenameRule>,
+    /// Name of the `#[mcp_tools]` block being built
+    part: Option<Ident>,
+    /// Names of the `#[mcp_tools]` blocks included by `#[mcp_server(tools = [..])]`
+    parts: Vec<Ident>,
 }
 
 impl McpBuilder {
-    fn new() -> Self {
+    fn new(rename_all: Option<RenameRule>, part: Option<Ident>, parts: Vec<Ident>) -> Self {
         Self {
             prompts: Vec::new(),
             resources: Vec::new(),
             tools: Vec::new(),
             complete_fns: Vec::new(),
+            rename_all,
+            part,
+            parts,
+        }
+    }
+    /// Name of a generated method, prefixed with the block name in `#[mcp_tools]`
+    fn method_ident(&self, name: &str) -> Ident {
+        match &self.part {
+            Some(part) => part_method_ident(part, name),
+            None => format_ident!("{name}"),
         }
     }
+    /// Names of the method `name` of each included `#[mcp_tools]` block
+    fn part_methods(&self, name: &str) -> Vec<Ident> {
+        self.parts
+            .iter()
+            .map(|part| part_method_ident(part, name))
+            .collect()
+    }
     fn push(&mut self, item: &mut ImplItem) -> Result<ItemPlacement> {
         if let ImplItem::Fn(f) = item {
             let Some(attr) = drain_attr(&mut f.attrs)? else {
@@ -179,12 +327,16 @@ impl McpBuilder {
             };
             match attr {
                 ItemAttr::Prompt(attr) => {
-                    self.prompts.push(PromptEntry::from_impl_item_fn(f, attr)?)
+                    self.prompts
+                        .push(PromptEntry::from_impl_item_fn(f, attr, self.rename_all)?)
                 }
                 ItemAttr::Resource(attr) => self
                     .resources
                     .push(ResourceEntry::from_impl_item_fn(f, attr)?),
-                ItemAttr::Tool(attr) => self.tools.push(ToolEntry::from_impl_item_fn(f, attr)?),
+                ItemAttr::Tool(attr) => {
+                    self.tools
+                        .push(ToolEntry::from_impl_item_fn(f, attr, self.rename_all)?)
+                }
                 ItemAttr::CompleteFn => {
                     // Transform the complete_fn in-place and get inner function
                     let inner_fn = apply_complete_fn_transformation(f)?;
@@ -209,13 +361,19 @@ impl McpBuilder {
         let instructions = build_if(!is_defined(items, "instructions"), || {
             self.build_instructions(impl_doc)
         })?;
-        let prompts = build_if(!self.prompts.is_empty(), || self.build_prompts())?;
-        let resources = build_if(!self.resources.is_empty(), || self.build_resources(items))?;
-        let tools = build_if(!self.tools.is_empty(), || self.build_tools())?;
+        let has_parts = !self.parts.is_empty();
+        let prompts = build_if(!self.prompts.is_empty() || has_parts, || {
+            self.build_prompts()
+        })?;
+        let resources = build_if(!self.resources.is_empty() || has_parts, || {
+            self.build_resources(items)
+        })?;
+        let tools = build_if(!self.tools.is_empty() || has_parts, || self.build_tools())?;
 
         let completion_complete = build_if(!is_defined(items, "completion_complete"), || {
             self.build_completion_complete()
         })?;
+        let into_handler = build_if(self.is_locked(), || self.build_into_handler())?;
         Ok((
             quote! {
                 #capabilities
@@ -224,43 +382,69 @@ impl McpBuilder {
                 #resources
                 #tools
                 #completion_complete
+                #into_handler
             },
             &self.complete_fns,
         ))
     }
     fn build_capabilities(&self, items: &[ImplItem]) -> Result<TokenStream> {
-        let prompts = if !self.prompts.is_empty() || is_defined(items, "prompts_list") {
-            quote!(Some(::mcp_attr::schema::ServerCapabilitiesPrompts {
-                ..::std::default::Default::default()
-            }))
-        } else {
-            quote!(None)
-        };
-        let resources = if !self.resources.is_empty() || is_defined(items, "resources_read") {
-            quote!(Some(::mcp_attr::schema::ServerCapabilitiesResources {
-                ..::std::default::Default::default()
-            }))
-        } else {
-            quote!(None)
-        };
-        let tools = if !self.tools.is_empty() || is_defined(items, "tools_list") {
-            quote!(Some(::mcp_attr::schema::ServerCapabilitiesTools {
-                ..::std::default::Default::default()
-            }))
-        } else {
-            quote!(None)
-        };
+        const PROMPTS: &[&str] = &["prompts_list", "prompts_get"];
+        const RESOURCES: &[&str] = &[
+            "resources_list",
+            "resources_templates_list",
+            "resources_read",
+        ];
+        const TOOLS: &[&str] = &["tools_list", "tools_call"];
+        let prompts = self.build_capability(
+            !self.prompts.is_empty() || is_defined_any(items, PROMPTS),
+            "PROMPTS",
+        );
+        let resources = self.build_capability(
+            !self.resources.is_empty() || is_defined_any(items, RESOURCES),
+            "RESOURCES",
+        );
+        let tools = self.build_capability(
+            !self.tools.is_empty() || is_defined_any(items, TOOLS),
+            "TOOLS",
+        );
+        let completions = self.build_capability(
+            self.has_completions() || is_defined(items, "completion_complete"),
+            "COMPLETIONS",
+        );
         Ok(quote! {
             fn capabilities(&self) -> ::mcp_attr::schema::ServerCapabilities {
                 ::mcp_attr::schema::ServerCapabilities {
                     prompts: #prompts,
                     resources: #resources,
                     tools: #tools,
+                    completions: #completions,
                     ..::std::default::Default::default()
                 }
             }
         })
     }
+    /// Returns whether a prompt or resource of this block has an argument with a completion function
+    fn has_completions(&self) -> bool {
+        self.prompts
+            .iter()
+            .any(|p| !p.get_completion_info().is_empty())
+            || self
+                .resources
+                .iter()
+                .any(|r| !r.get_completion_info().is_empty())
+    }
+    /// Builds a capability that is enabled if `defined` or if an included `#[mcp_tools]` block defines it
+    fn build_capability(&self, defined: bool, kind: &str) -> TokenStream {
+        let value = quote!(Some(::std::default::Default::default()));
+        if defined {
+            return value;
+        }
+        if self.parts.is_empty() {
+            return quote!(None);
+        }
+        let consts = self.parts.iter().map(|part| part_const_ident(part, kind));
+        quote!(if #(Self::#consts)||* { #value } else { None })
+    }
     fn build_prompts(&self) -> Result<TokenStream> {
         let list = self.build_prompts_list()?;
         let get = self.build_prompts_get()?;
@@ -290,26 +474,162 @@ impl McpBuilder {
         })
     }
     fn build_prompts_list(&self) -> Result<TokenStream> {
-        PromptEntry::build_list(&self.prompts)
+        PromptEntry::build_list(
+            &self.prompts,
+            &self.method_ident("prompts_list"),
+            &self.part_methods("prompts_list"),
+        )
     }
     fn build_prompts_get(&self) -> Result<TokenStream> {
-        PromptEntry::build_get(&self.prompts)
+        let finds = self.part_methods("find_prompt");
+        let gets = self.part_methods("prompts_get");
+        let fallback = quote! {
+            #(if Self::#finds(p.name.as_str()) {
+                return self.#gets(p, cx).await;
+            })*
+        };
+        PromptEntry::build_get(&self.prompts, &self.method_ident("prompts_get"), &fallback)
     }
     fn build_resources_list(&self) -> Result<TokenStream> {
-        ResourceEntry::build_list(&self.resources)
+        ResourceEntry::build_list(
+            &self.resources,
+            &self.method_ident("resources_list"),
+            &self.part_methods("resources_list"),
+        )
     }
     fn build_resources_templates_list(&self) -> Result<TokenStream> {
-        ResourceEntry::build_templates_list(&self.resources)
+        ResourceEntry::build_templates_list(
+            &self.resources,
+            &self.method_ident("resources_templates_list"),
+            &self.part_methods("resources_templates_list"),
+        )
     }
     fn build_resources_read(&self) -> Result<TokenStream> {
-        ResourceEntry::build_read(&self.resources)
+        let finds = self.part_methods("find_resource");
+        let reads = self.part_methods("resources_read");
+        let fallback = quote! {
+            #(if Self::#finds(&p.uri) {
+                return self.#reads(p, cx).await;
+            })*
+        };
+        ResourceEntry::build_read(
+            &self.resources,
+            &self.method_ident("resources_read"),
+            &fallback,
+        )
     }
 
     fn build_tools_list(&self) -> Result<TokenStream> {
-        ToolEntry::build_list(&self.tools)
+        let checks = self.build_tool_name_checks(&quote!(Self));
+        let checks = if checks.is_empty() {
+            quote!()
+        } else {
+            quote!(const { #(#checks)* };)
+        };
+        ToolEntry::build_list(
+            &self.tools,
+            &self.method_ident("tools_list"),
+            &self.part_methods("tools_list"),
+            &checks,
+        )
+    }
+    /// Builds the assertions that the tool names of this block and the included `#[mcp_tools]` blocks do not overlap
+    fn build_tool_name_checks(&self, self_ty: &TokenStream) -> Vec<TokenStream> {
+        let parts = self
+            .parts
+            .iter()
+            .map(|part| (part.clone(), part_const_ident(part, "TOOL_NAMES")))
+            .collect::<Vec<_>>();
+        ToolEntry::build_name_checks(&self.tools, &parts, self_ty)
     }
     fn build_tools_call(&self) -> Result<TokenStream> {
-        ToolEntry::build_call(&self.tools)
+        let finds = self.part_methods("find_tool");
+        let calls = self.part_methods("tools_call");
+        let fallback = quote! {
+            #(if Self::#finds(p.name.as_str()).is_some() {
+                return self.#calls(p, cx).await;
+            })*
+        };
+        ToolEntry::build_call(&self.tools, &self.method_ident("tools_call"), &fallback)
+    }
+
+    /// Whether any tool takes `&mut self`
+    fn is_locked(&self) -> bool {
+        self.tools.iter().any(|t| t.is_locked())
+    }
+    fn build_tools_call_locked(&self) -> Result<TokenStream> {
+        let finds = self.part_methods("find_tool");
+        let calls = self.part_methods("tools_call");
+        let fallback = quote! {
+            #(if let ::std::option::Option::Some(read_only) = Self::#finds(p.name.as_str()) {
+                if read_only {
+                    return this.read().await.#calls(p, cx).await;
+                }
+                return this.write().await.#calls(p, cx).await;
+            })*
+        };
+        ToolEntry::build_call_locked(&self.tools, &fallback)
+    }
+    fn build_into_handler(&self) -> Result<TokenStream> {
+        Ok(quote! {
+            fn into_server_handler(
+                self,
+                options: &::mcp_attr::jsoncall::SessionOptions,
+            ) -> ::mcp_attr::helpers::McpServerHandler
+            where
+                Self: Sized + Send + Sync + 'static,
+            {
+                ::mcp_attr::server::McpServer::into_server_handler(
+                    ::mcp_attr::helpers::LockedServer::new(self),
+                    options,
+                )
+            }
+        })
+    }
+
+    fn build_client_methods(&self) -> Result<Vec<TokenStream>> {
+        let mut methods = Vec::new();
+        for p in &self.prompts {
+            methods.push(p.build_client_method()?);
+        }
+        for r in &self.resources {
+            methods.extend(r.build_client_method()?);
+        }
+        for t in &self.tools {
+            methods.push(t.build_client_method()?);
+        }
+        Ok(methods)
+    }
+    fn build_client(&self, client: &LitStr, self_ty: &Type) -> Result<TokenStream> {
+        let client_ident = client.parse::<Ident>()?;
+        let methods = self.build_client_methods()?;
+        let doc = format!(
+            "Typed client for `{}` generated by `#[mcp_server(client = ...)]`",
+            self_ty.to_token_stream().to_string().replace(' ', "")
+        );
+        Ok(quote! {
+            #[doc = #doc]
+            pub struct #client_ident(::mcp_attr::client::McpClient);
+
+            impl #client_ident {
+                /// Wraps a connected [`McpClient`](::mcp_attr::client::McpClient)
+                pub fn new(client: ::mcp_attr::client::McpClient) -> Self {
+                    Self(client)
+                }
+
+                /// Gets the underlying [`McpClient`](::mcp_attr::client::McpClient)
+                pub fn client(&self) -> &::mcp_attr::client::McpClient {
+                    &self.0
+                }
+
+                /// Converts into the underlying [`McpClient`](::mcp_attr::client::McpClient)
+                pub fn into_client(self) -> ::mcp_attr::client::McpClient {
+                    self.0
+                }
+
+                #(#methods)*
+            }
+        })
     }
 
     fn build_instructions(&self, impl_doc: &str) -> Result<TokenStream> {
@@ -347,7 +667,11 @@ impl McpBuilder {
         }
 
         // If no completions are defined, return empty method
-        if prompt_completions.is_empty() && resource_completions.is_empty() {
+        if prompt_completions.is_empty()
+            && resource_completions.is_empty()
+            && self.part.is_none()
+            && self.parts.is_empty()
+        {
             return Ok(quote! {});
         }
 
@@ -385,12 +709,18 @@ impl McpBuilder {
             })
             .collect();
 
+        let fn_ident = self.method_ident("completion_complete");
+        let finds = self.part_methods("find_completion");
+        let completes = self.part_methods("completion_complete");
         Ok(quote! {
-            async fn completion_complete(
+            async fn #fn_ident(
                 &self,
                 p: ::mcp_attr::schema::CompleteRequestParams,
                 cx: &mut ::mcp_attr::server::RequestContext,
             ) -> ::mcp_attr::Result<::mcp_attr::schema::CompleteResult> {
+                #(if Self::#finds(&p) {
+                    return self.#completes(p, cx).await;
+                })*
                 match &p.ref_ {
                     ::mcp_attr::schema::CompleteRequestParamsRef::PromptReference(prompt_ref) => {
                         match (prompt_ref.name.as_str(), p.argument.name.as_str()) {
@@ -409,6 +739,90 @@ impl McpBuilder {
         })
     }
 
+    /// Builds a function that returns whether a completion of this block matches the request
+    fn build_find_completion(&self) -> TokenStream {
+        let fn_ident = self.method_ident("find_completion");
+        let prompts = self
+            .prompts
+            .iter()
+            .flat_map(|p| p.get_completion_info())
+            .map(|(name, arg, _)| quote!((#name, #arg)));
+        let resources = self
+            .resources
+            .iter()
+            .flat_map(|r| r.get_completion_info())
+            .map(|(uri, arg, _)| quote!((#uri, #arg)));
+        quote! {
+            fn #fn_ident(p: &::mcp_attr::schema::CompleteRequestParams) -> bool {
+                match &p.ref_ {
+                    ::mcp_attr::schema::CompleteRequestParamsRef::PromptReference(prompt_ref) => {
+                        [#(#prompts),*].contains(&(prompt_ref.name.as_str(), p.argument.name.as_str()))
+                    }
+                    ::mcp_attr::schema::CompleteRequestParamsRef::ResourceTemplateReference(resource_ref) => {
+                        [#(#resources),*].contains(&(resource_ref.uri.as_str(), p.argument.name.as_str()))
+                    }
+                }
+            }
+        }
+    }
+
+    /// Builds the hidden methods through which `#[mcp_server]` calls the items of a `#[mcp_tools]` block
+    fn build_part(&self) -> Result<TokenStream> {
+        let Some(part) = &self.part else {
+            return Ok(quote!());
+        };
+        let fns = [
+            self.build_prompts_list()?,
+            self.build_prompts_get()?,
+            PromptEntry::build_find(&self.prompts, &self.method_ident("find_prompt")),
+            self.build_resources_list()?,
+            self.build_resources_templates_list()?,
+            self.build_resources_read()?,
+            ResourceEntry::build_find(&self.resources, &self.method_ident("find_resource")),
+            self.build_tools_list()?,
+            self.build_tools_call()?,
+            ToolEntry::build_find(&self.tools, &self.method_ident("find_tool")),
+            self.build_completion_complete()?,
+            self.build_find_completion(),
+        ];
+        let mut items = Vec::new();
+        for f in fns {
+            let mut f: ImplItemFn = parse2(f)?;
+            f.vis = parse_quote!(pub(crate));
+            f.attrs.push(parse_quote!(#[doc(hidden)]));
+            f.attrs.push(parse_quote!(#[allow(dead_code)]));
+            items.push(f);
+        }
+        let prompts = part_const_ident(part, "PROMPTS");
+        let has_prompts = !self.prompts.is_empty();
+        let resources = part_const_ident(part, "RESOURCES");
+        let has_resources = !self.resources.is_empty();
+        let tools = part_const_ident(part, "TOOLS");
+        let has_tools = !self.tools.is_empty();
+        let tool_names_ident = part_const_ident(part, "TOOL_NAMES");
+        let tool_names = self.tools.iter().map(|t| t.name());
+        let completions = part_const_ident(part, "COMPLETIONS");
+        let has_completions = self.has_completions();
+        Ok(quote! {
+            #(#items)*
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            pub(crate) const #prompts: bool = #has_prompts;
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            pub(crate) const #resources: bool = #has_resources;
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            pub(crate) const #tools: bool = #has_tools;
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            pub(crate) const #tool_names_ident: &[&str] = &[#(#tool_names),*];
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            pub(crate) const #completions: bool = #has_completions;
+        })
+    }
+
     fn generate_completion_call(&self, complete_func_expr: &CompleteFuncExpr) -> TokenStream {
         match complete_func_expr {
             CompleteFuncExpr::Expr(expr) => {
@@ -432,6 +846,37 @@ impl McpBuilder {
 #[derive(StructMeta, Default)]
 struct McpAttr {
     dump: bool,
+    client: Option<LitStr>,
+    rename_all: Option<LitStr>,
+    tools: Option<LitStrList>,
+}
+
+#[derive(StructMeta)]
+struct McpToolsAttr {
+    #[struct_meta(unnamed)]
+    name: LitStr,
+    dump: bool,
+    client: Option<LitStr>,
+    rename_all: Option<LitStr>,
+}
+
+/// `["a", "b"]`
+struct LitStrList(Vec<LitStr>);
+
+impl Parse for LitStrList {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        syn::bracketed!(content in input);
+        let items = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+        Ok(Self(items.into_iter().collect()))
+    }
+}
+
+fn part_method_ident(part: &Ident, name: &str) -> Ident {
+    format_ident!("__mcp_{part}_{name}")
+}
+fn part_const_ident(part: &Ident, kind: &str) -> Ident {
+    format_ident!("__MCP_{}_{kind}", part.to_string().to_uppercase())
 }
 
 #[derive(Debug, Clone)]
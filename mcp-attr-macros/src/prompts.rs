@@ -15,8 +15,8 @@ use syn::{
 use uri_template_ex::UriTemplate;
 
 use crate::utils::{
-    arg_name_of, description_expr, expand_option_ty, expr_to_option, get_doc, get_only_attr,
-    is_context, ret_span, take_doc,
+    RenameRule, arg_name_of, client_arg_ident, client_arg_ty, description_expr, expand_option_ty,
+    expr_to_option, get_doc, get_only_attr, is_context, ret_span, take_doc,
 };
 use crate::{
     syn_utils::{get_element, is_path, is_type},
@@ -35,6 +35,7 @@ pub struct PromptAttr {
     name: Option<LitStr>,
     description: Option<Expr>,
     title: Option<Expr>,
+    rename_all: Option<LitStr>,
     pub dump: bool,
 }
 
@@ -49,13 +50,17 @@ pub struct PromptEntry {
     ret_span: Span,
 }
 impl PromptEntry {
-    pub fn from_impl_item_fn(f: &mut ImplItemFn, attr: PromptAttr) -> Result<Self> {
+    pub fn from_impl_item_fn(
+        f: &mut ImplItemFn,
+        attr: PromptAttr,
+        rename_all: Option<RenameRule>,
+    ) -> Result<Self> {
         let f_span = f.span();
-        Self::new(&f.vis, &mut f.sig, &f.attrs, f_span, attr)
+        Self::new(&f.vis, &mut f.sig, &f.attrs, f_span, attr, rename_all)
     }
     pub fn from_item_fn(f: &mut ItemFn, attr: PromptAttr) -> Result<Self> {
         let f_span = f.span();
-        Self::new(&f.vis, &mut f.sig, &f.attrs, f_span, attr)
+        Self::new(&f.vis, &mut f.sig, &f.attrs, f_span, attr, None)
     }
     fn new(
         vis: &Visibility,
@@ -63,7 +68,9 @@ impl PromptEntry {
         attrs: &[Attribute],
         f_span: Span,
         attr: PromptAttr,
+        rename_all: Option<RenameRule>,
     ) -> Result<Self> {
+        let rename_all = RenameRule::from_attr(&attr.rename_all, rename_all)?;
         let name = attr
             .name
             .map(|n| n.value())
@@ -76,7 +83,8 @@ impl PromptEntry {
         let args = sig
             .inputs
             .iter_mut()
-            .map(PromptFnArg::new)
+            .enumerate()
+            .map(|(index, f)| PromptFnArg::new(f, index, rename_all))
             .collect::<Result<Vec<_>>>()?;
         let fn_ident = sig.ident.clone();
 
@@ -91,20 +99,39 @@ impl PromptEntry {
             ret_span: ret_span(sig, f_span),
         })
     }
-    pub fn build_list(items: &[Self]) -> Result<TokenStream> {
+    pub fn build_list(items: &[Self], fn_ident: &Ident, part_fns: &[Ident]) -> Result<TokenStream> {
         let prompts = items
             .iter()
             .map(|p| p.build_metadata())
             .collect::<Result<Vec<_>>>()?;
+        let mutability = if part_fns.is_empty() {
+            quote!()
+        } else {
+            quote!(mut)
+        };
+        let body = quote! {
+            let #mutability prompts: ::std::vec::Vec<::mcp_attr::schema::Prompt> = vec![#(#prompts,)*];
+            #(prompts.extend(self.#part_fns(::std::clone::Clone::clone(&p), cx).await?.prompts);)*
+            Ok(prompts.into())
+        };
         Ok(quote! {
-            async fn prompts_list(&self,
+            async fn #fn_ident(&self,
                 p: ::mcp_attr::schema::ListPromptsRequestParams,
                 cx: &mut ::mcp_attr::server::RequestContext)
                 -> ::mcp_attr::Result<::mcp_attr::schema::ListPromptsResult> {
-                    Ok(vec![#(#prompts,)*].into())
+                    #body
             }
         })
     }
+    /// Builds a function that returns whether the prompt `name` is defined
+    pub fn build_find(items: &[Self], fn_ident: &Ident) -> TokenStream {
+        let names = items.iter().map(|p| &p.name);
+        quote! {
+            fn #fn_ident(name: &str) -> bool {
+                [#(#names),*].contains(&name)
+            }
+        }
+    }
     fn build_metadata(&self) -> Result<TokenStream> {
         let name = &self.name;
         let description = if let Some(attr_desc) = &self.attr_description {
@@ -128,19 +155,26 @@ impl PromptEntry {
             }
         })
     }
-    pub fn build_get(items: &[Self]) -> Result<TokenStream> {
+    pub fn build_get(
+        items: &[Self],
+        fn_ident: &Ident,
+        fallback: &TokenStream,
+    ) -> Result<TokenStream> {
         let arms = items
             .iter()
             .map(|p| p.build_get_arms())
             .collect::<Result<Vec<_>>>()?;
         Ok(quote! {
-            async fn prompts_get(&self,
+            async fn #fn_ident(&self,
                 p: ::mcp_attr::schema::GetPromptRequestParams,
                 cx: &mut ::mcp_attr::server::RequestContext)
                 -> ::mcp_attr::Result<::mcp_attr::schema::GetPromptResult> {
                     match p.name.as_str() {
                         #(#arms)*
-                        _ => return ::std::result::Result::Err(::mcp_attr::server::errors::prompt_not_found(&p.name)),
+                        _ => {
+                            #fallback
+                            return ::std::result::Result::Err(::mcp_attr::server::errors::prompt_not_found(&p.name));
+                        }
                     }
                 }
         })
@@ -158,7 +192,7 @@ impl PromptEntry {
             #name => {
                 #[allow(clippy::useless_conversion)]
                 {
-                    return Ok(<::mcp_attr::schema::GetPromptResult as ::std::convert::From<_>>::from(Self::#fn_ident(#(#args,)*).await?));
+                    return Ok(<::mcp_attr::schema::GetPromptResult as ::std::convert::From<_>>::from(Self::#fn_ident(#(#args,)*).await.map_err(::mcp_attr::server::IntoMcpError::into_mcp_error)?));
                 }
             }
         })
@@ -225,7 +259,7 @@ impl PromptEntry {
                     |p: &::mcp_attr::schema::GetPromptRequestParams, cx: &::mcp_attr::server::RequestContext| {
                         Box::pin(async move {
                             Ok(::mcp_attr::schema::GetPromptResult::from(
-                                #fn_ident(#(#args,)*).await?,
+                                #fn_ident(#(#args,)*).await.map_err(::mcp_attr::server::IntoMcpError::into_mcp_error)?,
                             ))
                         })
                     }
@@ -234,6 +268,39 @@ impl PromptEntry {
         })
     }
 
+    pub fn build_client_method(&self) -> Result<TokenStream> {
+        let fn_ident = &self.fn_ident;
+        let name = &self.name;
+        let doc = &self.description;
+        let mut params = Vec::new();
+        let mut stmts = Vec::new();
+        for arg in &self.args {
+            if let PromptFnArg::Property(arg) = arg {
+                params.push(arg.build_client_param());
+                stmts.push(arg.build_client_stmt());
+            }
+        }
+        let mutability = if stmts.is_empty() {
+            quote!()
+        } else {
+            quote!(mut)
+        };
+        Ok(quote! {
+            #[doc = #doc]
+            pub async fn #fn_ident(&self, #(#params,)*)
+                -> ::mcp_attr::SessionResult<::mcp_attr::schema::GetPromptResult> {
+                let #mutability arguments = ::std::collections::BTreeMap::new();
+                #(#stmts)*
+                self.0
+                    .prompts_get(::mcp_attr::schema::GetPromptRequestParams {
+                        name: #name.into(),
+                        arguments,
+                    })
+                    .await
+            }
+        })
+    }
+
     pub fn get_completion_info(&self) -> Vec<(String, String, crate::CompleteFuncExpr)> {
         let mut completions = Vec::new();
         for arg in &self.args {
@@ -259,7 +326,7 @@ enum PromptFnArg {
     Receiver(Span),
 }
 impl PromptFnArg {
-    fn new(f: &mut FnArg) -> Result<Self> {
+    fn new(f: &mut FnArg, index: usize, rename_all: Option<RenameRule>) -> Result<Self> {
         let span = f.span();
         let typed_arg = match f {
             FnArg::Typed(pat_type) => pat_type,
@@ -278,11 +345,16 @@ impl PromptFnArg {
         let name = if let Some(name) = &arg_attr.name {
             name.value()
         } else {
-            arg_name_of(typed_arg)?
+            let name = arg_name_of(typed_arg)?;
+            match rename_all {
+                Some(rule) => rule.apply(&name),
+                None => name,
+            }
         };
         let (ty, required) = expand_option_ty(&typed_arg.ty);
         Ok(Self::Property(PromptArg {
             name,
+            client_ident: client_arg_ident(typed_arg, index),
             ty,
             description,
             required,
@@ -308,6 +380,7 @@ impl PromptFnArg {
 #[derive(Debug)]
 struct PromptArg {
     name: String,
+    client_ident: Ident,
     ty: Type,
     description: String,
     required: bool,
@@ -343,3 +416,30 @@ impl PromptArg {
         }
     }
 }
+
+impl PromptArg {
+    fn build_client_param(&self) -> TokenStream {
+        let ident = &self.client_ident;
+        let ty = client_arg_ty(&self.ty);
+        if self.required {
+            quote!(#ident: #ty)
+        } else {
+            quote!(#ident: ::std::option::Option<#ty>)
+        }
+    }
+    fn build_client_stmt(&self) -> TokenStream {
+        let ident = &self.client_ident;
+        let name = &self.name;
+        if self.required {
+            quote! {
+                arguments.insert(#name.to_string(), ::std::string::ToString::to_string(&#ident));
+            }
+        } else {
+            quote! {
+                if let ::std::option::Option::Some(value) = #ident {
+                    arguments.insert(#name.to_string(), ::std::string::ToString::to_string(&value));
+                }
+            }
+        }
+    }
+}
@@ -15,8 +15,8 @@ use syn::{
 use uri_template_ex::UriTemplate;
 
 use crate::utils::{
-    description_expr, expand_option_ty, expr_to_option, get_doc, get_only_attr, is_context,
-    opt_expr, ret_span, take_doc,
+    client_arg_ident, client_arg_ty, description_expr, expand_option_ty, expr_to_option, get_doc,
+    get_only_attr, is_context, opt_expr, ret_span, take_doc,
 };
 use crate::{
     syn_utils::{get_element, is_path, is_type},
@@ -86,7 +86,8 @@ impl ResourceEntry {
         let args = sig
             .inputs
             .iter_mut()
-            .map(|f| ResourceFnArg::new(f, &uri))
+            .enumerate()
+            .map(|(index, f)| ResourceFnArg::new(f, &uri, index))
             .collect::<Result<Vec<_>>>()?;
         let fn_ident = sig.ident.clone();
         Ok(Self {
@@ -102,17 +103,27 @@ impl ResourceEntry {
             ret_span: ret_span(sig, f_span),
         })
     }
-    pub fn build_list(items: &[Self]) -> Result<TokenStream> {
+    pub fn build_list(items: &[Self], fn_ident: &Ident, part_fns: &[Ident]) -> Result<TokenStream> {
         let arms = items
             .iter()
             .filter_map(|r| r.build_list_arm().transpose())
             .collect::<Result<Vec<TokenStream>>>()?;
+        let mutability = if part_fns.is_empty() {
+            quote!()
+        } else {
+            quote!(mut)
+        };
+        let body = quote! {
+            let #mutability resources: ::std::vec::Vec<::mcp_attr::schema::Resource> = vec![#(#arms,)*];
+            #(resources.extend(self.#part_fns(::std::clone::Clone::clone(&p), cx).await?.resources);)*
+            Ok(resources.into())
+        };
         Ok(quote! {
-            async fn resources_list(&self,
+            async fn #fn_ident(&self,
                 p: ::mcp_attr::schema::ListResourcesRequestParams,
                 cx: &mut ::mcp_attr::server::RequestContext)
                 -> ::mcp_attr::Result<::mcp_attr::schema::ListResourcesResult> {
-                    Ok(vec![#(#arms,)*].into())
+                    #body
             }
         })
     }
@@ -147,17 +158,31 @@ impl ResourceEntry {
         }))
     }
 
-    pub fn build_templates_list(items: &[Self]) -> Result<TokenStream> {
+    pub fn build_templates_list(
+        items: &[Self],
+        fn_ident: &Ident,
+        part_fns: &[Ident],
+    ) -> Result<TokenStream> {
         let arms = items
             .iter()
             .filter_map(|r| r.build_templates_list_arm().transpose())
             .collect::<Result<Vec<TokenStream>>>()?;
+        let mutability = if part_fns.is_empty() {
+            quote!()
+        } else {
+            quote!(mut)
+        };
+        let body = quote! {
+            let #mutability templates: ::std::vec::Vec<::mcp_attr::schema::ResourceTemplate> = vec![#(#arms,)*];
+            #(templates.extend(self.#part_fns(::std::clone::Clone::clone(&p), cx).await?.resource_templates);)*
+            Ok(templates.into())
+        };
         Ok(quote! {
-            async fn resources_templates_list(&self,
+            async fn #fn_ident(&self,
                 p: ::mcp_attr::schema::ListResourceTemplatesRequestParams,
                 cx: &mut ::mcp_attr::server::RequestContext)
                 -> ::mcp_attr::Result<::mcp_attr::schema::ListResourceTemplatesResult> {
-                    Ok(vec![#(#arms,)*].into())
+                    #body
             }
         })
     }
@@ -191,22 +216,53 @@ impl ResourceEntry {
         }))
     }
 
-    pub fn build_read(items: &[Self]) -> Result<TokenStream> {
+    pub fn build_read(
+        items: &[Self],
+        fn_ident: &Ident,
+        fallback: &TokenStream,
+    ) -> Result<TokenStream> {
         let stmts = items
             .iter()
             .map(|r| r.build_read_stmt())
             .collect::<Result<Vec<_>>>()?;
         Ok(quote! {
             #[allow(unreachable_code)]
-            async fn resources_read(&self,
+            async fn #fn_ident(&self,
                 p: ::mcp_attr::schema::ReadResourceRequestParams,
                 cx: &mut ::mcp_attr::server::RequestContext)
                 -> ::mcp_attr::Result<::mcp_attr::schema::ReadResourceResult> {
                     #(#stmts)*
-                    ::mcp_attr::bail_public!(::mcp_attr::ErrorCode::INVALID_PARAMS, "resource `{}` not found", p.uri);
+                    #fallback
+                    return ::std::result::Result::Err(::mcp_attr::server::errors::resource_not_found(&p.uri));
                 }
         })
     }
+    /// Builds a function that returns whether a resource matches `uri`
+    pub fn build_find(items: &[Self], fn_ident: &Ident) -> TokenStream {
+        let checks = items.iter().map(|r| {
+            if let Some(uri) = &r.uri {
+                let uri = uri.to_string();
+                quote! {
+                    {
+                        static URI_TEMPLATE : ::std::sync::LazyLock<::mcp_attr::helpers::uri_template_ex::UriTemplate> =
+                            ::std::sync::LazyLock::new(|| ::mcp_attr::helpers::uri_template_ex::UriTemplate::new(#uri).unwrap());
+                        if URI_TEMPLATE.captures(uri).is_some() {
+                            return true;
+                        }
+                    }
+                }
+            } else {
+                quote!(return true;)
+            }
+        });
+        quote! {
+            #[allow(unreachable_code)]
+            fn #fn_ident(uri: &str) -> bool {
+                #(#checks)*
+                false
+            }
+        }
+    }
 
     pub fn build_route(&self) -> Result<TokenStream> {
         let fn_ident = &self.fn_ident;
@@ -310,7 +366,7 @@ impl ResourceEntry {
                         cx: &::mcp_attr::server::RequestContext | {
                         Box::pin(async move {
                             Ok(::mcp_attr::schema::ReadResourceResult::from(
-                                #fn_ident(#(#args,)*).await?,
+                                #fn_ident(#(#args,)*).await.map_err(::mcp_attr::server::IntoMcpError::into_mcp_error)?,
                             ))
                         })
                     }
@@ -348,7 +404,7 @@ impl ResourceEntry {
                         let _captures = &_captures;
                         #[allow(clippy::useless_conversion)]
                         {
-                            return Ok(<::mcp_attr::schema::ReadResourceResult as ::std::convert::From<_>>::from(Self::#fn_ident(#(#args,)*).await?));
+                            return Ok(<::mcp_attr::schema::ReadResourceResult as ::std::convert::From<_>>::from(Self::#fn_ident(#(#args,)*).await.map_err(::mcp_attr::server::IntoMcpError::into_mcp_error)?));
                         }
                     }
                 }
@@ -357,12 +413,44 @@ impl ResourceEntry {
             Ok(Some(quote_spanned! {ret_span=>
                 #[allow(clippy::useless_conversion)]
                 {
-                    return Ok(<::mcp_attr::schema::ReadResourceResult as ::std::convert::From<_>>::from(Self::#fn_ident(#(#args,)*).await?));
+                    return Ok(<::mcp_attr::schema::ReadResourceResult as ::std::convert::From<_>>::from(Self::#fn_ident(#(#args,)*).await.map_err(::mcp_attr::server::IntoMcpError::into_mcp_error)?));
                 }
             }))
         }
     }
 
+    pub fn build_client_method(&self) -> Result<Option<TokenStream>> {
+        let Some(uri) = &self.uri else {
+            return Ok(None);
+        };
+        let uri = uri.to_string();
+        let fn_ident = &self.fn_ident;
+        let doc = &self.description;
+        let mut params = Vec::new();
+        let mut vars = Vec::new();
+        for arg in &self.args {
+            if let ResourceFnArg::Var(var) = arg {
+                params.push(var.build_client_param());
+                vars.push(var.build_client_var());
+            }
+        }
+        Ok(Some(quote! {
+            #[doc = #doc]
+            pub async fn #fn_ident(&self, #(#params,)*)
+                -> ::mcp_attr::SessionResult<::mcp_attr::schema::ReadResourceResult> {
+                static URI_TEMPLATE : ::std::sync::LazyLock<::mcp_attr::helpers::uri_template_ex::UriTemplate> =
+                    ::std::sync::LazyLock::new(|| ::mcp_attr::helpers::uri_template_ex::UriTemplate::new(#uri).unwrap());
+                #[allow(unused_mut)]
+                let mut vars = ::std::collections::BTreeMap::<&str, ::std::string::String>::new();
+                #(#vars)*
+                let uri = URI_TEMPLATE.expand(&vars);
+                self.0
+                    .resources_read(::mcp_attr::schema::ReadResourceRequestParams::new(&uri))
+                    .await
+            }
+        }))
+    }
+
     pub fn get_completion_info(&self) -> Vec<(String, String, crate::CompleteFuncExpr)> {
         let mut completions = Vec::new();
         if let Some(uri) = &self.uri {
@@ -392,7 +480,7 @@ enum ResourceFnArg {
 }
 
 impl ResourceFnArg {
-    fn new(f: &mut FnArg, uri: &Option<UriTemplate>) -> Result<Self> {
+    fn new(f: &mut FnArg, uri: &Option<UriTemplate>, index: usize) -> Result<Self> {
         let span = f.span();
         let typed_arg = match f {
             FnArg::Typed(pat_type) => pat_type,
@@ -407,11 +495,12 @@ impl ResourceFnArg {
                 "complete",
             )?;
             let name = arg_name_of(typed_arg)?;
-            if let Some(index) = uri.find_var_name(&name) {
+            if let Some(var_index) = uri.find_var_name(&name) {
                 let (ty, required) = expand_option_ty(&typed_arg.ty);
                 Ok(Self::Var(UriVar {
+                    client_ident: client_arg_ident(typed_arg, index),
                     name,
-                    index,
+                    index: var_index,
                     ty,
                     required,
                     span,
@@ -438,6 +527,7 @@ impl ResourceFnArg {
 
 struct UriVar {
     name: String,
+    client_ident: Ident,
     index: usize,
     ty: Type,
     required: bool,
@@ -457,3 +547,27 @@ impl UriVar {
         })
     }
 }
+impl UriVar {
+    fn build_client_param(&self) -> TokenStream {
+        let ident = &self.client_ident;
+        let ty = client_arg_ty(&self.ty);
+        if self.required {
+            quote!(#ident: #ty)
+        } else {
+            quote!(#ident: ::std::option::Option<#ty>)
+        }
+    }
+    fn build_client_var(&self) -> TokenStream {
+        let ident = &self.client_ident;
+        let name = &self.name;
+        if self.required {
+            quote!(vars.insert(#name, ::std::string::ToString::to_string(&#ident));)
+        } else {
+            quote! {
+                if let ::std::option::Option::Some(value) = #ident {
+                    vars.insert(#name, ::std::string::ToString::to_string(&value));
+                }
+            }
+        }
+    }
+}
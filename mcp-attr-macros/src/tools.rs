@@ -17,8 +17,9 @@ use uri_template_ex::UriTemplate;
 use crate::{
     route_ident,
     utils::{
-        arg_name_of, description_expr, expand_option_ty, expr_to_option, get_doc, get_only_attr,
-        is_context, ret_span, take_doc,
+        RenameRule, arg_name_of, client_arg_ident, client_arg_ty, description_expr,
+        expand_option_ty, expr_to_option, get_doc, get_only_attr, is_context, opt_expr,
+        result_ok_ty, ret_span, take_doc,
     },
 };
 use crate::{
@@ -30,6 +31,50 @@ use crate::{
 pub struct ToolArgAttr {
     #[struct_meta(unnamed)]
     name: Option<LitStr>,
+    flatten: bool,
+    secret: bool,
+    default: Option<Expr>,
+    example: Option<Expr>,
+    min: Option<Expr>,
+    max: Option<Expr>,
+    min_length: Option<Expr>,
+    max_length: Option<Expr>,
+    pattern: Option<LitStr>,
+}
+impl ToolArgAttr {
+    fn constraints(&self) -> Option<TokenStream> {
+        if self.min.is_none()
+            && self.max.is_none()
+            && self.min_length.is_none()
+            && self.max_length.is_none()
+            && self.pattern.is_none()
+        {
+            return None;
+        }
+        let minimum = opt_expr(
+            &self.min,
+            |e| quote!(::mcp_attr::helpers::arg_constraint_value(#e)),
+        );
+        let maximum = opt_expr(
+            &self.max,
+            |e| quote!(::mcp_attr::helpers::arg_constraint_value(#e)),
+        );
+        let min_length = opt_expr(&self.min_length, |e| quote!(#e));
+        let max_length = opt_expr(&self.max_length, |e| quote!(#e));
+        let pattern = opt_expr(
+            &self.pattern,
+            |e| quote!(::std::string::ToString::to_string(#e)),
+        );
+        Some(quote! {
+            ::mcp_attr::server::ArgConstraints {
+                minimum: #minimum,
+                maximum: #maximum,
+                min_length: #min_length,
+                max_length: #max_length,
+                pattern: #pattern,
+            }
+        })
+    }
 }
 
 #[derive(StructMeta, Default)]
@@ -38,6 +83,7 @@ pub struct ToolAttr {
     name: Option<LitStr>,
     description: Option<Expr>,
     title: Option<Expr>,
+    rename_all: Option<LitStr>,
     pub dump: bool,
     // MCP spec: destructive -> non_destructive (inverted)
     non_destructive: bool,
@@ -47,6 +93,10 @@ pub struct ToolAttr {
     closed_world: bool,
     // MCP spec: read_only -> read_only (same)
     read_only: bool,
+    strict: bool,
+    lenient: bool,
+    blocking: bool,
+    enabled: Option<Path>,
 }
 
 pub struct ToolEntry {
@@ -58,7 +108,25 @@ pub struct ToolEntry {
     attr_title: Option<Expr>,
     args: Vec<ToolFnArg>,
     ret_span: Span,
+    ret_ty: Option<Type>,
     tool_annotations: Option<ToolAnnotationsData>,
+    strict: bool,
+    lenient: bool,
+    is_async: bool,
+    blocking: bool,
+    read_only: bool,
+    mut_receiver: bool,
+    enabled: Option<Path>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CallMode {
+    /// `#[tool]` on a free function
+    Route,
+    /// `#[tool]` on a method called with `&self`
+    Method,
+    /// `#[tool]` on a method called through the lock of `LockedServer`
+    Locked,
 }
 
 #[derive(Clone)]
@@ -69,13 +137,17 @@ struct ToolAnnotationsData {
     read_only_hint: Option<bool>,
 }
 impl ToolEntry {
-    pub fn from_impl_item_fn(f: &mut ImplItemFn, attr: ToolAttr) -> Result<Self> {
+    pub fn from_impl_item_fn(
+        f: &mut ImplItemFn,
+        attr: ToolAttr,
+        rename_all: Option<RenameRule>,
+    ) -> Result<Self> {
         let f_span = f.span();
-        Self::new(&f.vis, &mut f.sig, &f.attrs, f_span, attr)
+        Self::new(&f.vis, &mut f.sig, &f.attrs, f_span, attr, rename_all)
     }
     pub fn from_item_fn(f: &mut ItemFn, attr: ToolAttr) -> Result<Self> {
         let f_span = f.span();
-        Self::new(&f.vis, &mut f.sig, &f.attrs, f_span, attr)
+        Self::new(&f.vis, &mut f.sig, &f.attrs, f_span, attr, None)
     }
     fn new(
         vis: &Visibility,
@@ -83,7 +155,9 @@ impl ToolEntry {
         attrs: &[Attribute],
         f_span: Span,
         attr: ToolAttr,
+        rename_all: Option<RenameRule>,
     ) -> Result<Self> {
+        let rename_all = RenameRule::from_attr(&attr.rename_all, rename_all)?;
         let tool_annotations = build_tool_annotations(&attr)?;
         let name = attr
             .name
@@ -98,8 +172,30 @@ impl ToolEntry {
         let args = sig
             .inputs
             .iter_mut()
-            .map(ToolFnArg::new)
+            .enumerate()
+            .map(|(index, f)| ToolFnArg::new(f, index, rename_all))
             .collect::<Result<Vec<_>>>()?;
+        check_flatten(&args)?;
+        let is_async = sig.asyncness.is_some();
+        let mut_receiver = sig
+            .receiver()
+            .is_some_and(|r| r.reference.is_some() && r.mutability.is_some());
+        if attr.blocking {
+            if let Some(asyncness) = &sig.asyncness {
+                bail!(asyncness.span, "`blocking` cannot be used with `async fn`");
+            }
+            if let Some(ToolFnArg::Context(span)) =
+                args.iter().find(|a| matches!(a, ToolFnArg::Context(..)))
+            {
+                bail!(*span, "`blocking` tools cannot take `RequestContext`");
+            }
+        }
+        if attr.read_only && mut_receiver {
+            bail!(
+                sig.inputs.span(),
+                "`read_only` tools cannot take `&mut self`"
+            );
+        }
         Ok(Self {
             vis: vis.clone(),
             name,
@@ -109,23 +205,123 @@ impl ToolEntry {
             attr_title: attr.title,
             args,
             ret_span: ret_span(sig, f_span),
+            ret_ty: result_ok_ty(sig),
             tool_annotations,
+            strict: attr.strict,
+            lenient: attr.lenient,
+            is_async,
+            blocking: attr.blocking,
+            read_only: attr.read_only,
+            mut_receiver,
+            enabled: attr.enabled,
         })
     }
-    pub fn build_list(items: &[Self]) -> Result<TokenStream> {
-        let items = items
-            .iter()
-            .map(|t| t.build_metadata())
-            .collect::<Result<Vec<_>>>()?;
+    /// Whether the tool needs `&mut self` and must be called through `LockedServer`
+    pub fn is_locked(&self) -> bool {
+        self.mut_receiver
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn build_list(
+        items: &[Self],
+        fn_ident: &Ident,
+        part_fns: &[Ident],
+        checks: &TokenStream,
+    ) -> Result<TokenStream> {
+        let settings = if items.is_empty() {
+            quote!()
+        } else {
+            quote! {
+                let settings = &::mcp_attr::server::McpServer::schema_settings(self);
+            }
+        };
+        let mut pushes = Vec::new();
+        for t in items {
+            let metadata = t.build_metadata()?;
+            pushes.push(if let Some(enabled) = &t.enabled {
+                quote! {
+                    if #enabled(cx) {
+                        tools.push(#metadata);
+                    }
+                }
+            } else {
+                quote!(tools.push(#metadata);)
+            });
+        }
+        let mutability = if part_fns.is_empty() && pushes.is_empty() {
+            quote!()
+        } else {
+            quote!(mut)
+        };
+        let body = quote! {
+            let #mutability tools: ::std::vec::Vec<::mcp_attr::schema::Tool> = ::std::vec::Vec::new();
+            #(#pushes)*
+            #(tools.extend(self.#part_fns(::std::clone::Clone::clone(&p), cx).await?.tools);)*
+            Ok(tools.into())
+        };
         Ok(quote! {
-            async fn tools_list(&self,
+            async fn #fn_ident(&self,
                 p: ::mcp_attr::schema::ListToolsRequestParams,
                 cx: &mut ::mcp_attr::server::RequestContext)
                 -> ::mcp_attr::Result<::mcp_attr::schema::ListToolsResult> {
-                    Ok(vec![#(#items,)*].into())
+                    #checks
+                    #settings
+                    #body
             }
         })
     }
+    /// Builds the assertions that the tools of this block and of the included `#[mcp_tools]` blocks have unique names
+    ///
+    /// `parts` are the included `#[mcp_tools]` blocks and the constants of `self_ty` holding their tool names.
+    pub fn build_name_checks(
+        items: &[Self],
+        parts: &[(Ident, Ident)],
+        self_ty: &TokenStream,
+    ) -> Vec<TokenStream> {
+        let mut checks = Vec::new();
+        for (i, (part, names)) in parts.iter().enumerate() {
+            for t in items {
+                let name = &t.name;
+                let message =
+                    format!("tool `{name}` is also defined in `#[mcp_tools(\"{part}\")]`");
+                checks.push(quote_spanned! {t.fn_ident.span()=>
+                    ::std::assert!(
+                        !::mcp_attr::helpers::contains_tool_name(<#self_ty>::#names, #name),
+                        #message
+                    );
+                });
+            }
+            for (other, other_names) in &parts[..i] {
+                let message = format!(
+                    "`#[mcp_tools(\"{other}\")]` and `#[mcp_tools(\"{part}\")]` define tools with the same name"
+                );
+                checks.push(quote_spanned! {part.span()=>
+                    ::std::assert!(
+                        !::mcp_attr::helpers::has_common_tool_name(
+                            <#self_ty>::#other_names,
+                            <#self_ty>::#names,
+                        ),
+                        #message
+                    );
+                });
+            }
+        }
+        checks
+    }
+    /// Builds a function that returns whether the tool `name` is `read_only`, or `None` if it is not defined
+    pub fn build_find(items: &[Self], fn_ident: &Ident) -> TokenStream {
+        let names = items.iter().map(|t| &t.name);
+        let read_only = items.iter().map(|t| t.read_only);
+        quote! {
+            fn #fn_ident(name: &str) -> ::std::option::Option<bool> {
+                match name {
+                    #(#names => ::std::option::Option::Some(#read_only),)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+        }
+    }
     fn build_metadata(&self) -> Result<TokenStream> {
         let name = &self.name;
         let description = if let Some(attr_desc) = &self.attr_description {
@@ -161,11 +357,11 @@ impl ToolEntry {
 
         Ok(quote! {
             {
-                let mut input_schema = ::mcp_attr::schema::ToolInputSchema::new();
+                let mut input_schema = ::mcp_attr::server::ToolInputSchemaBuilder::new(settings);
                 #(#args)*
                 ::mcp_attr::schema::Tool {
                     name: #name.into(),
-                    input_schema,
+                    input_schema: input_schema.build()?,
                     description: #description,
                     annotations: #annotations,
                     meta: Default::default(),
@@ -175,66 +371,311 @@ impl ToolEntry {
             }
         })
     }
-    pub fn build_call(items: &[Self]) -> Result<TokenStream> {
+    pub fn build_call(
+        items: &[Self],
+        fn_ident: &Ident,
+        fallback: &TokenStream,
+    ) -> Result<TokenStream> {
         let arms = items
             .iter()
-            .map(|t| t.build_call_arms())
+            .map(|t| t.build_call_arms(CallMode::Method))
             .collect::<Result<Vec<_>>>()?;
         Ok(quote! {
-            async fn tools_call(&self,
+            async fn #fn_ident(&self,
                 p: ::mcp_attr::schema::CallToolRequestParams,
                 cx: &mut ::mcp_attr::server::RequestContext)
                 -> ::mcp_attr::Result<::mcp_attr::schema::CallToolResult> {
                     match p.name.as_str() {
                         #(#arms)*
-                        _ => return ::std::result::Result::Err(::mcp_attr::server::errors::tool_not_found(&p.name)),
+                        _ => {
+                            #fallback
+                            return ::std::result::Result::Err(::mcp_attr::server::errors::tool_not_found(&p.name));
+                        }
                     }
             }
         })
     }
-    fn build_call_arms(&self) -> Result<TokenStream> {
+    pub fn build_call_locked(items: &[Self], fallback: &TokenStream) -> Result<TokenStream> {
+        let arms = items
+            .iter()
+            .map(|t| t.build_call_arms(CallMode::Locked))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(quote! {
+            async fn tools_call_locked(
+                this: &::std::sync::Arc<::mcp_attr::helpers::RwLock<Self>>,
+                p: ::mcp_attr::schema::CallToolRequestParams,
+                cx: &mut ::mcp_attr::server::RequestContext)
+                -> ::mcp_attr::Result<::mcp_attr::schema::CallToolResult> {
+                    match p.name.as_str() {
+                        #(#arms)*
+                        _ => {
+                            #fallback
+                            return ::std::result::Result::Err(::mcp_attr::server::errors::tool_not_found(&p.name));
+                        }
+                    }
+            }
+        })
+    }
+    fn build_call_arms(&self, mode: CallMode) -> Result<TokenStream> {
         let name = &self.name;
+        if mode == CallMode::Method && self.mut_receiver {
+            return Ok(quote! {
+                #name => return ::std::result::Result::Err(::mcp_attr::helpers::tool_requires_lock(#name)),
+            });
+        }
+        let receiver = match mode {
+            CallMode::Locked if self.mut_receiver => quote!(&mut *this),
+            CallMode::Locked => quote!(&*this),
+            CallMode::Method | CallMode::Route => quote!(self),
+        };
         let args = self
             .args
             .iter()
-            .map(|a| a.build_call())
+            .map(|a| a.build_call(&quote!(), self.lenient, &receiver))
             .collect::<Result<Vec<_>>>()?;
         let fn_ident = &self.fn_ident;
         let ret_span = self.ret_span;
+        let deny_unknown_args = self.build_deny_unknown_args(&quote!());
+        let check_enabled = self.build_check_enabled();
+        let schema_settings = self.build_schema_settings();
+        let lock = if mode == CallMode::Locked {
+            self.build_lock()
+        } else {
+            quote!()
+        };
+        let invoke = self.build_invoke(&quote!(Self::#fn_ident), &args, mode);
         Ok(quote_spanned! {ret_span=>
             #name => {
+                #check_enabled
+                #schema_settings
+                #deny_unknown_args
+                #lock
                 #[allow(clippy::useless_conversion)]
                 {
-                    return Ok(<::mcp_attr::schema::CallToolResult as ::std::convert::From<_>>::from(Self::#fn_ident(#(#args,)*).await?));
+                    return match #invoke {
+                        Ok(value) => Ok(<::mcp_attr::schema::CallToolResult as ::std::convert::From<_>>::from(value)),
+                        Err(e) => ::mcp_attr::helpers::IntoToolError::into_tool_error(e).into_result(cx),
+                    };
                 }
             }
         })
     }
+    fn build_lock(&self) -> TokenStream {
+        let guard = match (self.read_only, self.blocking) {
+            (true, false) => quote!(this.read().await),
+            (false, false) => quote!(this.write().await),
+            (true, true) => quote!(::std::sync::Arc::clone(this).read_owned().await),
+            (false, true) => quote!(::std::sync::Arc::clone(this).write_owned().await),
+        };
+        let mutability = if self.mut_receiver {
+            quote!(mut)
+        } else {
+            quote!()
+        };
+        quote!(let #mutability this = #guard;)
+    }
+    /// Builds the expression that calls the tool function and evaluates to its return value
+    fn build_invoke(&self, f: &TokenStream, args: &[TokenStream], mode: CallMode) -> TokenStream {
+        let ret_span = self.ret_span;
+        if !self.blocking {
+            return if self.is_async {
+                quote_spanned!(ret_span=> #f(#(#args,)*).await)
+            } else {
+                quote_spanned!(ret_span=> #f(#(#args,)*))
+            };
+        }
+        // Arguments are parsed before moving to the blocking thread.
+        let mut bindings = Vec::new();
+        let mut params = Vec::new();
+        for (index, (arg, expr)) in self.args.iter().zip(args).enumerate() {
+            if let ToolFnArg::Receiver(..) = arg {
+                params.push(expr.clone());
+            } else {
+                let var = format_ident!("__arg{index}");
+                bindings.push(quote!(let #var = #expr;));
+                params.push(quote!(#var));
+            }
+        }
+        let call = match mode {
+            CallMode::Route | CallMode::Locked => quote_spanned! {ret_span=>
+                ::mcp_attr::helpers::spawn_blocking(move || #f(#(#params,)*)).await
+            },
+            CallMode::Method => quote_spanned! {ret_span=>
+                ::mcp_attr::helpers::block_in_place(move || #f(#(#params,)*))
+            },
+        };
+        quote! {
+            {
+                #(#bindings)*
+                #call
+            }
+        }
+    }
     pub fn build_route(&self) -> Result<TokenStream> {
+        let name = &self.name;
         let fn_ident = &self.fn_ident;
         let route_ident = route_ident(fn_ident);
         let vis = &self.vis;
+        // Errors while parsing arguments are protocol errors, not tool execution failures.
+        let map_err = quote!(.map_err(::mcp_attr::server::ToolError::Protocol));
         let args = self
             .args
             .iter()
-            .map(|a| a.build_call())
+            .map(|a| a.build_call(&map_err, self.lenient, &quote!(self)))
             .collect::<Result<Vec<_>>>()?;
         let metadata = self.build_metadata()?;
+        let deny_unknown_args = self.build_deny_unknown_args(&map_err);
+        let schema_settings = self.build_schema_settings();
+        let invoke = self.build_invoke(&quote!(#fn_ident), &args, CallMode::Route);
+        let enabled = self
+            .enabled
+            .as_ref()
+            .map(|enabled| quote!(.with_enabled(#enabled)));
         Ok(quote! {
             #vis fn #route_ident() -> ::mcp_attr::Result<::mcp_attr::server::builder::ToolDefinition> {
-                Ok(::mcp_attr::server::builder::ToolDefinition::new(
-                    #metadata,
+                Ok(::mcp_attr::server::builder::ToolDefinition::with_schema_settings(
+                    #name,
+                    |settings: &::mcp_attr::server::SchemaSettings| Ok(#metadata),
                     |p: &::mcp_attr::schema::CallToolRequestParams, cx: &::mcp_attr::server::RequestContext| {
                         Box::pin(async move {
-                            Ok(::mcp_attr::schema::CallToolResult::from(
-                                #fn_ident(#(#args,)*).await?,
-                            ))
+                            #schema_settings
+                            #deny_unknown_args
+                            match #invoke {
+                                Ok(value) => Ok(::mcp_attr::schema::CallToolResult::from(value)),
+                                Err(e) => Err(::mcp_attr::helpers::IntoToolError::into_tool_error(e)),
+                            }
                         })
                     }
-                ))
+                )#enabled)
             }
         })
     }
+    /// Builds a statement that binds the schema settings of the session used to parse the arguments
+    ///
+    /// The settings are cloned so that `cx` can still be passed to the tool function.
+    fn build_schema_settings(&self) -> TokenStream {
+        if !self
+            .args
+            .iter()
+            .any(|a| matches!(a, ToolFnArg::Property(..)))
+        {
+            return quote!();
+        }
+        quote! {
+            let __schema_settings = ::mcp_attr::helpers::schema_settings(cx);
+        }
+    }
+    /// Builds a statement that fails as an unknown tool if the tool is disabled in the session
+    fn build_check_enabled(&self) -> TokenStream {
+        let Some(enabled) = &self.enabled else {
+            return quote!();
+        };
+        quote! {
+            if !#enabled(cx) {
+                return ::std::result::Result::Err(::mcp_attr::server::errors::tool_not_found(&p.name));
+            }
+        }
+    }
+    fn build_deny_unknown_args(&self, map_err: &TokenStream) -> TokenStream {
+        if !self.strict {
+            return quote!();
+        }
+        if let Some(arg) = self.flatten_arg() {
+            let ty = &arg.ty;
+            return quote! {
+                ::mcp_attr::helpers::deny_unknown_tool_args_of::<#ty>(&p.arguments, &__schema_settings) #map_err ?;
+            };
+        }
+        let names = self.args.iter().filter_map(|a| match a {
+            ToolFnArg::Property(arg) => Some(&arg.name),
+            ToolFnArg::Context(..) | ToolFnArg::Receiver(..) => None,
+        });
+        quote! {
+            ::mcp_attr::helpers::deny_unknown_tool_args(&p.arguments, &[#(#names),*]) #map_err ?;
+        }
+    }
+    fn flatten_arg(&self) -> Option<&ToolArg> {
+        self.args.iter().find_map(|a| match a {
+            ToolFnArg::Property(arg) if arg.flatten => Some(arg),
+            _ => None,
+        })
+    }
+    pub fn build_client_method(&self) -> Result<TokenStream> {
+        let fn_ident = &self.fn_ident;
+        let name = &self.name;
+        let doc = &self.description;
+        let mut params = Vec::new();
+        let mut stmts = Vec::new();
+        for arg in &self.args {
+            if let ToolFnArg::Property(arg) = arg {
+                params.push(arg.build_client_param());
+                stmts.push(arg.build_client_stmt());
+            }
+        }
+        let (out, decode) = match &self.ret_ty {
+            Some(ty) if is_text_ty(ty) => (
+                quote!(::std::string::String),
+                quote!(Ok(::mcp_attr::helpers::client_tool_result_text(r))),
+            ),
+            Some(ty) if get_element(ty, &[&["mcp_attr", "utils"]], "Json").is_some() => {
+                let ty = get_element(ty, &[&["mcp_attr", "utils"]], "Json").unwrap();
+                (
+                    quote!(#ty),
+                    quote!(::mcp_attr::helpers::client_tool_result_json(r)),
+                )
+            }
+            _ => (quote!(::mcp_attr::schema::CallToolResult), quote!(Ok(r))),
+        };
+        let mutability = if stmts.is_empty() {
+            quote!()
+        } else {
+            quote!(mut)
+        };
+        Ok(quote! {
+            #[doc = #doc]
+            pub async fn #fn_ident(&self, #(#params,)*)
+                -> ::std::result::Result<#out, ::mcp_attr::client::TypedCallError> {
+                let #mutability arguments = ::std::default::Default::default();
+                #(#stmts)*
+                let r = ::mcp_attr::helpers::client_call_tool(&self.0, #name, arguments).await?;
+                #decode
+            }
+        })
+    }
+}
+
+fn check_flatten(args: &[ToolFnArg]) -> Result<()> {
+    let properties: Vec<&ToolArg> = args
+        .iter()
+        .filter_map(|a| match a {
+            ToolFnArg::Property(arg) => Some(arg),
+            ToolFnArg::Context(..) | ToolFnArg::Receiver(..) => None,
+        })
+        .collect();
+    if let Some(arg) = properties.iter().find(|a| a.flatten) {
+        if properties.len() > 1 {
+            bail!(
+                arg.span,
+                "`#[arg(flatten)]` cannot be used with other arguments"
+            );
+        }
+        if !arg.required {
+            bail!(arg.span, "`#[arg(flatten)]` cannot be used with `Option`");
+        }
+    }
+    Ok(())
+}
+
+fn is_text_ty(ty: &Type) -> bool {
+    if let Type::Reference(r) = ty {
+        is_type(
+            &r.elem,
+            &[&["std", "primitive"], &["core", "primitive"]],
+            "str",
+        )
+    } else {
+        is_type(ty, &[&["std", "string"], &["alloc", "string"]], "String")
+    }
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -244,7 +685,7 @@ enum ToolFnArg {
     Receiver(Span),
 }
 impl ToolFnArg {
-    fn new(f: &mut FnArg) -> Result<Self> {
+    fn new(f: &mut FnArg, index: usize, rename_all: Option<RenameRule>) -> Result<Self> {
         let span = f.span();
         let mut typed_arg = match f {
             FnArg::Typed(pat_type) => pat_type,
@@ -258,18 +699,49 @@ impl ToolFnArg {
         if is_context(&typed_arg.ty) && !has_arg_attr {
             return Ok(Self::Context(span));
         }
+        let constraints = arg_arg.constraints();
+        if arg_arg.flatten {
+            if let Some(name) = &arg_arg.name {
+                bail!(name.span(), "`#[arg(flatten)]` cannot have a name");
+            }
+            if arg_arg.default.is_some()
+                || arg_arg.example.is_some()
+                || arg_arg.secret
+                || constraints.is_some()
+            {
+                bail!(
+                    span,
+                    "`#[arg(flatten)]` cannot be used with `default`, `example`, `secret` or constraints"
+                );
+            }
+        }
         let name = if let Some(name) = &arg_arg.name {
             name.value()
         } else {
-            arg_name_of(typed_arg)?
+            let name = arg_name_of(typed_arg)?;
+            match rename_all {
+                Some(rule) => rule.apply(&name),
+                None => name,
+            }
         };
         let (ty, required) = expand_option_ty(&typed_arg.ty);
+        if let Some(default) = &arg_arg.default {
+            if !required {
+                bail!(default.span(), "`default` cannot be used with `Option`");
+            }
+        }
 
         Ok(Self::Property(ToolArg {
             name,
+            client_ident: client_arg_ident(typed_arg, index),
             ty,
             description,
             required,
+            flatten: arg_arg.flatten,
+            secret: arg_arg.secret,
+            default: arg_arg.default,
+            example: arg_arg.example,
+            constraints,
             span,
         }))
     }
@@ -279,20 +751,31 @@ impl ToolFnArg {
             Self::Context(..) | Self::Receiver(..) => Ok(quote!()),
         }
     }
-    fn build_call(&self) -> Result<TokenStream> {
+    fn build_call(
+        &self,
+        map_err: &TokenStream,
+        lenient: bool,
+        receiver: &TokenStream,
+    ) -> Result<TokenStream> {
         match self {
-            Self::Property(arg) => arg.build_call(),
+            Self::Property(arg) => arg.build_call(map_err, lenient),
             Self::Context(span) => Ok(quote_spanned!(*span=> cx)),
-            Self::Receiver(span) => Ok(quote_spanned!(*span=> self)),
+            Self::Receiver(span) => Ok(quote_spanned!(*span=> #receiver)),
         }
     }
 }
 
 struct ToolArg {
     name: String,
+    client_ident: Ident,
     ty: Type,
     description: String,
     required: bool,
+    flatten: bool,
+    secret: bool,
+    default: Option<Expr>,
+    example: Option<Expr>,
+    constraints: Option<TokenStream>,
     span: Span,
 }
 impl ToolArg {
@@ -300,24 +783,102 @@ impl ToolArg {
         let name = &self.name;
         let ty = &self.ty;
         let description = &self.description;
-        let required = self.required;
+        let required = self.is_required();
+        if self.flatten {
+            return Ok(quote! {
+                input_schema.insert_flatten::<#ty>()?;
+            });
+        }
+        let default = self.default.as_ref().map(|default| {
+            quote! {
+                input_schema.insert_keyword(#name, "default", &{ let value: #ty = #default; value })?;
+            }
+        });
+        let example = self.example.as_ref().map(|example| {
+            quote! {
+                input_schema.insert_keyword(#name, "examples", &[{ let value: #ty = #example; value }])?;
+            }
+        });
+        let constraints = self.constraints.as_ref().map(|constraints| {
+            quote! {
+                input_schema.insert_constraints(#name, &#constraints)?;
+            }
+        });
+        let secret = self.secret.then(|| {
+            quote! {
+                input_schema.insert_secret(#name)?;
+            }
+        });
         Ok(quote! {
             input_schema.insert_property::<#ty>(#name, #description, #required)?;
+            #secret
+            #default
+            #example
+            #constraints
         })
     }
-    fn build_call(&self) -> Result<TokenStream> {
+    /// Whether the argument is required by the input schema
+    fn is_required(&self) -> bool {
+        self.required && self.default.is_none()
+    }
+    fn build_call(&self, map_err: &TokenStream, lenient: bool) -> Result<TokenStream> {
         let name = &self.name;
         let ty = &self.ty;
         let required = self.required;
+        let secret = self.secret;
         let span = self.span;
-        if self.required {
-            Ok(
-                quote_spanned! {span=> ::mcp_attr::helpers::parse_tool_arg::<#ty>(&p.arguments, #name)?},
-            )
+        if self.flatten {
+            return Ok(
+                quote_spanned! {span=> ::mcp_attr::helpers::parse_tool_args::<#ty>(&p.arguments, #lenient, &__schema_settings) #map_err ?},
+            );
+        }
+        let parse = if let Some(default) = &self.default {
+            quote_spanned! {span=> (::mcp_attr::helpers::parse_tool_arg_opt::<#ty>(&p.arguments, #name, #lenient, #secret, &__schema_settings) #map_err ?).unwrap_or_else(|| #default)}
+        } else if self.required {
+            quote_spanned! {span=> ::mcp_attr::helpers::parse_tool_arg::<#ty>(&p.arguments, #name, #lenient, #secret, &__schema_settings) #map_err ?}
+        } else {
+            quote_spanned! {span=> ::mcp_attr::helpers::parse_tool_arg_opt::<#ty>(&p.arguments, #name, #lenient, #secret, &__schema_settings) #map_err ?}
+        };
+        if let Some(constraints) = &self.constraints {
+            Ok(quote_spanned! {span=>
+                {
+                    ::mcp_attr::helpers::check_tool_arg::<#ty>(&p.arguments, #name, #lenient, #secret, &#constraints, &__schema_settings) #map_err ?;
+                    #parse
+                }
+            })
+        } else {
+            Ok(parse)
+        }
+    }
+}
+
+impl ToolArg {
+    fn build_client_param(&self) -> TokenStream {
+        let ident = &self.client_ident;
+        let ty = client_arg_ty(&self.ty);
+        if self.is_required() {
+            quote!(#ident: #ty)
+        } else {
+            quote!(#ident: ::std::option::Option<#ty>)
+        }
+    }
+    fn build_client_stmt(&self) -> TokenStream {
+        let ident = &self.client_ident;
+        let name = &self.name;
+        if self.flatten {
+            quote! {
+                ::mcp_attr::helpers::extend_client_tool_args(&mut arguments, &#ident)?;
+            }
+        } else if self.is_required() {
+            quote! {
+                ::mcp_attr::helpers::insert_client_tool_arg(&mut arguments, #name, &#ident)?;
+            }
         } else {
-            Ok(
-                quote_spanned! {span=> ::mcp_attr::helpers::parse_tool_arg_opt::<#ty>(&p.arguments, #name)?},
-            )
+            quote! {
+                if let ::std::option::Option::Some(value) = #ident {
+                    ::mcp_attr::helpers::insert_client_tool_arg(&mut arguments, #name, &value)?;
+                }
+            }
         }
     }
 }
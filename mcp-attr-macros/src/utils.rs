@@ -6,7 +6,7 @@ use std::{
 };
 
 use proc_macro2::{Span, TokenStream};
-use quote::{ToTokens, quote};
+use quote::{ToTokens, format_ident, quote};
 use structmeta::{NameArgs, NameValue, StructMeta};
 use syn::{
     Attribute, Expr, FnArg, Ident, ImplItem, ImplItemFn, ItemFn, ItemImpl, Lit, LitStr, Meta, Pat,
@@ -96,6 +96,10 @@ pub(crate) fn is_defined(items: &[ImplItem], name: &str) -> bool {
     })
 }
 
+pub(crate) fn is_defined_any(items: &[ImplItem], names: &[&str]) -> bool {
+    names.iter().any(|name| is_defined(items, name))
+}
+
 pub(crate) fn get_only_attr<T: Parse>(attrs: &[Attribute], name: &str) -> Result<Option<T>> {
     for (i, attr) in attrs.iter().enumerate() {
         if attr.path().is_ident(name) {
@@ -262,6 +266,122 @@ pub(crate) fn arg_name_of(typed_arg: &PatType) -> Result<String> {
     }
 }
 
+/// Naming convention of arguments specified by `rename_all`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+impl RenameRule {
+    pub(crate) fn from_lit(lit: &LitStr) -> Result<Self> {
+        Ok(match lit.value().as_str() {
+            "lowercase" => Self::Lower,
+            "UPPERCASE" => Self::Upper,
+            "PascalCase" => Self::Pascal,
+            "camelCase" => Self::Camel,
+            "snake_case" => Self::Snake,
+            "SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
+            "kebab-case" => Self::Kebab,
+            "SCREAMING-KEBAB-CASE" => Self::ScreamingKebab,
+            _ => bail!(
+                lit.span(),
+                "unknown rename rule. expected one of: \"lowercase\", \"UPPERCASE\", \"PascalCase\", \"camelCase\", \"snake_case\", \"SCREAMING_SNAKE_CASE\", \"kebab-case\", \"SCREAMING-KEBAB-CASE\""
+            ),
+        })
+    }
+    pub(crate) fn from_attr(
+        lit: &Option<LitStr>,
+        default: Option<RenameRule>,
+    ) -> Result<Option<Self>> {
+        match lit {
+            Some(lit) => Ok(Some(Self::from_lit(lit)?)),
+            None => Ok(default),
+        }
+    }
+
+    /// Converts a snake_case name
+    pub(crate) fn apply(self, name: &str) -> String {
+        match self {
+            Self::Lower | Self::Snake => name.to_string(),
+            Self::Upper | Self::ScreamingSnake => name.to_ascii_uppercase(),
+            Self::Pascal => name
+                .split('_')
+                .map(|word| {
+                    let mut cs = word.chars();
+                    match cs.next() {
+                        Some(c) => c.to_ascii_uppercase().to_string() + cs.as_str(),
+                        None => String::new(),
+                    }
+                })
+                .collect(),
+            Self::Camel => {
+                let pascal = Self::Pascal.apply(name);
+                let mut cs = pascal.chars();
+                match cs.next() {
+                    Some(c) => c.to_ascii_lowercase().to_string() + cs.as_str(),
+                    None => String::new(),
+                }
+            }
+            Self::Kebab => name.replace('_', "-"),
+            Self::ScreamingKebab => name.replace('_', "-").to_ascii_uppercase(),
+        }
+    }
+}
+#[test]
+fn rename_rule_test() {
+    let name = "max_item_count";
+    assert_eq!(RenameRule::Lower.apply(name), "max_item_count");
+    assert_eq!(RenameRule::Upper.apply(name), "MAX_ITEM_COUNT");
+    assert_eq!(RenameRule::Pascal.apply(name), "MaxItemCount");
+    assert_eq!(RenameRule::Camel.apply(name), "maxItemCount");
+    assert_eq!(RenameRule::Snake.apply(name), "max_item_count");
+    assert_eq!(RenameRule::ScreamingSnake.apply(name), "MAX_ITEM_COUNT");
+    assert_eq!(RenameRule::Kebab.apply(name), "max-item-count");
+    assert_eq!(RenameRule::ScreamingKebab.apply(name), "MAX-ITEM-COUNT");
+}
+
+pub(crate) fn client_arg_ident(typed_arg: &PatType, index: usize) -> Ident {
+    match &*typed_arg.pat {
+        Pat::Ident(pat_ident) => pat_ident.ident.clone(),
+        _ => format_ident!("arg_{index}"),
+    }
+}
+
+pub(crate) fn client_arg_ty(ty: &Type) -> TokenStream {
+    if is_type(ty, &[&["std", "string"], &["alloc", "string"]], "String") {
+        quote!(&str)
+    } else {
+        quote!(#ty)
+    }
+}
+
+pub(crate) fn result_ok_ty(sig: &Signature) -> Option<Type> {
+    let syn::ReturnType::Type(_, ty) = &sig.output else {
+        return None;
+    };
+    let Type::Path(ty) = &**ty else {
+        return None;
+    };
+    let last = ty.path.segments.last()?;
+    if last.ident != "Result" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &last.arguments else {
+        return None;
+    };
+    if let Some(syn::GenericArgument::Type(ty)) = args.args.first() {
+        Some(ty.clone())
+    } else {
+        None
+    }
+}
+
 pub(crate) fn ret_span(sig: &Signature, f_span: Span) -> Span {
     match &sig.output {
         syn::ReturnType::Default => f_span,
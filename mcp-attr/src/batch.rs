@@ -0,0 +1,348 @@
+//! JSON-RPC batch support for newline-delimited transports
+//!
+//! jsoncall reads batches but answers their requests one by one, and never writes batches,
+//! so batches are handled between the transport and the session.
+
+use std::{
+    collections::HashSet,
+    io,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use jsoncall::ErrorCode;
+use serde_json::{Value, json};
+use tokio::{
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader, DuplexStream},
+    sync::{mpsc, watch},
+};
+
+use crate::utils::ProtocolVersion;
+
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// Returns whether JSON-RPC batches are allowed in a session of the specified protocol version
+pub(crate) fn is_batch_supported(v: ProtocolVersion) -> bool {
+    v == ProtocolVersion::V_2025_03_26
+}
+
+fn is_batch(line: &str) -> bool {
+    line.trim_start().starts_with('[')
+}
+fn is_response(m: &Value) -> bool {
+    m.get("method").is_none() && m.get("id").is_some()
+}
+fn id_key(m: &Value) -> Option<String> {
+    Some(m.get("id")?.to_string())
+}
+fn error_response(code: ErrorCode, message: &str) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": null,
+        "error": { "code": code, "message": message },
+    })
+    .to_string()
+}
+async fn write_line(writer: &mut (impl AsyncWrite + Unpin + ?Sized), line: &str) -> io::Result<()> {
+    writer.write_all(line.as_bytes()).await?;
+    writer.write_all(b"\n").await?;
+    writer.flush().await
+}
+
+struct PendingBatch {
+    ids: HashSet<String>,
+    responses: Vec<Value>,
+}
+
+/// Wraps the transport of a server session to accept batches in sessions that negotiated a version supporting them
+///
+/// The negotiated version has to be sent through the returned sender once the session is initialized.
+/// Until then, and for as long as the version supports batches, requests in a batch are passed to the session one by one
+/// and their responses are written as a single batch.
+/// Once another version is negotiated, messages are copied as they are.
+pub(crate) fn server_transport(
+    reader: impl AsyncBufRead + Send + Sync + 'static,
+    writer: impl AsyncWrite + Send + Sync + 'static,
+) -> (
+    BufReader<DuplexStream>,
+    DuplexStream,
+    watch::Sender<Option<ProtocolVersion>>,
+) {
+    let (version_tx, version) = watch::channel(None);
+    let batches = Arc::new(Mutex::new(Vec::new()));
+    let (line_tx, mut extra_lines) = mpsc::unbounded_channel::<String>();
+    let (session_reader, mut incoming) = tokio::io::duplex(BUFFER_SIZE);
+    let (session_writer, outgoing) = tokio::io::duplex(BUFFER_SIZE);
+
+    let (b, v) = (batches.clone(), version.clone());
+    tokio::spawn(async move {
+        let mut lines = Box::pin(reader).lines();
+        loop {
+            if !v.borrow().is_none_or(is_batch_supported) {
+                let _ = tokio::io::copy(&mut lines.into_inner(), &mut incoming).await;
+                return;
+            }
+            let Ok(Some(line)) = lines.next_line().await else {
+                return;
+            };
+            let messages = if is_batch(&line) {
+                match accept_batch(&line, *v.borrow(), &b) {
+                    Ok(messages) => messages,
+                    Err(e) => {
+                        let _ = line_tx.send(e);
+                        continue;
+                    }
+                }
+            } else {
+                if let Some(batch) = cancel_response(&line, &b) {
+                    let _ = line_tx.send(batch);
+                }
+                vec![line]
+            };
+            for m in messages {
+                if write_line(&mut incoming, &m).await.is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    tokio::spawn(async move {
+        let mut writer = Box::pin(writer);
+        let mut lines = BufReader::new(outgoing).lines();
+        loop {
+            if !version.borrow().is_none_or(is_batch_supported) {
+                while let Ok(line) = extra_lines.try_recv() {
+                    if write_line(&mut writer, &line).await.is_err() {
+                        return;
+                    }
+                }
+                let _ = tokio::io::copy(&mut lines.into_inner(), &mut writer).await;
+                break;
+            }
+            let line = tokio::select! {
+                line = lines.next_line() => match line {
+                    Ok(Some(line)) => complete_response(line, &batches),
+                    _ => break,
+                },
+                Some(line) = extra_lines.recv() => Some(line),
+            };
+            if let Some(line) = line {
+                if write_line(&mut writer, &line).await.is_err() {
+                    return;
+                }
+            }
+        }
+        let _ = writer.shutdown().await;
+    });
+    (BufReader::new(session_reader), session_writer, version_tx)
+}
+
+fn accept_batch(
+    line: &str,
+    version: Option<ProtocolVersion>,
+    batches: &Mutex<Vec<PendingBatch>>,
+) -> Result<Vec<String>, String> {
+    let Ok(messages) = serde_json::from_str::<Vec<Value>>(line) else {
+        return Err(error_response(ErrorCode::PARSE_ERROR, "Parse error"));
+    };
+    if !version.is_some_and(is_batch_supported) {
+        return Err(error_response(
+            ErrorCode::INVALID_REQUEST,
+            "JSON-RPC batches are not supported in this protocol version",
+        ));
+    }
+    if messages.is_empty() {
+        return Err(error_response(
+            ErrorCode::INVALID_REQUEST,
+            "JSON-RPC batch is empty",
+        ));
+    }
+    let ids: HashSet<String> = messages
+        .iter()
+        .filter(|m| m.get("method").is_some())
+        .filter_map(id_key)
+        .collect();
+    if !ids.is_empty() {
+        batches.lock().unwrap().push(PendingBatch {
+            ids,
+            responses: Vec::new(),
+        });
+    }
+    Ok(messages.iter().map(|m| m.to_string()).collect())
+}
+
+/// Stops waiting for the response of a cancelled request, which is never sent
+///
+/// Returns the batch to write if it was waiting only for that response.
+fn cancel_response(line: &str, batches: &Mutex<Vec<PendingBatch>>) -> Option<String> {
+    let mut batches = batches.lock().unwrap();
+    if batches.is_empty() {
+        return None;
+    }
+    let m = serde_json::from_str::<Value>(line).ok()?;
+    if m.get("method").and_then(Value::as_str) != Some("notifications/cancelled") {
+        return None;
+    }
+    let id = m.pointer("/params/requestId")?.to_string();
+    let index = batches.iter().position(|b| b.ids.contains(&id))?;
+    let batch = &mut batches[index];
+    batch.ids.remove(&id);
+    if !batch.ids.is_empty() {
+        return None;
+    }
+    let batch = batches.remove(index);
+    if batch.responses.is_empty() {
+        return None;
+    }
+    Some(Value::Array(batch.responses).to_string())
+}
+
+fn complete_response(line: String, batches: &Mutex<Vec<PendingBatch>>) -> Option<String> {
+    let mut batches = batches.lock().unwrap();
+    if batches.is_empty() {
+        return Some(line);
+    }
+    let Ok(m) = serde_json::from_str::<Value>(&line) else {
+        return Some(line);
+    };
+    if !is_response(&m) {
+        return Some(line);
+    }
+    let id = id_key(&m)?;
+    let Some(index) = batches.iter().position(|b| b.ids.contains(&id)) else {
+        return Some(line);
+    };
+    let batch = &mut batches[index];
+    batch.ids.remove(&id);
+    batch.responses.push(m);
+    if !batch.ids.is_empty() {
+        return None;
+    }
+    let batch = batches.remove(index);
+    Some(Value::Array(batch.responses).to_string())
+}
+
+/// Groups requests written by a client session into batches
+///
+/// The requests of a batch are sent through the session, so they get their ids from the same counter as other requests.
+/// While a batch is being collected, requests written by the session are claimed by their method and parameters,
+/// and the batch is written once all of its requests are claimed.
+/// jsoncall reads the batch of responses by itself.
+pub(crate) struct ClientBatcher {
+    tx: mpsc::WeakUnboundedSender<String>,
+    next_key: AtomicU64,
+    batches: Mutex<Vec<CollectingBatch>>,
+}
+struct CollectingBatch {
+    key: u64,
+    requests: Vec<(String, Option<Value>)>,
+    messages: Vec<Value>,
+}
+impl ClientBatcher {
+    /// Starts collecting the specified requests into a batch
+    ///
+    /// The requests have to be sent through the session while the returned guard is alive.
+    pub(crate) fn collect(&self, requests: Vec<(String, Option<Value>)>) -> CollectGuard<'_> {
+        let key = self.next_key.fetch_add(1, Ordering::Relaxed);
+        let requests = requests
+            .into_iter()
+            .map(|(method, params)| (method, params.filter(|p| !p.is_null())))
+            .collect();
+        self.batches.lock().unwrap().push(CollectingBatch {
+            key,
+            requests,
+            messages: Vec::new(),
+        });
+        CollectGuard { batcher: self, key }
+    }
+
+    /// Claims a line written by the session and returns the line to write, if any
+    fn claim(&self, line: String) -> Option<String> {
+        let mut batches = self.batches.lock().unwrap();
+        if batches.is_empty() {
+            return Some(line);
+        }
+        let Ok(m) = serde_json::from_str::<Value>(&line) else {
+            return Some(line);
+        };
+        let (Some(method), Some(_)) = (m.get("method").and_then(Value::as_str), m.get("id")) else {
+            return Some(line);
+        };
+        let params = m.get("params").filter(|p| !p.is_null());
+        let index = batches.iter_mut().position(|b| {
+            let Some(i) = b
+                .requests
+                .iter()
+                .position(|r| r.0 == method && r.1.as_ref() == params)
+            else {
+                return false;
+            };
+            b.requests.swap_remove(i);
+            true
+        });
+        let Some(index) = index else {
+            return Some(line);
+        };
+        let batch = &mut batches[index];
+        batch.messages.push(m);
+        if !batch.requests.is_empty() {
+            return None;
+        }
+        let batch = batches.remove(index);
+        Some(Value::Array(batch.messages).to_string())
+    }
+}
+
+/// Stops collecting a batch and writes the requests claimed so far
+pub(crate) struct CollectGuard<'a> {
+    batcher: &'a ClientBatcher,
+    key: u64,
+}
+impl Drop for CollectGuard<'_> {
+    fn drop(&mut self) {
+        let mut batches = self.batcher.batches.lock().unwrap();
+        let Some(index) = batches.iter().position(|b| b.key == self.key) else {
+            return;
+        };
+        let batch = batches.remove(index);
+        if let (false, Some(tx)) = (batch.messages.is_empty(), self.batcher.tx.upgrade()) {
+            let _ = tx.send(Value::Array(batch.messages).to_string());
+        }
+    }
+}
+
+/// Wraps the writer of a client session to send batches collected by [`ClientBatcher`]
+pub(crate) fn client_transport(
+    writer: impl AsyncWrite + Send + Sync + 'static,
+) -> (DuplexStream, Arc<ClientBatcher>) {
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    tokio::spawn(async move {
+        let mut writer = Box::pin(writer);
+        while let Some(line) = rx.recv().await {
+            if write_line(&mut writer, &line).await.is_err() {
+                break;
+            }
+        }
+        let _ = writer.shutdown().await;
+    });
+    let batcher = Arc::new(ClientBatcher {
+        tx: tx.downgrade(),
+        next_key: AtomicU64::new(0),
+        batches: Mutex::new(Vec::new()),
+    });
+    let (session_writer, outgoing) = tokio::io::duplex(BUFFER_SIZE);
+    let b = batcher.clone();
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(outgoing).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if let Some(line) = b.claim(line) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    (session_writer, batcher)
+}
@@ -1,34 +1,58 @@
 //! Module for implementing MCP client
 
-use std::{future::Future, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    future::Future,
+    io,
+    process::ExitStatus,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use derive_ex::Ex;
+use futures::{Stream, StreamExt, TryStreamExt, stream};
 use jsoncall::{
-    Handler, NotificationContext, Params, RequestContext, RequestContextAs, Response, Result,
-    Session, SessionOptions, SessionResult,
+    Error, ErrorCode, ErrorObject, Handler, NotificationContext, Params, RequestContext,
+    RequestContextAs, Response, Result, Session, SessionError, SessionErrorKind, SessionOptions,
+    SessionResult,
 };
-use serde_json::Map;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value, json};
 use tokio::{
     io::{AsyncBufRead, AsyncWrite},
-    process::Command,
+    process::{Child, Command},
 };
 
 use crate::{
-    common::McpCancellationHook,
+    CapabilityNotSupported,
+    batch::{self, ClientBatcher, is_batch_supported},
+    client::{
+        process::{ChildProcess, clone_command},
+        retry::is_retryable_tool,
+    },
+    common::{McpCancellationHook, to_session_error},
     schema::{
         CallToolRequestParams, CallToolResult, CancelledNotificationParams, ClientCapabilities,
-        ClientCapabilitiesRoots, CompleteRequestParams, CompleteResult, CreateMessageRequestParams,
-        CreateMessageResult, GetPromptRequestParams, GetPromptResult, Implementation,
-        InitializeRequestParams, InitializeResult, InitializedNotificationParams,
+        ClientCapabilitiesRoots, CompleteRequestParams, CompleteResult, ContentBlock,
+        CreateMessageRequestParams, CreateMessageResult, GetPromptRequestParams, GetPromptResult,
+        Implementation, InitializeRequestParams, InitializeResult, InitializedNotificationParams,
         ListPromptsRequestParams, ListPromptsResult, ListResourceTemplatesRequestParams,
         ListResourceTemplatesResult, ListResourcesRequestParams, ListResourcesResult,
-        ListRootsResult, ListToolsRequestParams, ListToolsResult, PingRequestParams,
-        ReadResourceRequestParams, ReadResourceResult, Root,
+        ListRootsResult, ListToolsRequestParams, ListToolsResult, PaginatedRequestParams,
+        PingRequestParams, Prompt, ReadResourceRequestParams, ReadResourceResult,
+        ReadResourceResultContentsItem, Resource, ResourceTemplate, Root, Tool, ToolInputSchema,
     },
     server::McpServer,
     utils::{Empty, ProtocolVersion},
 };
 
+mod process;
+mod retry;
+
+pub use process::StderrMode;
+pub use retry::{CallOptions, RequestTimeout, RetryPolicy};
+
 /// Trait for implementing [client features]
 ///
 /// Used with [`McpClientBuilder::with_handler`] to create an MCP client that supports client features.
@@ -83,7 +107,7 @@ impl<T: McpClientHandler + Send + Sync + 'static> DynSamplingHandler for T {
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Ex)]
+#[derive(Clone, Ex)]
 #[derive_ex(Default)]
 #[default(Self::new())]
 pub struct McpClientBuilder {
@@ -91,6 +115,15 @@ pub struct McpClientBuilder {
     roots: Option<Vec<Root>>,
     client_info: Implementation,
     expose_internals: Option<bool>,
+    stderr: StderrMode,
+    requests: RequestSettings,
+    protocol_versions: Vec<ProtocolVersion>,
+}
+
+#[derive(Clone, Default)]
+struct RequestSettings {
+    timeout: Option<Duration>,
+    retry: Option<RetryPolicy>,
 }
 impl McpClientBuilder {
     /// Creates a new [`McpClient`]
@@ -100,6 +133,9 @@ impl McpClientBuilder {
             roots: None,
             client_info: Implementation::from_compile_time_env(),
             expose_internals: None,
+            stderr: StderrMode::Inherit,
+            requests: RequestSettings::default(),
+            protocol_versions: ProtocolVersion::ALL.to_vec(),
         }
     }
 
@@ -130,31 +166,101 @@ impl McpClientBuilder {
         self
     }
 
+    /// Sets how to handle the standard error of the process launched by [`build_with_command`](Self::build_with_command)
+    pub fn with_stderr(mut self, stderr: StderrMode) -> Self {
+        self.stderr = stderr;
+        self
+    }
+
+    /// Sets the protocol versions supported by the client
+    ///
+    /// The latest version in the list is requested in the [`initialize`] request,
+    /// and building the client fails with [`UnsupportedProtocolVersion`] if the server responds with a version not in the list.
+    /// If the list is empty, building the client fails without sending the `initialize` request.
+    /// The default is [`ProtocolVersion::ALL`].
+    ///
+    /// [`initialize`]: https://modelcontextprotocol.io/specification/2025-06-18/basic/lifecycle#version-negotiation
+    pub fn with_protocol_versions(mut self, protocol_versions: &[ProtocolVersion]) -> Self {
+        self.protocol_versions = protocol_versions.to_vec();
+        self
+    }
+
+    /// Sets the time to wait for the response of each request
+    ///
+    /// When the time expires, `notifications/cancelled` is sent to the server and the request fails.
+    /// Use [`CallOptions::with_timeout`] with the `_with` methods of [`McpClient`] (e.g. [`McpClient::tools_call_with`]) to override it for a single request.
+    /// An expired request fails with [`RequestTimeout`].
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.requests.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the policy for retrying [`tools/call`] requests
+    ///
+    /// See [`RetryPolicy`] for which requests are retried.
+    ///
+    /// [`tools/call`]: https://modelcontextprotocol.io/specification/2025-06-18/client/tools#calling-tools
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.requests.retry = Some(retry);
+        self
+    }
+
     /// Builds a [`McpClient`] client using the specified reader and writer
     pub async fn build(
         self,
         reader: impl AsyncBufRead + Send + Sync + 'static,
         writer: impl AsyncWrite + Send + Sync + 'static,
     ) -> SessionResult<McpClient> {
+        let requests = self.requests.clone();
+        let protocol_versions = self.protocol_versions.clone();
         let (handler, options, p) = self.build_raw();
-        McpClient::initialize(Session::new(handler, reader, writer, &options), p).await
+        let (writer, batcher) = batch::client_transport(writer);
+        let session = Session::new(handler, reader, writer, &options);
+        let mut client = McpClient::initialize_with(session, p, &protocol_versions).await?;
+        client.requests = requests;
+        client.batcher = Some(batcher);
+        Ok(client)
     }
     /// Launches a MCP server process with the specified command and builds [`McpClient`] that communicates with it using stdio transport
+    ///
+    /// The process is killed when the returned `McpClient` is dropped.
+    /// Use [`McpClient::shutdown`] to stop it gracefully and [`McpClient::restart`] to launch it again.
+    /// A process that exits on its own is not restarted automatically; the client has to call [`McpClient::restart`].
     pub async fn build_with_command(self, command: &mut Command) -> SessionResult<McpClient> {
+        let builder = self.clone();
+        let stderr = self.stderr;
+        let requests = self.requests.clone();
+        let protocol_versions = self.protocol_versions.clone();
         let (handler, options, p) = self.build_raw();
-        McpClient::initialize(Session::from_command(handler, command, &options)?, p).await
+        let (process, reader, writer) = ChildProcess::spawn(command, stderr)?;
+        let (writer, batcher) = batch::client_transport(writer);
+        let session = Session::new(handler, reader, writer, &options);
+        let mut client = McpClient::initialize_with(session, p, &protocol_versions).await?;
+        client.requests = requests;
+        client.batcher = Some(batcher);
+        client.process = Some(McpClientProcess {
+            process,
+            builder,
+            command: clone_command(command),
+        });
+        Ok(client)
     }
 
     /// Builds a [`McpClient`] client that communicates with the specified [`McpServer`]
     ///
     /// The specified `McpServer` will be owned by the returned McpClient.
     pub async fn build_with_server(self, server: impl McpServer) -> SessionResult<McpClient> {
+        let requests = self.requests.clone();
+        let protocol_versions = self.protocol_versions.clone();
         let (client_handler, options, p) = self.build_raw();
-        let server_handler = server.into_handler();
+        let server_handler = server.into_handler_with(SessionOptions {
+            expose_internals: options.expose_internals,
+        });
 
         let (client, server) = Session::new_channel(client_handler, server_handler, &options);
-        let mut client = McpClient::initialize(client, p).await?;
+        let mut client = McpClient::initialize_with(client, p, &protocol_versions).await?;
         client.server = Some(server);
+        client.requests = requests;
         Ok(client)
     }
 
@@ -200,7 +306,12 @@ impl McpClientBuilder {
         let p = InitializeRequestParams {
             capabilities,
             client_info: self.client_info,
-            protocol_version: ProtocolVersion::LATEST.to_string(),
+            protocol_version: self
+                .protocol_versions
+                .iter()
+                .max()
+                .unwrap_or(&ProtocolVersion::LATEST)
+                .to_string(),
         };
         (handler, options, p)
     }
@@ -269,6 +380,9 @@ impl McpClientJsonRpcHandler {
 /// To create an `McpClient`, use the [`with_server`](Self::with_server) method or [`McpClientBuilder`].
 /// The method to create an `McpClient` performs an [`initialize`] request to the server and returns control when the request completes.
 ///
+/// Requests for prompts, resources and tools fail with [`CapabilityNotSupported`](crate::CapabilityNotSupported) without being sent
+/// if the server did not advertise the corresponding capability.
+///
 /// [`initialize`]: https://modelcontextprotocol.io/specification/2025-06-18/client/initialize
 ///
 /// # Example
@@ -297,6 +411,7 @@ impl McpClientJsonRpcHandler {
 /// let params = CallToolRequestParams {
 ///     name: "tool_name".to_string(),
 ///     arguments: Some(serde_json::Map::new()),
+///     ..Default::default()
 /// };
 /// let result = client.tools_call(params).await?;
 /// # Ok(())
@@ -305,7 +420,19 @@ impl McpClientJsonRpcHandler {
 pub struct McpClient {
     session: Session,
     init: InitializeResult,
+    protocol_version: ProtocolVersion,
     server: Option<Session>,
+    process: Option<McpClientProcess>,
+    requests: RequestSettings,
+    retryable_tools: Mutex<HashMap<String, bool>>,
+    tool_input_schemas: Mutex<HashMap<String, Arc<ToolInputSchema>>>,
+    batcher: Option<Arc<ClientBatcher>>,
+}
+
+struct McpClientProcess {
+    process: ChildProcess,
+    builder: McpClientBuilder,
+    command: Command,
 }
 
 impl McpClient {
@@ -325,10 +452,36 @@ impl McpClient {
     /// Performs an [`initialize`] request to the server and returns the result
     ///
     /// [`initialize`]: https://modelcontextprotocol.io/specification/2025-06-18/client/initialize
+    ///
+    /// Fails if the server responds with a protocol version that this crate does not support.
     pub async fn initialize(session: Session, p: InitializeRequestParams) -> SessionResult<Self> {
+        Self::initialize_with(session, p, ProtocolVersion::ALL).await
+    }
+    async fn initialize_with(
+        session: Session,
+        p: InitializeRequestParams,
+        protocol_versions: &[ProtocolVersion],
+    ) -> SessionResult<Self> {
+        if protocol_versions.is_empty() {
+            let e = Error::new(ErrorCode::INVALID_PARAMS).with_message(
+                "no protocol version is set by `McpClientBuilder::with_protocol_versions`",
+                true,
+            );
+            return Err(to_session_error(e));
+        }
         let init = session
             .request::<InitializeResult>("initialize", Some(&p))
             .await?;
+        let protocol_version = init
+            .protocol_version
+            .parse::<ProtocolVersion>()
+            .ok()
+            .filter(|v| protocol_versions.contains(v));
+        let Some(protocol_version) = protocol_version else {
+            return Err(
+                UnsupportedProtocolVersion::new(&init.protocol_version, protocol_versions).into(),
+            );
+        };
         session.notification(
             "notifications/initialized",
             Some(&InitializedNotificationParams::default()),
@@ -336,10 +489,66 @@ impl McpClient {
         Ok(Self {
             session,
             init,
+            protocol_version,
             server: None,
+            process: None,
+            requests: RequestSettings::default(),
+            retryable_tools: Mutex::new(HashMap::new()),
+            tool_input_schemas: Mutex::new(HashMap::new()),
+            batcher: None,
         })
     }
 
+    /// Gets the server process launched by [`McpClientBuilder::build_with_command`]
+    pub fn child(&self) -> Option<&Child> {
+        Some(&self.process.as_ref()?.process.child)
+    }
+
+    /// Gets the server process launched by [`McpClientBuilder::build_with_command`] as mutable
+    ///
+    /// Use [`Child::try_wait`] to check whether the process has exited.
+    pub fn child_mut(&mut self) -> Option<&mut Child> {
+        Some(&mut self.process.as_mut()?.process.child)
+    }
+
+    /// Stops the server process launched by [`McpClientBuilder::build_with_command`]
+    ///
+    /// Closes the standard input of the process and waits for it to exit.
+    /// If it does not exit within `timeout`, it is killed.
+    ///
+    /// Returns `None` if this client was not built with `build_with_command`.
+    pub async fn shutdown(mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        let Some(mut p) = self.process.take() else {
+            return Ok(None);
+        };
+        drop(self);
+        p.process.shutdown(timeout).await.map(Some)
+    }
+
+    /// Stops the server process launched by [`McpClientBuilder::build_with_command`] and launches it again
+    ///
+    /// The process is stopped in the same way as [`shutdown`](Self::shutdown), then the same command is launched and the [`initialize`] handshake is performed again.
+    /// Only the program, arguments, environment variables and working directory of the original command are reused.
+    ///
+    /// The process is not restarted automatically when it crashes.
+    /// Use [`Child::try_wait`] on [`child_mut`](Self::child_mut) to detect that it has exited, then call this method.
+    ///
+    /// [`initialize`]: https://modelcontextprotocol.io/specification/2025-06-18/client/initialize
+    pub async fn restart(&mut self, timeout: Duration) -> SessionResult<()> {
+        let Some(p) = &mut self.process else {
+            return Err(to_session_error(
+                Error::new(ErrorCode::INTERNAL_ERROR).with_message(
+                    "only clients built with `build_with_command` can be restarted",
+                    true,
+                ),
+            ));
+        };
+        p.process.shutdown(timeout).await?;
+        let mut command = clone_command(&p.command);
+        *self = p.builder.clone().build_with_command(&mut command).await?;
+        Ok(())
+    }
+
     /// Gets the JSON RPC Session
     pub fn session(&self) -> &Session {
         &self.session
@@ -352,6 +561,13 @@ impl McpClient {
         self.init.instructions.as_deref()
     }
 
+    /// Gets the protocol version negotiated in the [`initialize`] request
+    ///
+    /// [`initialize`]: https://modelcontextprotocol.io/specification/2025-06-18/basic/lifecycle#version-negotiation
+    pub fn protocol_version(&self) -> ProtocolVersion {
+        self.protocol_version
+    }
+
     /// Gets the `server_info` obtained from the [`initialize`] request response
     ///
     /// [`initialize`]: https://modelcontextprotocol.io/specification/2025-06-18/client/initialize
@@ -366,7 +582,19 @@ impl McpClient {
         &self,
         params: Option<ListPromptsRequestParams>,
     ) -> SessionResult<ListPromptsResult> {
-        self.session.request("prompts/list", params.as_ref()).await
+        self.prompts_list_with(params, &CallOptions::new()).await
+    }
+
+    /// Calls [`prompts/list`] with the specified timeout
+    ///
+    /// [`prompts/list`]: https://modelcontextprotocol.io/specification/2025-06-18/client/prompts#listing-prompts
+    pub async fn prompts_list_with(
+        &self,
+        params: Option<ListPromptsRequestParams>,
+        options: &CallOptions,
+    ) -> SessionResult<ListPromptsResult> {
+        self.request_with_timeout("prompts/list", params.as_ref(), options.timeout(self))
+            .await
     }
 
     /// Calls [`prompts/get`]
@@ -376,7 +604,19 @@ impl McpClient {
         &self,
         params: GetPromptRequestParams,
     ) -> SessionResult<GetPromptResult> {
-        self.session.request("prompts/get", Some(&params)).await
+        self.prompts_get_with(params, &CallOptions::new()).await
+    }
+
+    /// Calls [`prompts/get`] with the specified timeout
+    ///
+    /// [`prompts/get`]: https://modelcontextprotocol.io/specification/2025-06-18/client/prompts#getting-a-prompt
+    pub async fn prompts_get_with(
+        &self,
+        params: GetPromptRequestParams,
+        options: &CallOptions,
+    ) -> SessionResult<GetPromptResult> {
+        self.request_with_timeout("prompts/get", Some(&params), options.timeout(self))
+            .await
     }
 
     /// Calls [`resources/list`]
@@ -386,8 +626,18 @@ impl McpClient {
         &self,
         params: Option<ListResourcesRequestParams>,
     ) -> SessionResult<ListResourcesResult> {
-        self.session
-            .request("resources/list", params.as_ref())
+        self.resources_list_with(params, &CallOptions::new()).await
+    }
+
+    /// Calls [`resources/list`] with the specified timeout
+    ///
+    /// [`resources/list`]: https://modelcontextprotocol.io/specification/2025-06-18/client/resources#listing-resources
+    pub async fn resources_list_with(
+        &self,
+        params: Option<ListResourcesRequestParams>,
+        options: &CallOptions,
+    ) -> SessionResult<ListResourcesResult> {
+        self.request_with_timeout("resources/list", params.as_ref(), options.timeout(self))
             .await
     }
 
@@ -398,11 +648,26 @@ impl McpClient {
         &self,
         params: Option<ListResourceTemplatesRequestParams>,
     ) -> SessionResult<ListResourceTemplatesResult> {
-        self.session
-            .request("resources/templates/list", params.as_ref())
+        self.resources_templates_list_with(params, &CallOptions::new())
             .await
     }
 
+    /// Calls [`resources/templates/list`] with the specified timeout
+    ///
+    /// [`resources/templates/list`]: https://modelcontextprotocol.io/specification/2025-06-18/client/resources#listing-resource-templates
+    pub async fn resources_templates_list_with(
+        &self,
+        params: Option<ListResourceTemplatesRequestParams>,
+        options: &CallOptions,
+    ) -> SessionResult<ListResourceTemplatesResult> {
+        self.request_with_timeout(
+            "resources/templates/list",
+            params.as_ref(),
+            options.timeout(self),
+        )
+        .await
+    }
+
     /// Calls [`resources/read`]
     ///
     /// [`resources/read`]: https://modelcontextprotocol.io/specification/2025-06-18/client/resources#reading-a-resource
@@ -410,7 +675,19 @@ impl McpClient {
         &self,
         params: ReadResourceRequestParams,
     ) -> SessionResult<ReadResourceResult> {
-        self.session.request("resources/read", Some(&params)).await
+        self.resources_read_with(params, &CallOptions::new()).await
+    }
+
+    /// Calls [`resources/read`] with the specified timeout
+    ///
+    /// [`resources/read`]: https://modelcontextprotocol.io/specification/2025-06-18/client/resources#reading-a-resource
+    pub async fn resources_read_with(
+        &self,
+        params: ReadResourceRequestParams,
+        options: &CallOptions,
+    ) -> SessionResult<ReadResourceResult> {
+        self.request_with_timeout("resources/read", Some(&params), options.timeout(self))
+            .await
     }
 
     /// Calls [`tools/list`]
@@ -420,14 +697,322 @@ impl McpClient {
         &self,
         params: Option<ListToolsRequestParams>,
     ) -> SessionResult<ListToolsResult> {
-        self.session.request("tools/list", params.as_ref()).await
+        self.tools_list_with(params, &CallOptions::new()).await
+    }
+
+    /// Calls [`tools/list`] with the specified timeout
+    ///
+    /// [`tools/list`]: https://modelcontextprotocol.io/specification/2025-06-18/client/tools#listing-tools
+    pub async fn tools_list_with(
+        &self,
+        params: Option<ListToolsRequestParams>,
+        options: &CallOptions,
+    ) -> SessionResult<ListToolsResult> {
+        let r: ListToolsResult = self
+            .request_with_timeout("tools/list", params.as_ref(), options.timeout(self))
+            .await?;
+        r.tools.iter().for_each(|tool| self.record_tool(tool));
+        Ok(r)
     }
 
     /// Calls [`tools/call`]
     ///
     /// [`tools/call`]: https://modelcontextprotocol.io/specification/2025-06-18/client/tools#calling-tools
     pub async fn tools_call(&self, params: CallToolRequestParams) -> SessionResult<CallToolResult> {
-        self.session.request("tools/call", Some(&params)).await
+        self.tools_call_with(params, &CallOptions::new()).await
+    }
+
+    /// Calls [`tools/call`] with the specified timeout and retry policy
+    ///
+    /// [`tools/call`]: https://modelcontextprotocol.io/specification/2025-06-18/client/tools#calling-tools
+    pub async fn tools_call_with(
+        &self,
+        params: CallToolRequestParams,
+        options: &CallOptions,
+    ) -> SessionResult<CallToolResult> {
+        let timeout = options.timeout(self);
+        let retry = match options.retry.as_ref().or(self.requests.retry.as_ref()) {
+            Some(retry) if self.is_retryable_tool(&params.name).await => Some(retry),
+            _ => None,
+        };
+        let mut retries = 0;
+        loop {
+            let r = self
+                .request_with_timeout("tools/call", Some(&params), timeout)
+                .await;
+            match (r, retry) {
+                (Err(e), Some(retry)) if retries < retry.max_retries && is_retryable_error(&e) => {
+                    tokio::time::sleep(retry.backoff(retries)).await;
+                    retries += 1;
+                }
+                (r, _) => return r,
+            }
+        }
+    }
+
+    fn record_tool(&self, tool: &Tool) {
+        self.retryable_tools
+            .lock()
+            .unwrap()
+            .insert(tool.name.clone(), is_retryable_tool(tool));
+        self.tool_input_schemas
+            .lock()
+            .unwrap()
+            .insert(tool.name.clone(), Arc::new(tool.input_schema.clone()));
+    }
+
+    /// Returns the input schema of the tool named `name` listed by the server in this session
+    ///
+    /// Schemas are recorded by [`tools_list`](Self::tools_list) and [`tools_list_all`](Self::tools_list_all).
+    /// Attach the schema with [`CallToolRequestParams::with_input_schema`]
+    /// to redact values of `writeOnly` schemas from the `Debug` output of the params.
+    pub fn tool_input_schema(&self, name: &str) -> Option<Arc<ToolInputSchema>> {
+        self.tool_input_schemas.lock().unwrap().get(name).cloned()
+    }
+    async fn is_retryable_tool(&self, name: &str) -> bool {
+        let retryable = self.retryable_tools.lock().unwrap().get(name).copied();
+        if let Some(retryable) = retryable {
+            return retryable;
+        }
+        if self.init.capabilities.tools.is_none() {
+            return false;
+        }
+        // The annotations of a tool that has not been listed yet are fetched before calling it.
+        // If they cannot be fetched, the tool is treated as not retryable.
+        let Ok(tools) = self.tools_list_all().try_collect::<Vec<Tool>>().await else {
+            return false;
+        };
+        let retryable = tools.iter().any(|t| t.name == name && is_retryable_tool(t));
+        self.retryable_tools
+            .lock()
+            .unwrap()
+            .entry(name.to_string())
+            .or_insert(retryable);
+        retryable
+    }
+
+    fn check_capability(&self, method: &str) -> SessionResult<()> {
+        let c = &self.init.capabilities;
+        let capability = method.split('/').next().unwrap_or_default();
+        let is_supported = match capability {
+            "prompts" => c.prompts.is_some(),
+            "resources" => c.resources.is_some(),
+            "tools" => c.tools.is_some(),
+            _ => true,
+        };
+        if is_supported {
+            Ok(())
+        } else {
+            Err(CapabilityNotSupported::new(method, capability).into())
+        }
+    }
+
+    async fn request<T: DeserializeOwned + Send + Sync + 'static>(
+        &self,
+        method: &str,
+        params: Option<&impl Serialize>,
+    ) -> SessionResult<T> {
+        self.request_with_timeout(method, params, self.requests.timeout)
+            .await
+    }
+    async fn request_with_timeout<T: DeserializeOwned + Send + Sync + 'static>(
+        &self,
+        method: &str,
+        params: Option<&impl Serialize>,
+        timeout: Option<Duration>,
+    ) -> SessionResult<T> {
+        self.check_capability(method)?;
+        let request = self.session.request(method, params);
+        let Some(timeout) = timeout else {
+            return request.await;
+        };
+        // Dropping the request sends `notifications/cancelled` through `McpCancellationHook`.
+        tokio::time::timeout(timeout, request)
+            .await
+            .unwrap_or_else(|_| Err(RequestTimeout::new(method, timeout).into()))
+    }
+
+    /// Calls [`prompts/list`] repeatedly and returns all prompts as a stream
+    ///
+    /// Pages are requested lazily by following `next_cursor`.
+    /// If the server returns a cursor that has already been returned, the stream ends with an error.
+    ///
+    /// [`prompts/list`]: https://modelcontextprotocol.io/specification/2025-06-18/client/prompts#listing-prompts
+    pub fn prompts_list_all(&self) -> impl Stream<Item = SessionResult<Prompt>> + Send + '_ {
+        self.paginate("prompts/list", |r: ListPromptsResult| {
+            (r.prompts, r.next_cursor)
+        })
+    }
+
+    /// Calls [`resources/list`] repeatedly and returns all resources as a stream
+    ///
+    /// See [`prompts_list_all`](Self::prompts_list_all) for how pages are followed.
+    ///
+    /// [`resources/list`]: https://modelcontextprotocol.io/specification/2025-06-18/client/resources#listing-resources
+    pub fn resources_list_all(&self) -> impl Stream<Item = SessionResult<Resource>> + Send + '_ {
+        self.paginate("resources/list", |r: ListResourcesResult| {
+            (r.resources, r.next_cursor)
+        })
+    }
+
+    /// Calls [`resources/templates/list`] repeatedly and returns all resource templates as a stream
+    ///
+    /// See [`prompts_list_all`](Self::prompts_list_all) for how pages are followed.
+    ///
+    /// [`resources/templates/list`]: https://modelcontextprotocol.io/specification/2025-06-18/client/resources#listing-resource-templates
+    pub fn resources_templates_list_all(
+        &self,
+    ) -> impl Stream<Item = SessionResult<ResourceTemplate>> + Send + '_ {
+        self.paginate(
+            "resources/templates/list",
+            |r: ListResourceTemplatesResult| (r.resource_templates, r.next_cursor),
+        )
+    }
+
+    /// Calls [`tools/list`] repeatedly and returns all tools as a stream
+    ///
+    /// See [`prompts_list_all`](Self::prompts_list_all) for how pages are followed.
+    ///
+    /// [`tools/list`]: https://modelcontextprotocol.io/specification/2025-06-18/client/tools#listing-tools
+    pub fn tools_list_all(&self) -> impl Stream<Item = SessionResult<Tool>> + Send + '_ {
+        self.paginate("tools/list", |r: ListToolsResult| (r.tools, r.next_cursor))
+            .inspect_ok(|tool| self.record_tool(tool))
+    }
+
+    fn paginate<R, T>(
+        &self,
+        method: &'static str,
+        split: fn(R) -> (Vec<T>, Option<String>),
+    ) -> impl Stream<Item = SessionResult<T>> + Send + '_
+    where
+        R: DeserializeOwned + Send + Sync + 'static,
+        T: Send + 'static,
+    {
+        struct State {
+            cursor: Option<String>,
+            seen: HashSet<String>,
+            is_end: bool,
+            is_repeated: bool,
+        }
+        let state = State {
+            cursor: None,
+            seen: HashSet::new(),
+            is_end: false,
+            is_repeated: false,
+        };
+        stream::try_unfold(state, move |mut state| async move {
+            if state.is_repeated {
+                let e = Error::new(ErrorCode::INTERNAL_ERROR).with_message(
+                    format!("`{method}` returned the same cursor more than once"),
+                    true,
+                );
+                return Err(to_session_error(e));
+            }
+            if state.is_end {
+                return Ok(None);
+            }
+            let p = PaginatedRequestParams {
+                cursor: state.cursor.take(),
+            };
+            let r: R = self.request(method, Some(&p)).await?;
+            let (items, next_cursor) = split(r);
+            match next_cursor {
+                Some(c) if !state.seen.insert(c.clone()) => state.is_repeated = true,
+                Some(c) => state.cursor = Some(c),
+                None => state.is_end = true,
+            }
+            Ok(Some((
+                stream::iter(items).map(Ok::<T, SessionError>),
+                state,
+            )))
+        })
+        .try_flatten()
+    }
+
+    /// Calls [`tools/call`] with typed arguments and result
+    ///
+    /// `args` is serialized into the `arguments` object of the request.
+    /// The result is deserialized from `structured_content`, or from the first text content parsed as JSON if `structured_content` is empty.
+    /// If the tool returns a result with `is_error` set, [`TypedCallError::Tool`] is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use mcp_attr::client::McpClient;
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Args {
+    ///     text: String,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Output {
+    ///     count: usize,
+    /// }
+    ///
+    /// # async fn example(client: McpClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let args = Args { text: "abc".to_string() };
+    /// let output: Output = client.call_tool_typed("char_count", &args).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`tools/call`]: https://modelcontextprotocol.io/specification/2025-06-18/client/tools#calling-tools
+    pub async fn call_tool_typed<Args, Out>(
+        &self,
+        name: &str,
+        args: &Args,
+    ) -> std::result::Result<Out, TypedCallError>
+    where
+        Args: Serialize + ?Sized,
+        Out: DeserializeOwned,
+    {
+        let arguments = match serde_json::to_value(args).map_err(TypedCallError::Json)? {
+            Value::Object(arguments) => Some(arguments),
+            Value::Null => None,
+            _ => return Err(TypedCallError::ArgumentsNotObject),
+        };
+        let r = self.call_tool_checked(name, arguments).await?;
+        decode_tool_result(r)
+    }
+    pub(crate) async fn call_tool_checked(
+        &self,
+        name: &str,
+        arguments: Option<Map<String, Value>>,
+    ) -> std::result::Result<CallToolResult, TypedCallError> {
+        let r = self
+            .tools_call(CallToolRequestParams {
+                name: name.to_string(),
+                arguments,
+                ..Default::default()
+            })
+            .await?;
+        if r.is_error == Some(true) {
+            return Err(TypedCallError::Tool(r));
+        }
+        Ok(r)
+    }
+
+    /// Calls [`resources/read`] and deserializes the first text content as JSON
+    ///
+    /// [`resources/read`]: https://modelcontextprotocol.io/specification/2025-06-18/client/resources#reading-a-resource
+    pub async fn read_resource_typed<Out: DeserializeOwned>(
+        &self,
+        uri: &str,
+    ) -> std::result::Result<Out, TypedCallError> {
+        let r = self
+            .resources_read(ReadResourceRequestParams::new(uri))
+            .await?;
+        let value = if let Some(text) = r.contents.iter().find_map(|c| match c {
+            ReadResourceResultContentsItem::TextResourceContents(t) => Some(&t.text),
+            _ => None,
+        }) {
+            serde_json::from_str(text).map_err(TypedCallError::Json)?
+        } else {
+            Value::Null
+        };
+        serde_json::from_value(value).map_err(TypedCallError::Json)
     }
 
     /// Calls [`completion/complete`]
@@ -437,18 +1022,252 @@ impl McpClient {
         &self,
         params: CompleteRequestParams,
     ) -> SessionResult<CompleteResult> {
-        self.session
-            .request("completion/complete", Some(&params))
+        self.completion_complete_with(params, &CallOptions::new())
+            .await
+    }
+
+    /// Calls [`completion/complete`] with the specified timeout
+    ///
+    /// [`completion/complete`]: https://modelcontextprotocol.io/specification/2025-06-18/client/completion#completing-a-prompt
+    pub async fn completion_complete_with(
+        &self,
+        params: CompleteRequestParams,
+        options: &CallOptions,
+    ) -> SessionResult<CompleteResult> {
+        self.request_with_timeout("completion/complete", Some(&params), options.timeout(self))
             .await
     }
     /// Calls [`ping`]
     ///
     /// [`ping`]: https://modelcontextprotocol.io/specification/2025-06-18/basic/utilities/ping
     pub async fn ping(&self) -> SessionResult<()> {
+        self.ping_with(&CallOptions::new()).await
+    }
+
+    /// Calls [`ping`] with the specified timeout
+    ///
+    /// [`ping`]: https://modelcontextprotocol.io/specification/2025-06-18/basic/utilities/ping
+    pub async fn ping_with(&self, options: &CallOptions) -> SessionResult<()> {
         let _: Empty = self
-            .session
-            .request("ping", Some(&PingRequestParams::default()))
+            .request_with_timeout(
+                "ping",
+                Some(&PingRequestParams::default()),
+                options.timeout(self),
+            )
             .await?;
         Ok(())
     }
+
+    /// Sends requests as a single [JSON-RPC batch] and returns their results in the same order
+    ///
+    /// Batches are defined only in protocol version 2025-03-26, so this fails for sessions that negotiated another version.
+    /// Clients that do not use a byte stream transport, such as those built with [`McpClientBuilder::build_with_server`], send the requests one by one.
+    /// The timeout set by [`McpClientBuilder::with_timeout`] applies to each request in the batch.
+    ///
+    /// [JSON-RPC batch]: https://modelcontextprotocol.io/specification/2025-03-26/basic#batching
+    pub async fn batch(
+        &self,
+        requests: Vec<BatchRequest>,
+    ) -> SessionResult<Vec<SessionResult<Value>>> {
+        if !is_batch_supported(self.protocol_version) {
+            let e = Error::new(ErrorCode::INVALID_REQUEST).with_message(
+                format!(
+                    "JSON-RPC batches are not supported in protocol version {}",
+                    self.protocol_version
+                ),
+                true,
+            );
+            return Err(to_session_error(e));
+        }
+        for r in &requests {
+            self.check_capability(&r.method)?;
+        }
+        if requests.is_empty() {
+            return Ok(Vec::new());
+        }
+        let _batch = self.batcher.as_ref().map(|b| {
+            b.collect(
+                requests
+                    .iter()
+                    .map(|r| (r.method.clone(), r.params.clone()))
+                    .collect(),
+            )
+        });
+        let results = requests
+            .iter()
+            .map(|r| self.request::<Value>(&r.method, r.params.as_ref()));
+        Ok(futures::future::join_all(results).await)
+    }
+}
+
+fn is_retryable_error(e: &SessionError) -> bool {
+    match e.kind() {
+        SessionErrorKind::ErrorObject => RequestTimeout::from_error(e).is_some(),
+        SessionErrorKind::Shutdown => true,
+        _ => std::error::Error::source(e).is_some_and(|s| s.is::<io::Error>()),
+    }
+}
+
+pub(crate) fn decode_tool_result<Out: DeserializeOwned>(
+    r: CallToolResult,
+) -> std::result::Result<Out, TypedCallError> {
+    let value = if !r.structured_content.is_empty() {
+        Value::Object(r.structured_content)
+    } else if let Some(text) = r.content.iter().find_map(|c| match c {
+        ContentBlock::TextContent(t) => Some(&t.text),
+        _ => None,
+    }) {
+        serde_json::from_str(text).map_err(TypedCallError::Json)?
+    } else {
+        Value::Null
+    };
+    serde_json::from_value(value).map_err(TypedCallError::Json)
+}
+
+/// Error indicating that the server responded to the [`initialize`] request with a protocol version that the client does not support
+///
+/// Converted into an error with code [`CODE`](Self::CODE) whose `data` contains the version and the versions supported by the client.
+/// Use [`from_error`](Self::from_error) to get it back from a [`SessionError`].
+///
+/// [`initialize`]: https://modelcontextprotocol.io/specification/2025-06-18/basic/lifecycle#version-negotiation
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsupportedProtocolVersion {
+    /// Protocol version returned by the server
+    pub version: String,
+    /// Protocol versions supported by the client
+    pub supported: Vec<String>,
+}
+impl UnsupportedProtocolVersion {
+    /// Error code used for `UnsupportedProtocolVersion`
+    ///
+    /// An implementation-defined code from the JSON-RPC server error range,
+    /// so that a failed version negotiation can be told apart from other errors of the `initialize` request.
+    pub const CODE: ErrorCode = ErrorCode(-32004);
+
+    fn new(version: &str, supported: &[ProtocolVersion]) -> Self {
+        Self {
+            version: version.to_string(),
+            supported: supported.iter().map(|v| v.to_string()).collect(),
+        }
+    }
+
+    /// Gets `UnsupportedProtocolVersion` from an error returned by the methods that build [`McpClient`]
+    pub fn from_error(e: &SessionError) -> Option<Self> {
+        let o = e.error_object()?;
+        if o.code != Self::CODE {
+            return None;
+        }
+        let data = o.data.as_ref()?.get("unsupportedProtocolVersion")?;
+        serde_json::from_value(data.clone()).ok()
+    }
+}
+impl fmt::Display for UnsupportedProtocolVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "server responded with unsupported protocol version `{}` (supported: {})",
+            self.version,
+            self.supported.join(", ")
+        )
+    }
+}
+impl From<UnsupportedProtocolVersion> for Error {
+    fn from(e: UnsupportedProtocolVersion) -> Self {
+        ErrorObject {
+            code: UnsupportedProtocolVersion::CODE,
+            message: e.to_string(),
+            data: Some(json!({ "unsupportedProtocolVersion": e })),
+        }
+        .into()
+    }
+}
+impl From<UnsupportedProtocolVersion> for SessionError {
+    fn from(e: UnsupportedProtocolVersion) -> Self {
+        to_session_error(e.into())
+    }
+}
+
+/// Error returned by [`McpClient::call_tool_typed`] and [`McpClient::read_resource_typed`]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TypedCallError {
+    /// The request failed with a JSON-RPC error or the session was closed
+    Session(SessionError),
+    /// The tool returned a result with `is_error` set
+    Tool(CallToolResult),
+    /// The arguments were not serialized into a JSON object
+    ArgumentsNotObject,
+    /// The arguments could not be serialized or the result could not be deserialized
+    Json(serde_json::Error),
+}
+impl TypedCallError {
+    /// Gets the text contents of the result returned by a tool that failed
+    pub fn tool_error_message(&self) -> Option<String> {
+        let Self::Tool(r) = self else {
+            return None;
+        };
+        let texts: Vec<&str> = r
+            .content
+            .iter()
+            .filter_map(|c| match c {
+                ContentBlock::TextContent(t) => Some(t.text.as_str()),
+                _ => None,
+            })
+            .collect();
+        Some(texts.join("\n"))
+    }
+}
+impl fmt::Display for TypedCallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Session(e) => write!(f, "{e}"),
+            Self::Tool(_) => write!(
+                f,
+                "tool returned an error: {}",
+                self.tool_error_message().unwrap_or_default()
+            ),
+            Self::ArgumentsNotObject => write!(f, "arguments must be serialized as a JSON object"),
+            Self::Json(e) => write!(f, "{e}"),
+        }
+    }
+}
+impl std::error::Error for TypedCallError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Session(e) => Some(e),
+            Self::Json(e) => Some(e),
+            Self::Tool(_) | Self::ArgumentsNotObject => None,
+        }
+    }
+}
+impl From<SessionError> for TypedCallError {
+    fn from(e: SessionError) -> Self {
+        Self::Session(e)
+    }
+}
+
+/// Request sent by [`McpClient::batch`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchRequest {
+    /// Method name
+    pub method: String,
+    /// Parameters of the request
+    pub params: Option<Value>,
+}
+impl BatchRequest {
+    /// Creates a request without parameters
+    pub fn new(method: &str) -> Self {
+        Self {
+            method: method.to_string(),
+            params: None,
+        }
+    }
+
+    /// Sets the parameters of the request
+    pub fn with_params(self, params: impl Serialize) -> serde_json::Result<Self> {
+        Ok(Self {
+            params: Some(serde_json::to_value(params)?),
+            ..self
+        })
+    }
 }
@@ -0,0 +1,104 @@
+use std::{io, process::Stdio, time::Duration};
+
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream},
+    process::{Child, ChildStdout, Command},
+    sync::oneshot,
+};
+
+const STDIN_BUFFER_SIZE: usize = 64 * 1024;
+
+/// How to handle the standard error of an MCP server process launched by [`McpClientBuilder::build_with_command`](super::McpClientBuilder::build_with_command)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StderrMode {
+    /// Inherits the standard error of the current process
+    #[default]
+    Inherit,
+    /// Emits each line as a `tracing` event with the target `mcp_attr::client::stderr`
+    Tracing,
+    /// Discards the output
+    Null,
+}
+
+pub(crate) struct ChildProcess {
+    pub(crate) child: Child,
+    close_stdin: Option<oneshot::Sender<()>>,
+}
+impl ChildProcess {
+    pub(crate) fn spawn(
+        command: &mut Command,
+        stderr: StderrMode,
+    ) -> io::Result<(Self, BufReader<ChildStdout>, DuplexStream)> {
+        command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(match stderr {
+                StderrMode::Inherit => Stdio::inherit(),
+                StderrMode::Tracing => Stdio::piped(),
+                StderrMode::Null => Stdio::null(),
+            })
+            .kill_on_drop(true);
+        let mut child = command.spawn()?;
+        let (Some(mut stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(io::Error::other(
+                "failed to open stdio of the child process",
+            ));
+        };
+        if let Some(stderr) = child.stderr.take() {
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    tracing::info!(target: "mcp_attr::client::stderr", "{line}");
+                }
+            });
+        }
+
+        // Writes go through a pipe so that stdin can be closed while the session is still alive.
+        let (writer, mut reader) = tokio::io::duplex(STDIN_BUFFER_SIZE);
+        let (close_stdin, closed) = oneshot::channel();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = tokio::io::copy(&mut reader, &mut stdin) => {}
+                _ = closed => {}
+            }
+            let _ = stdin.shutdown().await;
+        });
+        let process = Self {
+            child,
+            close_stdin: Some(close_stdin),
+        };
+        Ok((process, BufReader::new(stdout), writer))
+    }
+
+    pub(crate) async fn shutdown(
+        &mut self,
+        timeout: Duration,
+    ) -> io::Result<std::process::ExitStatus> {
+        if let Some(close_stdin) = self.close_stdin.take() {
+            let _ = close_stdin.send(());
+        }
+        if let Ok(status) = tokio::time::timeout(timeout, self.child.wait()).await {
+            return status;
+        }
+        self.child.kill().await?;
+        self.child.wait().await
+    }
+}
+
+/// Copies the program, arguments, environment variables and working directory of `command`
+pub(crate) fn clone_command(command: &Command) -> Command {
+    let source = command.as_std();
+    let mut c = Command::new(source.get_program());
+    c.args(source.get_args());
+    for (key, value) in source.get_envs() {
+        match value {
+            Some(value) => c.env(key, value),
+            None => c.env_remove(key),
+        };
+    }
+    if let Some(dir) = source.get_current_dir() {
+        c.current_dir(dir);
+    }
+    c
+}
@@ -0,0 +1,166 @@
+use std::{fmt, time::Duration};
+
+use derive_ex::Ex;
+use jsoncall::{Error, ErrorCode, ErrorObject, SessionError};
+use serde_json::json;
+
+use crate::{client::McpClient, common::to_session_error, schema::Tool};
+
+/// Policy for retrying [`tools/call`] requests
+///
+/// Retries are performed only for tools whose [`ToolAnnotations`](crate::schema::ToolAnnotations) have `idempotent_hint` or `read_only_hint` set.
+/// The annotations are taken from the results of [`McpClient::tools_list`](super::McpClient::tools_list) and [`McpClient::tools_list_all`](super::McpClient::tools_list_all).
+/// If the tool has not been listed yet, the tool list is fetched before the first call.
+/// If the server does not support tools, the tool list cannot be fetched or the tool is not in it, the tool is not retried.
+/// Requests that time out ([`RequestTimeout`]) or fail because of a transport error are retried.
+/// Errors returned by the server are not retried.
+///
+/// [`tools/call`]: https://modelcontextprotocol.io/specification/2025-06-18/client/tools#calling-tools
+#[derive(Clone, Debug, Ex)]
+#[derive_ex(Default)]
+#[default(Self::new())]
+pub struct RetryPolicy {
+    pub(crate) max_retries: u32,
+    pub(crate) initial_backoff: Duration,
+    pub(crate) max_backoff: Duration,
+}
+impl RetryPolicy {
+    /// Creates a policy that retries up to 3 times, waiting 100ms before the first retry and doubling the wait up to 5s
+    pub fn new() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+
+    /// Sets the maximum number of retries
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the wait time before the first retry
+    pub fn with_initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Sets the upper limit of the wait time between retries
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+}
+
+/// Options for a single request sent by the `_with` methods of [`McpClient`]
+///
+/// Values not set here fall back to the values set in [`McpClientBuilder`](super::McpClientBuilder).
+/// The retry policy is used only by [`McpClient::tools_call_with`].
+///
+/// [`tools/call`]: https://modelcontextprotocol.io/specification/2025-06-18/client/tools#calling-tools
+#[derive(Clone, Debug, Default)]
+pub struct CallOptions {
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) retry: Option<RetryPolicy>,
+}
+impl CallOptions {
+    /// Creates options that use the values of the client
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the time to wait for the response
+    ///
+    /// When the time expires, `notifications/cancelled` is sent to the server.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the policy for retrying the request
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    pub(crate) fn timeout(&self, client: &McpClient) -> Option<Duration> {
+        self.timeout.or(client.requests.timeout)
+    }
+}
+
+/// Error indicating that no response was received within the timeout set by [`McpClientBuilder::with_timeout`](super::McpClientBuilder::with_timeout) or [`CallOptions::with_timeout`]
+///
+/// Converted into an error with code [`CODE`](Self::CODE) whose `data` contains the method and the timeout.
+/// Use [`from_error`](Self::from_error) to get it back from a [`SessionError`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestTimeout {
+    /// Method of the request that timed out
+    pub method: String,
+    /// Time waited for the response
+    pub timeout: Duration,
+}
+impl RequestTimeout {
+    /// Error code used for `RequestTimeout`
+    ///
+    /// An implementation-defined code from the JSON-RPC server error range, distinct from `INTERNAL_ERROR`
+    /// so that a request that timed out can be told apart from a request that failed on the server.
+    pub const CODE: ErrorCode = ErrorCode(-32001);
+
+    pub(crate) fn new(method: &str, timeout: Duration) -> Self {
+        Self {
+            method: method.to_string(),
+            timeout,
+        }
+    }
+
+    /// Gets `RequestTimeout` from an error returned by [`McpClient`]
+    pub fn from_error(e: &SessionError) -> Option<Self> {
+        let o = e.error_object()?;
+        if o.code != Self::CODE {
+            return None;
+        }
+        let data = o.data.as_ref()?.get("requestTimeout")?;
+        Some(Self {
+            method: data.get("method")?.as_str()?.to_string(),
+            timeout: Duration::from_millis(data.get("timeoutMs")?.as_u64()?),
+        })
+    }
+}
+impl fmt::Display for RequestTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` timed out after {:?}", self.method, self.timeout)
+    }
+}
+impl From<RequestTimeout> for Error {
+    fn from(e: RequestTimeout) -> Self {
+        ErrorObject {
+            code: RequestTimeout::CODE,
+            message: e.to_string(),
+            data: Some(json!({
+                "requestTimeout": {
+                    "method": e.method,
+                    "timeoutMs": u64::try_from(e.timeout.as_millis()).unwrap_or(u64::MAX),
+                }
+            })),
+        }
+        .into()
+    }
+}
+impl From<RequestTimeout> for SessionError {
+    fn from(e: RequestTimeout) -> Self {
+        to_session_error(e.into())
+    }
+}
+
+pub(crate) fn is_retryable_tool(tool: &Tool) -> bool {
+    tool.annotations
+        .as_ref()
+        .is_some_and(|a| a.idempotent_hint == Some(true) || a.read_only_hint == Some(true))
+}
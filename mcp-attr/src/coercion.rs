@@ -0,0 +1,173 @@
+//! Schema-guided coercion of tool arguments generated by language models
+
+use serde_json::{Map, Value};
+
+use crate::{
+    redaction::{REDACTED, is_write_only},
+    schema::ToolInputSchema,
+    server::errors::{argument_pointer, pointer_token},
+};
+
+/// Fixes arguments of `tools/call` that do not match the JSON type in the input schema of the tool
+///
+/// Each fix is recorded with `tracing`.
+pub(crate) fn coerce_arguments(arguments: &mut Map<String, Value>, input_schema: &ToolInputSchema) {
+    let Ok(root) = serde_json::to_value(input_schema) else {
+        return;
+    };
+    for (name, value) in arguments.iter_mut() {
+        if let Some(schema) = root.get("properties").and_then(|p| p.get(name)) {
+            coerce(value, schema, &root, &argument_pointer(name), false);
+        }
+    }
+}
+
+/// Fixes `value` and its descendants that do not match the JSON type in `schema`
+///
+/// The following mismatches are fixed:
+///
+/// - A string containing a number where an integer or a number is expected
+/// - `"true"` or `"false"` where a boolean is expected
+/// - A string containing JSON where an object or an array is expected
+///
+/// `$ref` in `schema` is resolved as a JSON Pointer in `root`.
+///
+/// Values are not recorded if `secret` is `true` or the schema has `writeOnly`.
+pub(crate) fn coerce(value: &mut Value, schema: &Value, root: &Value, pointer: &str, secret: bool) {
+    let secret = secret || is_write_only(schema, root);
+    let schema = resolve(schema, root);
+    let types = schema_types(schema, root);
+    if !types.is_empty() && !types.iter().any(|ty| is_type(value, ty)) {
+        if let Some(new_value) = coerce_string(value, &types) {
+            if secret {
+                tracing::info!(%pointer, from = REDACTED, to = REDACTED, "coerced tool argument");
+            } else {
+                tracing::info!(%pointer, from = %value, to = %new_value, "coerced tool argument");
+            }
+            *value = new_value;
+        }
+    }
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if let Some(schema) = property_schema(schema, root, key) {
+                    let pointer = format!("{pointer}/{}", pointer_token(key));
+                    coerce(value, schema, root, &pointer, secret);
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(schema) = items_schema(schema, root) {
+                for (index, value) in items.iter_mut().enumerate() {
+                    coerce(value, schema, root, &format!("{pointer}/{index}"), secret);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn coerce_string(value: &Value, types: &[&str]) -> Option<Value> {
+    let Value::String(s) = value else {
+        return None;
+    };
+    let s = s.trim();
+    for ty in types {
+        let new_value = match *ty {
+            "integer" => s
+                .parse::<i64>()
+                .map(Value::from)
+                .or_else(|_| s.parse::<u64>().map(Value::from))
+                .ok(),
+            "number" => s
+                .parse::<f64>()
+                .ok()
+                .and_then(serde_json::Number::from_f64)
+                .map(Value::Number),
+            "boolean" => match s {
+                "true" => Some(Value::Bool(true)),
+                "false" => Some(Value::Bool(false)),
+                _ => None,
+            },
+            "object" | "array" => serde_json::from_str::<Value>(s)
+                .ok()
+                .filter(|v| is_type(v, ty)),
+            _ => None,
+        };
+        if new_value.is_some() {
+            return new_value;
+        }
+    }
+    None
+}
+
+fn is_type(value: &Value, ty: &str) -> bool {
+    match ty {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => false,
+    }
+}
+
+/// Follows `$ref` of `schema`
+pub(crate) fn resolve<'a>(mut schema: &'a Value, root: &'a Value) -> &'a Value {
+    for _ in 0..32 {
+        let Some(target) = schema
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|r| r.strip_prefix('#'))
+            .and_then(|p| root.pointer(p))
+        else {
+            break;
+        };
+        schema = target;
+    }
+    schema
+}
+
+/// Returns the JSON types allowed by `schema`, including those of `anyOf` and `oneOf` branches
+fn schema_types<'a>(schema: &'a Value, root: &'a Value) -> Vec<&'a str> {
+    let schema = resolve(schema, root);
+    let mut types = Vec::new();
+    match schema.get("type") {
+        Some(Value::String(ty)) => types.push(ty.as_str()),
+        Some(Value::Array(tys)) => types.extend(tys.iter().filter_map(Value::as_str)),
+        _ => {}
+    }
+    for branch in branches(schema) {
+        types.extend(schema_types(branch, root));
+    }
+    types
+}
+
+pub(crate) fn branches(schema: &Value) -> impl Iterator<Item = &Value> {
+    ["anyOf", "oneOf", "allOf"]
+        .into_iter()
+        .filter_map(|key| schema.get(key)?.as_array())
+        .flatten()
+}
+
+pub(crate) fn property_schema<'a>(
+    schema: &'a Value,
+    root: &'a Value,
+    key: &str,
+) -> Option<&'a Value> {
+    let schema = resolve(schema, root);
+    if let Some(s) = schema.get("properties").and_then(|p| p.get(key)) {
+        return Some(s);
+    }
+    branches(schema).find_map(|b| property_schema(b, root, key))
+}
+
+pub(crate) fn items_schema<'a>(schema: &'a Value, root: &'a Value) -> Option<&'a Value> {
+    let schema = resolve(schema, root);
+    if let Some(s) = schema.get("items").filter(|s| s.is_object()) {
+        return Some(s);
+    }
+    branches(schema).find_map(|b| items_schema(b, root))
+}
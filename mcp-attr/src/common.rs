@@ -1,7 +1,16 @@
-use jsoncall::{Hook, RequestId, SessionContext};
+use std::fmt;
+
+use jsoncall::{Error, ErrorCode, ErrorObject, Hook, RequestId, SessionContext, SessionError};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
 
 use crate::schema::CancelledNotificationParams;
 
+/// Converts an error raised on this side of the session into a [`SessionError`]
+pub(crate) fn to_session_error(e: Error) -> SessionError {
+    e.to_error_object(true).into()
+}
+
 pub(crate) struct McpCancellationHook;
 impl Hook for McpCancellationHook {
     fn cancel_outgoing_request(&self, id: RequestId, session: &SessionContext) {
@@ -16,3 +25,63 @@ impl Hook for McpCancellationHook {
             .unwrap()
     }
 }
+
+/// Error indicating that a request was not sent because the peer did not advertise the required capability
+///
+/// Converted into an error with code [`CODE`](Self::CODE) whose `data` contains the method and the capability.
+/// Use [`from_error`](Self::from_error) to get it back from a [`SessionError`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapabilityNotSupported {
+    /// Method of the request that was not sent
+    pub method: String,
+    /// Name of the capability required by the method (e.g. `tools`, `sampling`)
+    pub capability: String,
+}
+impl CapabilityNotSupported {
+    /// Error code used for `CapabilityNotSupported`
+    ///
+    /// An implementation-defined code from the JSON-RPC server error range, distinct from `METHOD_NOT_FOUND`
+    /// so that a missing capability can be told apart from an unknown method.
+    pub const CODE: ErrorCode = ErrorCode(-32003);
+
+    pub(crate) fn new(method: &str, capability: &str) -> Self {
+        Self {
+            method: method.to_string(),
+            capability: capability.to_string(),
+        }
+    }
+
+    /// Gets `CapabilityNotSupported` from an error returned by [`McpClient`](crate::client::McpClient) or [`RequestContext`](crate::server::RequestContext)
+    pub fn from_error(e: &SessionError) -> Option<Self> {
+        let o = e.error_object()?;
+        if o.code != Self::CODE {
+            return None;
+        }
+        let data = o.data.as_ref()?.get("capabilityNotSupported")?;
+        serde_json::from_value(data.clone()).ok()
+    }
+}
+impl fmt::Display for CapabilityNotSupported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` requires the `{}` capability, which the peer did not advertise",
+            self.method, self.capability
+        )
+    }
+}
+impl From<CapabilityNotSupported> for Error {
+    fn from(e: CapabilityNotSupported) -> Self {
+        ErrorObject {
+            code: CapabilityNotSupported::CODE,
+            message: e.to_string(),
+            data: Some(json!({ "capabilityNotSupported": e })),
+        }
+        .into()
+    }
+}
+impl From<CapabilityNotSupported> for SessionError {
+    fn from(e: CapabilityNotSupported) -> Self {
+        to_session_error(e.into())
+    }
+}
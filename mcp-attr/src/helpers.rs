@@ -1,12 +1,40 @@
-use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    str::FromStr,
+    sync::{Arc, LazyLock, Mutex},
+};
 
 use jsoncall::{ErrorCode, bail_public};
-use serde::de::DeserializeOwned;
+use jsonschema::Validator;
+use schemars::JsonSchema;
+use serde::{Serialize, de::DeserializeOwned};
 use serde_json::{Map, Value};
+use serde_path_to_error::Segment;
+use tokio::runtime::{Handle, RuntimeFlavor};
 use uri_template_ex::Captures;
 
-use crate::Result;
+use crate::{
+    Error, Result,
+    client::{McpClient, TypedCallError, decode_tool_result},
+    coercion::{coerce, resolve},
+    redaction::is_redacted_at,
+    schema::{CallToolResult, ContentBlock},
+    server::{
+        ArgConstraints, IntoMcpError, RequestContext, SchemaSettings, ToolError,
+        errors::{
+            ArgumentViolation, argument_pointer, invalid_argument, invalid_arguments, pointer_token,
+        },
+    },
+};
 
+pub use crate::server::{
+    McpServerHandler,
+    locked::{LockedMcpServer, LockedServer},
+};
+pub use jsoncall::Handler;
+pub use tokio::sync::RwLock;
 pub use uri_template_ex;
 
 pub fn parse_prompt_arg_opt<T>(
@@ -20,10 +48,10 @@ where
     if let Some(value) = arguments.get(name) {
         match T::from_str(value) {
             Ok(v) => Ok(Some(v)),
-            Err(e) => bail_public!(
-                ErrorCode::INVALID_PARAMS,
-                "argument `{name}` is invalid ({e})"
-            ),
+            Err(e) => Err(invalid_argument(
+                &argument_pointer(name),
+                format!("argument `{name}` is invalid ({e})"),
+            )),
         }
     } else {
         Ok(None)
@@ -38,7 +66,10 @@ where
     if let Some(value) = parse_prompt_arg_opt(arguments, name)? {
         Ok(value)
     } else {
-        bail_public!(ErrorCode::INVALID_PARAMS, "argument `{name}` is required");
+        Err(invalid_argument(
+            &argument_pointer(name),
+            format!("argument `{name}` is required"),
+        ))
     }
 }
 
@@ -72,23 +103,51 @@ where
     }
 }
 
+/// Returns the schema settings of the session, used to generate the schemas of tool arguments
+pub fn schema_settings(cx: &RequestContext) -> SchemaSettings {
+    cx.schema_settings().clone()
+}
+
+/// Parses the argument `name`
+///
+/// If `secret` is `true`, the value is not included in errors and logs.
+/// Values of `writeOnly` schemas in the schema of `T` are not included either.
 pub fn parse_tool_arg_opt<T>(
     arguments: &Option<Map<String, Value>>,
     name: &str,
+    lenient: bool,
+    secret: bool,
+    settings: &SchemaSettings,
 ) -> Result<Option<T>>
 where
-    T: DeserializeOwned,
+    T: DeserializeOwned + JsonSchema,
 {
     if let Some(arguments) = arguments {
         let value = arguments.get(name);
         if let Some(value) = value {
-            return match serde_json::from_value(value.clone()) {
+            let coerced;
+            let value = if lenient {
+                let mut value = value.clone();
+                let schema = tool_arg_schema::<T>(settings, false)?;
+                coerce(
+                    &mut value,
+                    &schema,
+                    &schema,
+                    &argument_pointer(name),
+                    secret,
+                );
+                coerced = value;
+                &coerced
+            } else {
+                value
+            };
+            return match serde_path_to_error::deserialize(value) {
                 Ok(v) => Ok(Some(v)),
                 Err(e) => {
                     if value.is_null() {
                         Ok(None)
                     } else {
-                        Err(e.into())
+                        Err(tool_arg_error::<T>(name, value, e, secret, settings))
                     }
                 }
             };
@@ -96,13 +155,389 @@ where
     }
     Ok(None)
 }
-pub fn parse_tool_arg<T: DeserializeOwned>(
+pub fn parse_tool_arg<T>(
     arguments: &Option<Map<String, Value>>,
     name: &str,
-) -> Result<T> {
-    if let Some(value) = parse_tool_arg_opt(arguments, name)? {
+    lenient: bool,
+    secret: bool,
+    settings: &SchemaSettings,
+) -> Result<T>
+where
+    T: DeserializeOwned + JsonSchema,
+{
+    if let Some(value) = parse_tool_arg_opt(arguments, name, lenient, secret, settings)? {
         Ok(value)
     } else {
-        bail_public!(ErrorCode::INVALID_PARAMS, "argument `{name}` is required");
+        Err(invalid_argument(
+            &argument_pointer(name),
+            format!(
+                "argument `{name}` is required (expected {})",
+                expected_type::<T>(settings)
+            ),
+        ))
+    }
+}
+
+/// Checks the argument `name` against the constraints given by `#[arg(..)]`
+///
+/// A missing or `null` argument is not checked.
+pub fn check_tool_arg<T: JsonSchema>(
+    arguments: &Option<Map<String, Value>>,
+    name: &str,
+    lenient: bool,
+    secret: bool,
+    constraints: &ArgConstraints,
+    settings: &SchemaSettings,
+) -> Result<()> {
+    let Some(value) = arguments.as_ref().and_then(|a| a.get(name)) else {
+        return Ok(());
+    };
+    if value.is_null() {
+        return Ok(());
+    }
+    let pointer = argument_pointer(name);
+    let schema = tool_arg_schema::<T>(settings, false)?;
+    let mut value = value.clone();
+    if lenient {
+        coerce(&mut value, &schema, &schema, &pointer, secret);
+    }
+    let validator = constraints_validator::<T>(&schema, constraints, settings)?;
+    let violations: Vec<ArgumentViolation> = validator
+        .iter_errors(&value)
+        .map(|e| ArgumentViolation {
+            pointer: format!("{pointer}{}", e.instance_path),
+            message: if secret
+                || is_redacted_at(&value, &schema, &schema, &e.instance_path.to_string())
+            {
+                format!("argument `{name}` violates `{}`", e.schema_path)
+            } else {
+                format!("argument `{name}` is invalid: {e}")
+            },
+        })
+        .collect();
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(invalid_arguments(violations))
+    }
+}
+
+/// Returns the validator of `constraints` for an argument of type `T`, cached for each type, constraints and settings
+fn constraints_validator<T: JsonSchema>(
+    schema: &Value,
+    constraints: &ArgConstraints,
+    settings: &SchemaSettings,
+) -> Result<Arc<Validator>> {
+    type Key = (Cow<'static, str>, String, SchemaSettings);
+    static VALIDATORS: LazyLock<Mutex<HashMap<Key, Arc<Validator>>>> =
+        LazyLock::new(Default::default);
+    let keywords = Value::Object(
+        constraints.to_keywords(resolve(schema, schema).as_object().unwrap_or(&Map::new())),
+    );
+    let key = (T::schema_id(), keywords.to_string(), settings.clone());
+    if let Some(validator) = VALIDATORS.lock().unwrap().get(&key) {
+        return Ok(validator.clone());
+    }
+    let validator = Arc::new(jsonschema::validator_for(&keywords)?);
+    Ok(VALIDATORS
+        .lock()
+        .unwrap()
+        .entry(key)
+        .or_insert(validator)
+        .clone())
+}
+
+pub fn arg_constraint_value<T: Into<Value>>(value: T) -> Value {
+    value.into()
+}
+
+/// Parses the whole `arguments` object as `T` for `#[arg(flatten)]`
+///
+/// Values of `writeOnly` schemas in the schema of `T` are not included in errors and logs.
+pub fn parse_tool_args<T>(
+    arguments: &Option<Map<String, Value>>,
+    lenient: bool,
+    settings: &SchemaSettings,
+) -> Result<T>
+where
+    T: DeserializeOwned + JsonSchema,
+{
+    let schema = tool_arg_schema::<T>(settings, true)?;
+    let mut value = Value::Object(arguments.clone().unwrap_or_default());
+    if lenient {
+        coerce(&mut value, &schema, &schema, "/arguments", false);
+    }
+    serde_path_to_error::deserialize(&value).map_err(|e| {
+        let pointer = path_pointer(e.path());
+        let path = e.path().to_string();
+        let message = if is_redacted_at(&value, &schema, &schema, &pointer) {
+            if path == "." {
+                "arguments are invalid".to_string()
+            } else {
+                format!("arguments are invalid at `{path}`")
+            }
+        } else {
+            let e = e.into_inner();
+            if path == "." {
+                format!("arguments are invalid: {e}")
+            } else {
+                format!("arguments are invalid at `{path}`: {e}")
+            }
+        };
+        invalid_argument(&format!("/arguments{pointer}"), message)
+    })
+}
+
+/// Returns the JSON Pointer of `path`, relative to the deserialized value
+fn path_pointer(path: &serde_path_to_error::Path) -> String {
+    let mut pointer = String::new();
+    for segment in path.iter() {
+        match segment {
+            Segment::Seq { index } => pointer.push_str(&format!("/{index}")),
+            Segment::Map { key } => pointer.push_str(&format!("/{}", pointer_token(key))),
+            Segment::Enum { .. } => {}
+            Segment::Unknown => break,
+        }
+    }
+    pointer
+}
+
+fn tool_arg_error<T: JsonSchema>(
+    name: &str,
+    value: &Value,
+    e: serde_path_to_error::Error<serde_json::Error>,
+    secret: bool,
+    settings: &SchemaSettings,
+) -> Error {
+    let expected = expected_type::<T>(settings);
+    let relative = path_pointer(e.path());
+    let redacted = secret
+        || tool_arg_schema::<T>(settings, false)
+            .is_ok_and(|schema| is_redacted_at(value, &schema, &schema, &relative));
+    if redacted {
+        return invalid_argument(
+            &argument_pointer(name),
+            format!("argument `{name}` is invalid (expected {expected})"),
+        );
+    }
+    let pointer = format!("{}{relative}", argument_pointer(name));
+    let path = e.path().to_string();
+    let e = e.into_inner();
+    let message = if path == "." {
+        format!("argument `{name}` is invalid (expected {expected}): {e}")
+    } else {
+        format!("argument `{name}` is invalid (expected {expected}) at `{path}`: {e}")
+    };
+    invalid_argument(&pointer, message)
+}
+
+/// Returns the schema of `T` as it is published in input schemas, cached for each type and settings
+///
+/// If `flatten` is `true`, returns the input schema of the arguments described by `T` for `#[arg(flatten)]`.
+fn tool_arg_schema<T: JsonSchema>(settings: &SchemaSettings, flatten: bool) -> Result<Arc<Value>> {
+    type Key = (Cow<'static, str>, bool, SchemaSettings);
+    static SCHEMAS: LazyLock<Mutex<HashMap<Key, Arc<Value>>>> = LazyLock::new(Default::default);
+    let key = (T::schema_id(), flatten, settings.clone());
+    if let Some(schema) = SCHEMAS.lock().unwrap().get(&key) {
+        return Ok(schema.clone());
+    }
+    let schema = if flatten {
+        settings.tool_args_schema::<T>()?
+    } else {
+        settings.tool_arg_schema::<T>()?
+    };
+    let schema = Arc::new(schema);
+    Ok(SCHEMAS.lock().unwrap().entry(key).or_insert(schema).clone())
+}
+
+/// Returns a description of the JSON type of `T` such as `integer (uint32)`
+fn expected_type<T: JsonSchema>(settings: &SchemaSettings) -> String {
+    let Ok(root) = tool_arg_schema::<T>(settings, false) else {
+        return T::schema_name();
+    };
+    let schema = resolve(&root, &root);
+    let ty = match schema.get("type") {
+        Some(Value::String(ty)) => ty.clone(),
+        Some(Value::Array(tys)) => tys
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" or "),
+        _ => return T::schema_name(),
+    };
+    match schema.get("format").and_then(Value::as_str) {
+        Some(format) => format!("{ty} ({format})"),
+        None => ty,
+    }
+}
+
+pub fn deny_unknown_tool_args(
+    arguments: &Option<Map<String, Value>>,
+    names: &[&str],
+) -> Result<()> {
+    let Some(arguments) = arguments else {
+        return Ok(());
+    };
+    let violations: Vec<ArgumentViolation> = arguments
+        .keys()
+        .filter(|key| !names.contains(&key.as_str()))
+        .map(|key| ArgumentViolation {
+            pointer: argument_pointer(key),
+            message: format!(
+                "unknown argument `{key}` (expected one of: {})",
+                names.join(", ")
+            ),
+        })
+        .collect();
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(invalid_arguments(violations))
+    }
+}
+
+/// Rejects arguments that are not properties of the struct `T` used with `#[arg(flatten)]`
+pub fn deny_unknown_tool_args_of<T: JsonSchema>(
+    arguments: &Option<Map<String, Value>>,
+    settings: &SchemaSettings,
+) -> Result<()> {
+    let schema = tool_arg_schema::<T>(settings, true)?;
+    let names: Vec<&str> = schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .map(|p| p.keys().map(String::as_str).collect())
+        .unwrap_or_default();
+    deny_unknown_tool_args(arguments, &names)
+}
+
+pub trait IntoToolError {
+    fn into_tool_error(self) -> ToolError;
+}
+impl IntoToolError for ToolError {
+    fn into_tool_error(self) -> ToolError {
+        self
+    }
+}
+impl<E: IntoMcpError> IntoToolError for E {
+    fn into_tool_error(self) -> ToolError {
+        ToolError::Execution(self.into_mcp_error())
+    }
+}
+
+/// Runs `f` on the blocking thread pool, resuming its panic if it panics
+pub async fn spawn_blocking<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(value) => value,
+        Err(e) => match e.try_into_panic() {
+            Ok(payload) => std::panic::resume_unwind(payload),
+            Err(e) => panic!("blocking tool was cancelled: {e}"),
+        },
+    }
+}
+
+/// Runs `f` with `block_in_place` on a multi-thread runtime, or directly otherwise
+pub fn block_in_place<R>(f: impl FnOnce() -> R) -> R {
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(f)
+        }
+        _ => f(),
+    }
+}
+
+pub fn tool_requires_lock(name: &str) -> Error {
+    Error::new(ErrorCode::INTERNAL_ERROR).with_message(
+        format!("Tool `{name}` must be called through `into_handler`"),
+        true,
+    )
+}
+
+/// Returns whether `names` contains `name`
+pub const fn contains_tool_name(names: &[&str], name: &str) -> bool {
+    let mut i = 0;
+    while i < names.len() {
+        if str_eq(names[i], name) {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Returns whether `a` and `b` have a name in common
+pub const fn has_common_tool_name(a: &[&str], b: &[&str]) -> bool {
+    let mut i = 0;
+    while i < a.len() {
+        if contains_tool_name(b, a[i]) {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+pub fn insert_client_tool_arg<T: Serialize + ?Sized>(
+    arguments: &mut Map<String, Value>,
+    name: &str,
+    value: &T,
+) -> std::result::Result<(), TypedCallError> {
+    let value = serde_json::to_value(value).map_err(TypedCallError::Json)?;
+    arguments.insert(name.to_string(), value);
+    Ok(())
+}
+
+pub fn extend_client_tool_args<T: Serialize + ?Sized>(
+    arguments: &mut Map<String, Value>,
+    value: &T,
+) -> std::result::Result<(), TypedCallError> {
+    match serde_json::to_value(value).map_err(TypedCallError::Json)? {
+        Value::Object(map) => arguments.extend(map),
+        _ => return Err(TypedCallError::ArgumentsNotObject),
     }
+    Ok(())
+}
+
+pub async fn client_call_tool(
+    client: &McpClient,
+    name: &str,
+    arguments: Map<String, Value>,
+) -> std::result::Result<CallToolResult, TypedCallError> {
+    client.call_tool_checked(name, Some(arguments)).await
+}
+
+pub fn client_tool_result_text(r: CallToolResult) -> String {
+    let texts: Vec<String> = r
+        .content
+        .into_iter()
+        .filter_map(|c| match c {
+            ContentBlock::TextContent(t) => Some(t.text),
+            _ => None,
+        })
+        .collect();
+    texts.concat()
+}
+
+pub fn client_tool_result_json<T: DeserializeOwned>(
+    r: CallToolResult,
+) -> std::result::Result<T, TypedCallError> {
+    decode_tool_result(r)
 }
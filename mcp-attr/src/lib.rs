@@ -1,7 +1,10 @@
+mod batch;
 pub mod client;
+mod coercion;
 mod common;
 #[doc(hidden)]
 pub mod helpers;
+mod redaction;
 
 /// Types defined in the [MCP protocol schema]
 ///
@@ -14,6 +17,7 @@ pub mod server;
 mod transitivity;
 pub mod utils;
 
+pub use common::CapabilityNotSupported;
 pub use jsoncall;
 pub use jsoncall::{Error, ErrorCode, Result, SessionError, SessionResult, bail, bail_public};
 
@@ -0,0 +1,94 @@
+//! Redaction of tool argument values described by `writeOnly` schemas, such as arguments declared with `#[arg(secret)]`
+
+use serde_json::{Map, Value};
+
+use crate::{
+    coercion::{branches, items_schema, property_schema, resolve},
+    schema::ToolInputSchema,
+};
+
+pub(crate) const REDACTED: &str = "<redacted>";
+
+/// Returns whether `schema` or the schema it refers to is marked as `writeOnly`
+///
+/// A schema whose `anyOf`, `oneOf` or `allOf` branch is marked as `writeOnly` is also treated as `writeOnly`,
+/// so that `Option<T>` of a `writeOnly` type is redacted.
+pub(crate) fn is_write_only(schema: &Value, root: &Value) -> bool {
+    if schema.get("writeOnly") == Some(&Value::Bool(true)) {
+        return true;
+    }
+    let target = resolve(schema, root);
+    if target.get("writeOnly") == Some(&Value::Bool(true)) {
+        return true;
+    }
+    branches(target).any(|b| is_write_only(b, root))
+}
+
+/// Returns a copy of `value` in which values of `writeOnly` schemas are replaced by `"<redacted>"`
+pub(crate) fn redact(value: &Value, schema: &Value, root: &Value) -> Value {
+    if is_write_only(schema, root) {
+        return Value::String(REDACTED.to_string());
+    }
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let value = match property_schema(schema, root, key) {
+                        Some(schema) => redact(value, schema, root),
+                        None => value.clone(),
+                    };
+                    (key.clone(), value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => match items_schema(schema, root) {
+            Some(schema) => Value::Array(items.iter().map(|v| redact(v, schema, root)).collect()),
+            None => value.clone(),
+        },
+        _ => value.clone(),
+    }
+}
+
+/// Returns a copy of the arguments of `tools/call` in which values of `writeOnly` schemas are replaced by `"<redacted>"`
+pub(crate) fn redact_arguments(
+    arguments: &Map<String, Value>,
+    input_schema: &ToolInputSchema,
+) -> Map<String, Value> {
+    let Ok(root) = serde_json::to_value(input_schema) else {
+        return arguments
+            .keys()
+            .map(|key| (key.clone(), Value::String(REDACTED.to_string())))
+            .collect();
+    };
+    match redact(&Value::Object(arguments.clone()), &root, &root) {
+        Value::Object(arguments) => arguments,
+        _ => Map::new(),
+    }
+}
+
+/// Returns whether the value at the JSON Pointer `pointer` in `value` is or contains a value of a `writeOnly` schema
+///
+/// `schema` describes `value`, and `pointer` is relative to `value`.
+pub(crate) fn is_redacted_at(value: &Value, schema: &Value, root: &Value, pointer: &str) -> bool {
+    let mut schema = schema;
+    for token in pointer.split('/').skip(1) {
+        if is_write_only(schema, root) {
+            return true;
+        }
+        let token = token.replace("~1", "/").replace("~0", "~");
+        let next = property_schema(schema, root, &token).or_else(|| {
+            token
+                .parse::<usize>()
+                .ok()
+                .and_then(|_| items_schema(schema, root))
+        });
+        let Some(next) = next else {
+            return false;
+        };
+        schema = next;
+    }
+    let Some(value) = value.pointer(pointer) else {
+        return is_write_only(schema, root);
+    };
+    redact(value, schema, root) != *value
+}
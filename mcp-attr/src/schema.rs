@@ -376,12 +376,15 @@ impl ::std::convert::From<&CallToolRequest> for CallToolRequest {
 ///}
 /// ```
 /// </details>
-#[derive(:: serde :: Deserialize, :: serde :: Serialize, Clone, Debug, PartialEq)]
+#[derive(:: serde :: Deserialize, :: serde :: Serialize, Clone, Default)]
 pub struct CallToolRequestParams {
     #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
     pub arguments:
         ::std::option::Option<::serde_json::Map<::std::string::String, ::serde_json::Value>>,
     pub name: ::std::string::String,
+    ///Input schema of the tool, used to redact values of `writeOnly` schemas from the `Debug` output
+    #[serde(skip)]
+    pub input_schema: ::std::option::Option<::std::sync::Arc<ToolInputSchema>>,
 }
 impl ::std::convert::From<&CallToolRequestParams> for CallToolRequestParams {
     fn from(value: &CallToolRequestParams) -> Self {
@@ -7691,6 +7694,9 @@ pub struct ToolInputSchema {
     pub required: ::std::vec::Vec<::std::string::String>,
     #[serde(rename = "type")]
     pub type_: ::std::string::String,
+    ///Additional keywords of the JSON Schema, such as `$schema` and `$defs`
+    #[serde(flatten)]
+    pub extra: ::serde_json::Map<::std::string::String, ::serde_json::Value>,
 }
 impl ::std::convert::From<&ToolInputSchema> for ToolInputSchema {
     fn from(value: &ToolInputSchema) -> Self {
@@ -1,13 +1,13 @@
 #![allow(missing_docs)]
 
-use jsoncall::{ErrorCode, bail_public};
-use schemars::{JsonSchema, r#gen::SchemaSettings, schema::Metadata};
+use schemars::JsonSchema;
 use serde::Serialize;
-use serde_json::{Value, to_value};
+use serde_json::{Map, to_value};
 use url::Url;
 
 use crate::{
     Result,
+    redaction::redact_arguments,
     schema::{
         Annotations, BlobResourceContents, CallToolRequestParams, CallToolResult,
         CompleteRequestParams, CompleteRequestParamsArgument, CompleteRequestParamsRef,
@@ -19,11 +19,15 @@ use crate::{
         ResourceTemplate, ResourceTemplateReference, Role, Root, TextContent, TextResourceContents,
         Tool, ToolAnnotations, ToolInputSchema,
     },
+    server::{SchemaSettings, ToolInputSchemaBuilder},
     utils::Base64Bytes,
 };
 use std::{
     collections::BTreeMap,
+    fmt,
+    mem::take,
     path::{Path, PathBuf},
+    sync::Arc,
 };
 use std::{fmt::Display, str::FromStr};
 
@@ -288,6 +292,7 @@ impl ToolInputSchema {
             properties: BTreeMap::new(),
             required: vec![],
             type_: "object".to_string(),
+            extra: Map::new(),
         }
     }
     pub fn insert_property<T: JsonSchema>(
@@ -296,29 +301,9 @@ impl ToolInputSchema {
         description: &str,
         required: bool,
     ) -> Result<()> {
-        let mut settings = SchemaSettings::default();
-        settings.inline_subschemas = true;
-        let g = settings.into_generator();
-        let mut root = g.into_root_schema_for::<T>();
-
-        if !description.is_empty() {
-            let metadata = root
-                .schema
-                .metadata
-                .get_or_insert(Box::new(Metadata::default()));
-            metadata.description = Some(description.to_string());
-        }
-        let value = to_value(root.schema)?;
-        let Value::Object(obj) = value else {
-            bail_public!(
-                ErrorCode::INVALID_PARAMS,
-                "schema for `{name}` is not an object"
-            );
-        };
-        self.properties.insert(name.to_string(), obj);
-        if required {
-            self.required.push(name.to_string());
-        }
+        let mut b = ToolInputSchemaBuilder::with_schema(take(self), &SchemaSettings::default());
+        b.insert_property::<T>(name, description, required)?;
+        *self = b.build()?;
         Ok(())
     }
     pub fn with_property<T: JsonSchema>(
@@ -341,6 +326,7 @@ impl CallToolRequestParams {
         CallToolRequestParams {
             name: name.to_string(),
             arguments: None,
+            input_schema: None,
         }
     }
     pub fn with_argument(mut self, name: &str, value: impl Serialize) -> Result<Self> {
@@ -349,6 +335,33 @@ impl CallToolRequestParams {
         self.arguments = Some(arguments);
         Ok(self)
     }
+
+    /// Attaches the input schema of the tool
+    ///
+    /// Values of `writeOnly` schemas, such as arguments declared with `#[arg(secret)]`, are redacted from the `Debug` output.
+    pub fn with_input_schema(mut self, input_schema: Arc<ToolInputSchema>) -> Self {
+        self.input_schema = Some(input_schema);
+        self
+    }
+}
+impl PartialEq for CallToolRequestParams {
+    fn eq(&self, other: &Self) -> bool {
+        self.arguments == other.arguments && self.name == other.name
+    }
+}
+impl fmt::Debug for CallToolRequestParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let arguments = match (&self.arguments, &self.input_schema) {
+            (Some(arguments), Some(input_schema)) => {
+                Some(redact_arguments(arguments, input_schema))
+            }
+            (arguments, _) => arguments.clone(),
+        };
+        f.debug_struct("CallToolRequestParams")
+            .field("arguments", &arguments)
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
 }
 
 impl TextContent {
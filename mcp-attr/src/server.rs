@@ -1,6 +1,10 @@
 //! Module for implementing MCP server
 
-use std::{future::Future, sync::Arc};
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
 
 use jsoncall::{
     ErrorCode, Handler, Hook, NotificationContext, Params, RequestContextAs, RequestId, Response,
@@ -8,8 +12,14 @@ use jsoncall::{
 };
 use serde::{Serialize, de::DeserializeOwned};
 use serde_json::Map;
+use tokio::{
+    io::{AsyncBufRead, AsyncWrite, BufReader, stdin, stdout},
+    sync::watch,
+};
 
 use crate::{
+    CapabilityNotSupported, batch,
+    coercion::coerce_arguments,
     common::McpCancellationHook,
     schema::{
         CallToolRequestParams, CallToolResult, CancelledNotificationParams, ClientCapabilities,
@@ -19,29 +29,52 @@ use crate::{
         ListPromptsResult, ListResourceTemplatesRequestParams, ListResourceTemplatesResult,
         ListResourcesRequestParams, ListResourcesResult, ListRootsRequestParams, ListRootsResult,
         ListToolsRequestParams, ListToolsResult, PingRequestParams, ProgressNotificationParams,
-        ReadResourceRequestParams, ReadResourceResult, Root, ServerCapabilities,
-        ServerCapabilitiesPrompts, ServerCapabilitiesResources, ServerCapabilitiesTools,
+        ReadResourceRequestParams, ReadResourceResult, Root, RootsListChangedNotificationParams,
+        ServerCapabilities, ServerCapabilitiesPrompts, ServerCapabilitiesResources,
+        ServerCapabilitiesTools,
+    },
+    server::{
+        downgrade::Downgrade,
+        errors::{prompt_not_found, resource_not_found, tool_not_found},
+        roots::RootsCache,
+        validation::ToolCache,
     },
-    server::errors::{prompt_not_found, tool_not_found},
     utils::{Empty, ProtocolVersion},
 };
 
 pub mod builder;
+mod downgrade;
 pub mod errors;
+mod input_schema;
+pub(crate) mod locked;
 mod mcp_server_attr;
+mod roots;
+mod validation;
 
 pub use builder::{McpServerBuilder, prompt, resource, route, tool};
-pub use mcp_server_attr::{complete_fn, mcp_server};
+pub use errors::{IntoMcpError, ToolError};
+pub use input_schema::{
+    ArgConstraints, OptionSchema, SchemaDraft, SchemaSettings, ToolInputSchemaBuilder,
+};
+pub use mcp_server_attr::{complete_fn, mcp_server, mcp_tools};
 
 struct SessionData {
     initialize: InitializeRequestParams,
     protocol_version: ProtocolVersion,
+    roots: Mutex<RootsCache>,
+    tools: Mutex<ToolCache>,
+    expose_internals: bool,
+    schema_settings: SchemaSettings,
 }
 
-struct McpServerHandler {
+#[doc(hidden)]
+pub struct McpServerHandler {
     server: Arc<dyn DynMcpServer>,
     data: Option<Arc<SessionData>>,
     is_initialized: bool,
+    expose_internals: bool,
+    schema_settings: SchemaSettings,
+    negotiated: Option<watch::Sender<Option<ProtocolVersion>>>,
 }
 impl Handler for McpServerHandler {
     fn hook(&self) -> Arc<dyn Hook> {
@@ -89,30 +122,50 @@ impl Handler for McpServerHandler {
         match method {
             "notifications/initialized" => cx.handle(self.initialized(params.to_opt()?)),
             "notifications/cancelled" => self.notifications_cancelled(params.to()?, cx),
+            "notifications/roots/list_changed" => {
+                self.notifications_roots_list_changed(params.to_opt()?, cx)
+            }
             _ => cx.method_not_found(),
         }
     }
 }
 impl McpServerHandler {
-    pub fn new(server: impl McpServer) -> Self {
+    fn new(server: impl McpServer, options: &SessionOptions) -> Self {
         Self {
+            schema_settings: server.schema_settings(),
             server: Arc::new(server),
             data: None,
             is_initialized: false,
+            expose_internals: options.expose_internals.unwrap_or(cfg!(debug_assertions)),
+            negotiated: None,
         }
     }
 }
 impl McpServerHandler {
     fn initialize(&mut self, p: InitializeRequestParams) -> Result<InitializeResult> {
-        let protocol_version = p
-            .protocol_version
-            .parse::<ProtocolVersion>()
-            .unwrap_or(ProtocolVersion::LATEST);
+        let supported = self.server.dyn_protocol_versions();
+        let protocol_version = match p.protocol_version.parse::<ProtocolVersion>() {
+            Ok(v) if supported.contains(&v) => v,
+            _ => match supported.iter().max() {
+                Some(v) => *v,
+                None => bail_public!(_, "Server does not support any protocol version"),
+            },
+        };
         self.data = Some(Arc::new(SessionData {
             initialize: p,
             protocol_version,
+            roots: Mutex::new(RootsCache::default()),
+            tools: Mutex::new(ToolCache::default()),
+            expose_internals: self.expose_internals,
+            schema_settings: self.schema_settings.clone(),
         }));
-        Ok(self.server.initialize_result(protocol_version))
+        if let Some(negotiated) = &self.negotiated {
+            negotiated.send_replace(Some(protocol_version));
+        }
+        Ok(self
+            .server
+            .initialize_result(protocol_version)
+            .downgraded(protocol_version))
     }
     fn initialized(&mut self, _p: Option<InitializedNotificationParams>) -> Result<()> {
         if self.data.is_none() {
@@ -135,6 +188,16 @@ impl McpServerHandler {
         cx.session().cancel_incoming_request(&p.request_id, None);
         cx.handle(Ok(()))
     }
+    fn notifications_roots_list_changed(
+        &self,
+        _p: Option<RootsListChangedNotificationParams>,
+        cx: NotificationContext,
+    ) -> Result<Response> {
+        if let Some(data) = &self.data {
+            data.roots.lock().unwrap().clear();
+        }
+        cx.handle(Ok(()))
+    }
 
     // fn logging_set_level(&self, p: SetLevelRequestParams) -> Result<()> {
     //     todo!()
@@ -181,6 +244,8 @@ impl McpServerHandler {
 trait DynMcpServer: Send + Sync + 'static {
     fn initialize_result(&self, protocol_version: ProtocolVersion) -> InitializeResult;
 
+    fn dyn_protocol_versions(&self) -> Vec<ProtocolVersion>;
+
     fn dyn_prompts_list(
         self: Arc<Self>,
         p: ListPromptsRequestParams,
@@ -247,14 +312,22 @@ impl<T: McpServer> DynMcpServer for T {
             server_info: self.server_info(),
         }
     }
+    fn dyn_protocol_versions(&self) -> Vec<ProtocolVersion> {
+        self.protocol_versions()
+    }
     fn dyn_prompts_list(
         self: Arc<Self>,
         p: ListPromptsRequestParams,
         cx: RequestContextAs<ListPromptsResult>,
         data: Arc<SessionData>,
     ) -> Result<Response> {
+        let v = data.protocol_version;
         let mut mcp_cx = RequestContext::new(&cx, data);
-        cx.handle_async(async move { self.prompts_list(p, &mut mcp_cx).await })
+        cx.handle_async(async move {
+            self.prompts_list(p, &mut mcp_cx)
+                .await
+                .map(|r| r.downgraded(v))
+        })
     }
 
     fn dyn_prompts_get(
@@ -263,8 +336,13 @@ impl<T: McpServer> DynMcpServer for T {
         cx: RequestContextAs<GetPromptResult>,
         data: Arc<SessionData>,
     ) -> Result<Response> {
+        let v = data.protocol_version;
         let mut mcp_cx = RequestContext::new(&cx, data);
-        cx.handle_async(async move { self.prompts_get(p, &mut mcp_cx).await })
+        cx.handle_async(async move {
+            self.prompts_get(p, &mut mcp_cx)
+                .await
+                .map(|r| r.downgraded(v))
+        })
     }
 
     fn dyn_resources_list(
@@ -273,8 +351,13 @@ impl<T: McpServer> DynMcpServer for T {
         cx: RequestContextAs<ListResourcesResult>,
         data: Arc<SessionData>,
     ) -> Result<Response> {
+        let v = data.protocol_version;
         let mut mcp_cx = RequestContext::new(&cx, data);
-        cx.handle_async(async move { self.resources_list(p, &mut mcp_cx).await })
+        cx.handle_async(async move {
+            self.resources_list(p, &mut mcp_cx)
+                .await
+                .map(|r| r.downgraded(v))
+        })
     }
 
     fn dyn_resources_templates_list(
@@ -283,8 +366,13 @@ impl<T: McpServer> DynMcpServer for T {
         cx: RequestContextAs<ListResourceTemplatesResult>,
         data: Arc<SessionData>,
     ) -> Result<Response> {
+        let v = data.protocol_version;
         let mut mcp_cx = RequestContext::new(&cx, data);
-        cx.handle_async(async move { self.resources_templates_list(p, &mut mcp_cx).await })
+        cx.handle_async(async move {
+            self.resources_templates_list(p, &mut mcp_cx)
+                .await
+                .map(|r| r.downgraded(v))
+        })
     }
 
     fn dyn_resources_read(
@@ -293,8 +381,13 @@ impl<T: McpServer> DynMcpServer for T {
         cx: RequestContextAs<ReadResourceResult>,
         data: Arc<SessionData>,
     ) -> Result<Response> {
+        let v = data.protocol_version;
         let mut mcp_cx = RequestContext::new(&cx, data);
-        cx.handle_async(async move { self.resources_read(p, &mut mcp_cx).await })
+        cx.handle_async(async move {
+            self.resources_read(p, &mut mcp_cx)
+                .await
+                .map(|r| r.downgraded(v))
+        })
     }
 
     fn dyn_tools_list(
@@ -303,18 +396,48 @@ impl<T: McpServer> DynMcpServer for T {
         cx: RequestContextAs<ListToolsResult>,
         data: Arc<SessionData>,
     ) -> Result<Response> {
+        let v = data.protocol_version;
         let mut mcp_cx = RequestContext::new(&cx, data);
-        cx.handle_async(async move { self.tools_list(p, &mut mcp_cx).await })
+        cx.handle_async(async move {
+            let r = self.tools_list(p, &mut mcp_cx).await?;
+            validation::record_tools(&r.tools, &mcp_cx);
+            Ok(r.downgraded(v))
+        })
     }
 
     fn dyn_tools_call(
         self: Arc<Self>,
-        p: CallToolRequestParams,
+        mut p: CallToolRequestParams,
         cx: RequestContextAs<CallToolResult>,
         data: Arc<SessionData>,
     ) -> Result<Response> {
+        let v = data.protocol_version;
         let mut mcp_cx = RequestContext::new(&cx, data);
-        cx.handle_async(async move { self.tools_call(p, &mut mcp_cx).await })
+        cx.handle_async(async move {
+            let lenient = self.lenient_tool_arguments();
+            let validate = self.validate_tool_arguments();
+            // The input schema is attached to the params to redact secret arguments,
+            // but the tools are looked up only when they are needed to coerce or validate the arguments.
+            let tool = if lenient || validate {
+                validation::compiled_tool(&*self, &p.name, &mut mcp_cx).await?
+            } else {
+                validation::cached_tool(&p.name, &mcp_cx)
+            };
+            if let Some(tool) = tool {
+                if lenient {
+                    if let Some(arguments) = &mut p.arguments {
+                        coerce_arguments(arguments, &tool.input_schema);
+                    }
+                }
+                if validate {
+                    validation::validate_tool_arguments(&tool, &p.arguments)?;
+                }
+                p.input_schema = Some(tool.input_schema.clone());
+            }
+            self.tools_call(p, &mut mcp_cx)
+                .await
+                .map(|r| r.downgraded(v))
+        })
     }
 
     fn dyn_completion_complete(
@@ -344,6 +467,51 @@ pub trait McpServer: Send + Sync + 'static {
         None
     }
 
+    /// Returns the protocol versions supported by this server
+    ///
+    /// If the version requested by the client is not included, the latest version in this list is used.
+    /// The default is [`ProtocolVersion::ALL`].
+    fn protocol_versions(&self) -> Vec<ProtocolVersion> {
+        ProtocolVersion::ALL.to_vec()
+    }
+
+    /// Returns the settings for generating the input schemas of tools defined with `#[tool]`
+    ///
+    /// The default is [`SchemaSettings::default`].
+    fn schema_settings(&self) -> SchemaSettings {
+        SchemaSettings::default()
+    }
+
+    /// Returns whether to validate the arguments of [`tools/call`] against the input schema of the tool
+    ///
+    /// If `true`, arguments are validated before [`tools_call`](Self::tools_call) is called,
+    /// and an `INVALID_PARAMS` error listing every violation with its JSON Pointer is returned if they are invalid.
+    /// The input schema is taken from the result of [`tools_list`](Self::tools_list) returned to the client,
+    /// or from a call to `tools_list` the first time a tool that has not been listed is called in a session.
+    /// It is compiled once and reused until the client receives a different schema for the tool from `tools/list`,
+    /// so servers whose tools change during a session without being listed again should not enable this.
+    /// Tools that are not found are looked up again on each call.
+    /// The default is `false`.
+    ///
+    /// [`tools/call`]: https://modelcontextprotocol.io/specification/2025-06-18/server/tools#calling-tools
+    fn validate_tool_arguments(&self) -> bool {
+        false
+    }
+
+    /// Returns whether to fix arguments of [`tools/call`] that do not match the JSON type in the input schema of the tool
+    ///
+    /// If `true`, strings such as `"5"`, `"true"` and JSON-encoded objects are converted
+    /// to the integers, booleans and objects expected by the input schema before the arguments are validated and [`tools_call`](Self::tools_call) is called.
+    /// Each fix is recorded with `tracing`. The advertised input schema is not changed.
+    /// The default is `false`.
+    ///
+    /// To enable this for a single tool, use `#[tool(lenient)]`.
+    ///
+    /// [`tools/call`]: https://modelcontextprotocol.io/specification/2025-06-18/server/tools#calling-tools
+    fn lenient_tool_arguments(&self) -> bool {
+        false
+    }
+
     /// Returns `capabilities` used in the [`initialize`] request response
     ///
     /// [`initialize`]: https://modelcontextprotocol.io/specification/2025-06-18/basic/lifecycle#initialization
@@ -419,7 +587,7 @@ pub trait McpServer: Send + Sync + 'static {
         p: ReadResourceRequestParams,
         cx: &mut RequestContext,
     ) -> impl Future<Output = Result<ReadResourceResult>> + Send {
-        async move { bail_public!(ErrorCode::INVALID_PARAMS, "Resource `{}` not found", p.uri) }
+        async move { Err(resource_not_found(&p.uri)) }
     }
 
     /// Handles [`tools/list`]
@@ -436,6 +604,9 @@ pub trait McpServer: Send + Sync + 'static {
 
     /// Handles [`tools/call`]
     ///
+    /// If the tool has been returned by [`tools_list`](Self::tools_list) in this session, `p.input_schema` is set to its input schema,
+    /// so values of `writeOnly` schemas are redacted from the `Debug` output of `p`.
+    ///
     /// [`tools/call`]: https://modelcontextprotocol.io/specification/2025-06-18/server/tools#calling-tools
     #[allow(unused_variables)]
     fn tools_call(
@@ -459,11 +630,32 @@ pub trait McpServer: Send + Sync + 'static {
     }
 
     /// Gets the JSON RPC `Handler`
+    ///
+    /// Equivalent to [`into_handler_with`](Self::into_handler_with) with the default [`SessionOptions`].
     fn into_handler(self) -> impl Handler + Send + Sync + 'static
     where
         Self: Sized + Send + Sync + 'static,
     {
-        McpServerHandler::new(self)
+        self.into_handler_with(SessionOptions::default())
+    }
+
+    /// Gets the JSON RPC `Handler` for a session created with `options`
+    ///
+    /// `expose_internals` of `options` also decides whether non-public messages of tool execution errors are sent to the client.
+    /// [`McpClientBuilder::build_with_server`](crate::client::McpClientBuilder::build_with_server) uses this method.
+    fn into_handler_with(self, options: SessionOptions) -> impl Handler + Send + Sync + 'static
+    where
+        Self: Sized + Send + Sync + 'static,
+    {
+        self.into_server_handler(&options)
+    }
+
+    #[doc(hidden)]
+    fn into_server_handler(self, options: &SessionOptions) -> McpServerHandler
+    where
+        Self: Sized + Send + Sync + 'static,
+    {
+        McpServerHandler::new(self, options)
     }
 }
 
@@ -498,6 +690,16 @@ impl RequestContext {
         self.data.protocol_version
     }
 
+    /// Whether messages that are not public are sent to the client in this session
+    pub(crate) fn expose_internals(&self) -> bool {
+        self.data.expose_internals
+    }
+
+    /// Settings used to generate the input schemas of tools in this session
+    pub(crate) fn schema_settings(&self) -> &SchemaSettings {
+        &self.data.schema_settings
+    }
+
     /// Notifies progress of the request associated with this context
     ///
     /// See [`notifications/progress`]
@@ -519,11 +721,16 @@ impl RequestContext {
 
     /// Calls [`sampling/createMessage`]
     ///
+    /// Returns [`CapabilityNotSupported`](crate::CapabilityNotSupported) if the client does not support sampling.
+    ///
     /// [`sampling/createMessage`]: https://modelcontextprotocol.io/specification/2025-06-18/client/sampling#creating-messages
     pub async fn sampling_create_message(
         &self,
         p: CreateMessageRequestParams,
     ) -> SessionResult<CreateMessageResult> {
+        if self.client_capabilities().sampling.is_none() {
+            return Err(CapabilityNotSupported::new("sampling/createMessage", "sampling").into());
+        }
         self.session
             .request("sampling/createMessage", Some(&p))
             .await
@@ -531,21 +738,72 @@ impl RequestContext {
 
     /// Calls [`roots/list`]
     ///
+    /// If the client supports `notifications/roots/list_changed`, the result is cached for the session
+    /// and the cache is cleared when the notification is received.
+    ///
+    /// Returns [`CapabilityNotSupported`](crate::CapabilityNotSupported) if the client does not support roots.
+    ///
     /// [`roots/list`]: https://modelcontextprotocol.io/specification/2025-06-18/client/roots#listing-roots
     pub async fn roots_list(&self) -> SessionResult<Vec<Root>> {
+        if self.client_capabilities().roots.is_none() {
+            return Err(CapabilityNotSupported::new("roots/list", "roots").into());
+        }
+        let generation = match self.data.roots.lock().unwrap().get() {
+            Ok(roots) => return Ok(roots),
+            Err(generation) => generation,
+        };
         let res: ListRootsResult = self
             .session
             .request("roots/list", Some(&ListRootsRequestParams::default()))
             .await?;
+        if self.is_roots_list_changed_supported() {
+            self.data
+                .roots
+                .lock()
+                .unwrap()
+                .set(generation, res.roots.clone());
+        }
         Ok(res.roots)
     }
+
+    fn is_roots_list_changed_supported(&self) -> bool {
+        self.client_capabilities()
+            .roots
+            .as_ref()
+            .and_then(|r| r.list_changed)
+            .unwrap_or(false)
+    }
+
+    /// Resolves a file path and checks that it is inside one of the roots declared by the client
+    ///
+    /// The path is canonicalized, so `..` components and symbolic links that point outside of the roots are rejected.
+    /// Relative paths are resolved against each root in order.
+    /// The path itself does not need to exist, but its non-existent part must not contain `..`.
+    ///
+    /// Returns an `INVALID_PARAMS` error if the path is outside of all roots.
+    pub async fn resolve_within_roots(&self, path: impl AsRef<Path>) -> Result<PathBuf> {
+        let path = path.as_ref();
+        let root_paths: Vec<PathBuf> = self
+            .roots_list()
+            .await?
+            .iter()
+            .filter_map(|r| r.to_file_path()?.canonicalize().ok())
+            .collect();
+        if let Some(path) = roots::resolve_within(path, &root_paths) {
+            Ok(path)
+        } else {
+            bail_public!(
+                ErrorCode::INVALID_PARAMS,
+                "path `{}` is outside of the roots",
+                path.display()
+            )
+        }
+    }
 }
 
 /// Runs an MCP server using stdio transport
 pub async fn serve_stdio(server: impl McpServer) -> SessionResult<()> {
-    Session::from_stdio(McpServerHandler::new(server), &SessionOptions::default())
-        .wait()
-        .await
+    serve_stdio_with(server, &SessionOptions::default()).await
 }
 
 /// Runs an MCP server using stdio transport with specified options
@@ -553,7 +811,28 @@ pub async fn serve_stdio_with(
     server: impl McpServer,
     options: &SessionOptions,
 ) -> SessionResult<()> {
-    Session::from_stdio(McpServerHandler::new(server), options)
-        .wait()
-        .await
+    serve(server, BufReader::new(stdin()), stdout(), options).await
+}
+
+/// Runs an MCP server using the specified reader and writer
+///
+/// Messages are newline-delimited JSON.
+/// In sessions that negotiated protocol version 2025-03-26, [JSON-RPC batches] are answered with a single batch.
+/// In sessions that negotiated other versions, the requests in a batch are answered one by one.
+///
+/// [JSON-RPC batches]: https://modelcontextprotocol.io/specification/2025-03-26/basic#batching
+pub async fn serve(
+    server: impl McpServer,
+    reader: impl AsyncBufRead + Send + Sync + 'static,
+    writer: impl AsyncWrite + Send + Sync + 'static,
+    options: &SessionOptions,
+) -> SessionResult<()> {
+    let versions = server.protocol_versions();
+    let mut handler = server.into_server_handler(options);
+    if !versions.into_iter().any(batch::is_batch_supported) {
+        return Session::new(handler, reader, writer, options).wait().await;
+    }
+    let (reader, writer, negotiated) = batch::server_transport(reader, writer);
+    handler.negotiated = Some(negotiated);
+    Session::new(handler, reader, writer, options).wait().await
 }
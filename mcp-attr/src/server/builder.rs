@@ -13,10 +13,11 @@ use crate::{
         ListResourcesResult, ListToolsRequestParams, ListToolsResult, Prompt,
         ReadResourceRequestParams, ReadResourceResult, Resource, ResourceTemplate, Tool,
     },
-    server::errors::{prompt_not_found, resource_not_found, tool_not_found},
+    server::errors::{ToolError, prompt_not_found, resource_not_found, tool_not_found},
+    utils::ProtocolVersion,
 };
 
-use super::{McpServer, RequestContext};
+use super::{McpServer, RequestContext, SchemaSettings};
 
 /// Completion function information for prompts and resources
 #[derive(Debug, Clone)]
@@ -54,6 +55,10 @@ struct CustomServer {
     route: Route,
     instructions: Option<String>,
     server_info: Implementation,
+    protocol_versions: Vec<ProtocolVersion>,
+    schema_settings: SchemaSettings,
+    validate_tool_arguments: bool,
+    lenient_tool_arguments: bool,
 }
 impl McpServer for CustomServer {
     fn capabilities(&self) -> crate::schema::ServerCapabilities {
@@ -73,6 +78,9 @@ impl McpServer for CustomServer {
                 ..Default::default()
             });
         }
+        if !self.route.completions.is_empty() {
+            c.completions = Some(Default::default());
+        }
         c
     }
     fn server_info(&self) -> Implementation {
@@ -81,6 +89,18 @@ impl McpServer for CustomServer {
     fn instructions(&self) -> Option<String> {
         self.instructions.clone()
     }
+    fn protocol_versions(&self) -> Vec<ProtocolVersion> {
+        self.protocol_versions.clone()
+    }
+    fn schema_settings(&self) -> SchemaSettings {
+        self.schema_settings.clone()
+    }
+    fn validate_tool_arguments(&self) -> bool {
+        self.validate_tool_arguments
+    }
+    fn lenient_tool_arguments(&self) -> bool {
+        self.lenient_tool_arguments
+    }
     async fn prompts_list(
         &self,
         _p: ListPromptsRequestParams,
@@ -148,9 +168,15 @@ impl McpServer for CustomServer {
     async fn tools_list(
         &self,
         _p: ListToolsRequestParams,
-        _cx: &mut RequestContext,
+        cx: &mut RequestContext,
     ) -> Result<ListToolsResult> {
-        let tools: Vec<Tool> = self.route.tools.iter().map(|t| t.tool.clone()).collect();
+        let tools = self
+            .route
+            .tools
+            .iter()
+            .filter(|t| t.is_enabled(cx))
+            .map(|t| (t.tool)(&self.schema_settings))
+            .collect::<Result<Vec<Tool>>>()?;
         Ok(tools.into())
     }
     async fn tools_call(
@@ -159,8 +185,8 @@ impl McpServer for CustomServer {
         cx: &mut RequestContext,
     ) -> Result<CallToolResult> {
         for tool in &self.route.tools {
-            if tool.tool.name == p.name {
-                return (tool.f)(&p, cx).await;
+            if tool.name == p.name && tool.is_enabled(cx) {
+                return (tool.f)(&p, cx).await.or_else(|e| e.into_result(cx));
             }
         }
         Err(tool_not_found(&p.name))
@@ -213,6 +239,10 @@ pub struct McpServerBuilder {
     route: Route,
     instructions: Option<String>,
     server_info: Implementation,
+    protocol_versions: Vec<ProtocolVersion>,
+    schema_settings: SchemaSettings,
+    validate_tool_arguments: bool,
+    lenient_tool_arguments: bool,
 }
 impl McpServerBuilder {
     pub fn new() -> Self {
@@ -220,6 +250,10 @@ impl McpServerBuilder {
             route: Route::default(),
             instructions: None,
             server_info: Implementation::from_compile_time_env(),
+            protocol_versions: ProtocolVersion::ALL.to_vec(),
+            schema_settings: SchemaSettings::default(),
+            validate_tool_arguments: false,
+            lenient_tool_arguments: false,
         }
     }
     pub fn route(mut self, route: impl Into<Route>) -> Self {
@@ -234,11 +268,38 @@ impl McpServerBuilder {
         self.server_info = server_info;
         self
     }
+    /// Sets the protocol versions supported by the server
+    ///
+    /// If the version requested by the client is not included, the latest version in this list is returned
+    /// and the client decides whether to continue.
+    /// The default is [`ProtocolVersion::ALL`].
+    ///
+    /// See [`McpServer::protocol_versions`].
+    pub fn protocol_versions(mut self, protocol_versions: &[ProtocolVersion]) -> Self {
+        self.protocol_versions = protocol_versions.to_vec();
+        self
+    }
+    pub fn schema_settings(mut self, settings: SchemaSettings) -> Self {
+        self.schema_settings = settings;
+        self
+    }
+    pub fn validate_tool_arguments(mut self, validate: bool) -> Self {
+        self.validate_tool_arguments = validate;
+        self
+    }
+    pub fn lenient_tool_arguments(mut self, lenient: bool) -> Self {
+        self.lenient_tool_arguments = lenient;
+        self
+    }
     pub fn build(self) -> impl McpServer {
         CustomServer {
             route: self.route,
             instructions: self.instructions,
             server_info: self.server_info,
+            protocol_versions: self.protocol_versions,
+            schema_settings: self.schema_settings,
+            validate_tool_arguments: self.validate_tool_arguments,
+            lenient_tool_arguments: self.lenient_tool_arguments,
         }
     }
 }
@@ -455,18 +516,23 @@ impl From<ResourceDefinition> for Route {
     }
 }
 
-type ToolResultFuture<'a> =
-    Pin<Box<dyn Future<Output = Result<CallToolResult>> + Send + Sync + 'a>>;
+type ToolResultFuture<'a> = Pin<
+    Box<dyn Future<Output = std::result::Result<CallToolResult, ToolError>> + Send + Sync + 'a>,
+>;
 
 #[doc(hidden)]
 pub struct ToolDefinition {
-    tool: Tool,
+    name: String,
+    #[allow(clippy::type_complexity)]
+    tool: Box<dyn Fn(&SchemaSettings) -> Result<Tool> + Send + Sync>,
     #[allow(clippy::type_complexity)]
     f: Box<
         dyn for<'a> Fn(&'a CallToolRequestParams, &'a RequestContext) -> ToolResultFuture<'a>
             + Send
             + Sync,
     >,
+    #[allow(clippy::type_complexity)]
+    enabled: Option<Box<dyn Fn(&RequestContext) -> bool + Send + Sync>>,
 }
 impl ToolDefinition {
     pub fn new(
@@ -476,8 +542,39 @@ impl ToolDefinition {
         + Sync
         + 'static,
     ) -> Self {
-        let f = Box::new(f);
-        Self { tool, f }
+        let name = tool.name.clone();
+        Self::with_schema_settings(&name, move |_| Ok(tool.clone()), f)
+    }
+
+    /// Creates a tool whose metadata is generated with the [`SchemaSettings`] of the server
+    pub fn with_schema_settings(
+        name: &str,
+        tool: impl Fn(&SchemaSettings) -> Result<Tool> + Send + Sync + 'static,
+        f: impl for<'a> Fn(&'a CallToolRequestParams, &'a RequestContext) -> ToolResultFuture<'a>
+        + Send
+        + Sync
+        + 'static,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            tool: Box::new(tool),
+            f: Box::new(f),
+            enabled: None,
+        }
+    }
+
+    /// Sets a predicate that decides whether the tool is listed and callable in the session
+    ///
+    /// Calls to a disabled tool fail as if the tool did not exist.
+    pub fn with_enabled(
+        mut self,
+        enabled: impl Fn(&RequestContext) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.enabled = Some(Box::new(enabled));
+        self
+    }
+    fn is_enabled(&self, cx: &RequestContext) -> bool {
+        self.enabled.as_ref().is_none_or(|f| f(cx))
     }
 }
 impl From<ToolDefinition> for Route {
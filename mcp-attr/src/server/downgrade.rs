@@ -0,0 +1,184 @@
+use serde_json::Value;
+
+use crate::{
+    schema::{
+        Annotations, CallToolResult, ContentBlock, EmbeddedResourceResource, GetPromptResult,
+        InitializeResult, ListPromptsResult, ListResourceTemplatesResult, ListResourcesResult,
+        ListToolsResult, Prompt, ReadResourceResult, ReadResourceResultContentsItem, Resource,
+        ResourceTemplate, TextContent, Tool,
+    },
+    utils::ProtocolVersion,
+};
+
+/// Removes the parts of a result that are not defined in older protocol versions
+pub(crate) trait Downgrade: Sized {
+    fn downgrade(&mut self, v: ProtocolVersion);
+
+    fn downgraded(mut self, v: ProtocolVersion) -> Self {
+        self.downgrade(v);
+        self
+    }
+}
+
+fn is_before_2025_03_26(v: ProtocolVersion) -> bool {
+    v < ProtocolVersion::V_2025_03_26
+}
+fn is_before_2025_06_18(v: ProtocolVersion) -> bool {
+    v < ProtocolVersion::V_2025_06_18
+}
+
+impl Downgrade for InitializeResult {
+    fn downgrade(&mut self, v: ProtocolVersion) {
+        if is_before_2025_03_26(v) {
+            self.capabilities.completions = None;
+        }
+        if is_before_2025_06_18(v) {
+            self.server_info.title = None;
+        }
+    }
+}
+
+impl Downgrade for ListPromptsResult {
+    fn downgrade(&mut self, v: ProtocolVersion) {
+        self.prompts.iter_mut().for_each(|p| p.downgrade(v));
+    }
+}
+impl Downgrade for Prompt {
+    fn downgrade(&mut self, v: ProtocolVersion) {
+        if is_before_2025_06_18(v) {
+            self.title = None;
+            self.meta.clear();
+            for a in &mut self.arguments {
+                a.title = None;
+            }
+        }
+    }
+}
+impl Downgrade for GetPromptResult {
+    fn downgrade(&mut self, v: ProtocolVersion) {
+        self.messages = std::mem::take(&mut self.messages)
+            .into_iter()
+            .filter_map(|mut m| {
+                m.content = downgrade_content(m.content, v)?;
+                Some(m)
+            })
+            .collect();
+    }
+}
+
+impl Downgrade for ListResourcesResult {
+    fn downgrade(&mut self, v: ProtocolVersion) {
+        self.resources.iter_mut().for_each(|r| r.downgrade(v));
+    }
+}
+impl Downgrade for Resource {
+    fn downgrade(&mut self, v: ProtocolVersion) {
+        if is_before_2025_06_18(v) {
+            self.title = None;
+            self.meta.clear();
+            downgrade_annotations(&mut self.annotations);
+        }
+    }
+}
+impl Downgrade for ListResourceTemplatesResult {
+    fn downgrade(&mut self, v: ProtocolVersion) {
+        self.resource_templates
+            .iter_mut()
+            .for_each(|r| r.downgrade(v));
+    }
+}
+impl Downgrade for ResourceTemplate {
+    fn downgrade(&mut self, v: ProtocolVersion) {
+        if is_before_2025_06_18(v) {
+            self.title = None;
+            self.meta.clear();
+            downgrade_annotations(&mut self.annotations);
+        }
+    }
+}
+impl Downgrade for ReadResourceResult {
+    fn downgrade(&mut self, v: ProtocolVersion) {
+        if is_before_2025_06_18(v) {
+            for c in &mut self.contents {
+                match c {
+                    ReadResourceResultContentsItem::TextResourceContents(c) => c.meta.clear(),
+                    ReadResourceResultContentsItem::BlobResourceContents(c) => c.meta.clear(),
+                }
+            }
+        }
+    }
+}
+
+impl Downgrade for ListToolsResult {
+    fn downgrade(&mut self, v: ProtocolVersion) {
+        self.tools.iter_mut().for_each(|t| t.downgrade(v));
+    }
+}
+impl Downgrade for Tool {
+    fn downgrade(&mut self, v: ProtocolVersion) {
+        if is_before_2025_03_26(v) {
+            self.annotations = None;
+        }
+        if is_before_2025_06_18(v) {
+            self.title = None;
+            self.output_schema = None;
+            self.meta.clear();
+        }
+    }
+}
+impl Downgrade for CallToolResult {
+    fn downgrade(&mut self, v: ProtocolVersion) {
+        if is_before_2025_06_18(v) && !self.structured_content.is_empty() {
+            let structured_content = std::mem::take(&mut self.structured_content);
+            if self.content.is_empty() {
+                let text = Value::Object(structured_content).to_string();
+                self.content.push(TextContent::new(text).into());
+            }
+        }
+        self.content = std::mem::take(&mut self.content)
+            .into_iter()
+            .filter_map(|c| downgrade_content(c, v))
+            .collect();
+    }
+}
+
+/// Converts `ResourceLink` into text and drops `AudioContent` for versions that do not define them
+fn downgrade_content(c: ContentBlock, v: ProtocolVersion) -> Option<ContentBlock> {
+    let mut c = match c {
+        ContentBlock::AudioContent(_) if is_before_2025_03_26(v) => return None,
+        ContentBlock::ResourceLink(l) if is_before_2025_06_18(v) => TextContent::new(l.uri).into(),
+        c => c,
+    };
+    if is_before_2025_06_18(v) {
+        match &mut c {
+            ContentBlock::TextContent(c) => {
+                c.meta.clear();
+                downgrade_annotations(&mut c.annotations);
+            }
+            ContentBlock::ImageContent(c) => {
+                c.meta.clear();
+                downgrade_annotations(&mut c.annotations);
+            }
+            ContentBlock::AudioContent(c) => {
+                c.meta.clear();
+                downgrade_annotations(&mut c.annotations);
+            }
+            ContentBlock::ResourceLink(_) => {}
+            ContentBlock::EmbeddedResource(c) => {
+                c.meta.clear();
+                downgrade_annotations(&mut c.annotations);
+                match &mut c.resource {
+                    EmbeddedResourceResource::TextResourceContents(r) => r.meta.clear(),
+                    EmbeddedResourceResource::BlobResourceContents(r) => r.meta.clear(),
+                }
+            }
+        }
+    }
+    Some(c)
+}
+
+fn downgrade_annotations(a: &mut Option<Annotations>) {
+    if let Some(a) = a {
+        a.last_modified = None;
+    }
+}
@@ -1,24 +1,281 @@
 //! Functions for creating errors for MCP server implementation
 
-use jsoncall::{Error, ErrorCode, ErrorObject};
-use serde_json::json;
+use std::fmt::Display;
+
+use jsoncall::{Error, ErrorCode, ErrorObject, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::{
+    CapabilityNotSupported,
+    schema::{CallToolResult, TextContent},
+    server::RequestContext,
+};
+
+/// Error code for when a resource is not found
+///
+/// See [Error Handling](https://modelcontextprotocol.io/specification/2025-06-18/server/resources#error-handling).
+pub const RESOURCE_NOT_FOUND: ErrorCode = ErrorCode(-32002);
+
+fn error_with_data(code: ErrorCode, message: String, data: Value) -> Error {
+    ErrorObject {
+        code,
+        message,
+        data: Some(data),
+    }
+    .into()
+}
 
 /// Creates an error for when a prompt is not found
-pub fn prompt_not_found(_name: &str) -> Error {
-    Error::new(ErrorCode::METHOD_NOT_FOUND).with_message("Prompt not found", true)
+///
+/// The `data` field contains the name of the prompt.
+pub fn prompt_not_found(name: &str) -> Error {
+    error_with_data(
+        ErrorCode::INVALID_PARAMS,
+        format!("Unknown prompt: {name}"),
+        json!({ "name": name }),
+    )
 }
 
 /// Creates an error for when a tool is not found
-pub fn tool_not_found(_name: &str) -> Error {
-    Error::new(ErrorCode::METHOD_NOT_FOUND).with_message("Tool not found", true)
+///
+/// The `data` field contains the name of the tool.
+pub fn tool_not_found(name: &str) -> Error {
+    error_with_data(
+        ErrorCode::INVALID_PARAMS,
+        format!("Unknown tool: {name}"),
+        json!({ "name": name }),
+    )
 }
 
 /// Creates an error for when a resource is not found
+///
+/// The `data` field contains the URI of the resource.
 pub fn resource_not_found(uri: &str) -> Error {
-    ErrorObject {
-        code: ErrorCode::INVALID_PARAMS,
-        message: "Resource not found".to_string(),
-        data: Some(json!({ "uri": uri })),
+    error_with_data(
+        RESOURCE_NOT_FOUND,
+        format!("Resource not found: {uri}"),
+        json!({ "uri": uri }),
+    )
+}
+
+/// Creates an error for when an argument is missing or invalid
+///
+/// `pointer` is a JSON Pointer to the argument in the request params, such as `/arguments/name`, and is stored in the `data` field.
+pub fn invalid_argument(pointer: &str, message: impl Display) -> Error {
+    error_with_data(
+        ErrorCode::INVALID_PARAMS,
+        message.to_string(),
+        json!({ "pointer": pointer }),
+    )
+}
+
+/// A violation of the input schema of a tool
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArgumentViolation {
+    /// JSON Pointer to the invalid value in the request params
+    pub pointer: String,
+    /// Description of the violation
+    pub message: String,
+}
+
+/// Creates an error for when arguments violate the input schema of a tool
+///
+/// The message lists every violation, and the `data` field contains them as `{ "violations": [{ "pointer", "message" }] }`.
+pub fn invalid_arguments(violations: Vec<ArgumentViolation>) -> Error {
+    let mut message = "Invalid arguments".to_string();
+    for v in &violations {
+        message.push_str(&format!("\n{}: {}", v.pointer, v.message));
+    }
+    error_with_data(
+        ErrorCode::INVALID_PARAMS,
+        message,
+        json!({ "violations": violations }),
+    )
+}
+
+/// Returns a JSON Pointer to the argument `name` of a tool or prompt in the request params
+pub fn argument_pointer(name: &str) -> String {
+    format!("/arguments/{}", pointer_token(name))
+}
+
+/// Escapes `~` and `/` in a reference token of a JSON Pointer
+pub(crate) fn pointer_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Creates an error for when a request requires a capability that the peer did not advertise
+///
+/// Equivalent to converting [`CapabilityNotSupported`] into [`Error`].
+pub fn capability_not_supported(method: &str, capability: &str) -> Error {
+    CapabilityNotSupported::new(method, capability).into()
+}
+
+/// Error returned by a tool
+///
+/// Tools that return `Result<T, ToolError>` can choose how a failure is reported to the client.
+/// [`Error`] is converted into [`ToolError::Execution`], so tools that return [`Result<T>`](crate::Result) report failures as [`ToolError::Execution`].
+#[derive(Debug)]
+pub enum ToolError {
+    /// The tool failed while running
+    ///
+    /// Reported as a [`CallToolResult`] with `is_error` set, so that the model can see the error message and react to it.
+    Execution(Error),
+
+    /// The request was invalid, such as missing arguments
+    ///
+    /// Reported as a JSON-RPC error.
+    Protocol(Error),
+}
+impl ToolError {
+    /// Creates an error reported as a [`CallToolResult`] with `is_error` set
+    pub fn execution(e: impl Into<Error>) -> Self {
+        Self::Execution(e.into())
+    }
+
+    /// Creates an error reported as a JSON-RPC error
+    pub fn protocol(e: impl Into<Error>) -> Self {
+        Self::Protocol(e.into())
+    }
+
+    /// Converts into the result of `tools/call`
+    ///
+    /// Messages that are not public are hidden in the same way as JSON-RPC errors of the session of `cx`.
+    pub fn into_result(self, cx: &RequestContext) -> Result<CallToolResult> {
+        match self {
+            Self::Execution(e) => {
+                let expose_internals = cx.expose_internals();
+                let mut message = e.to_error_object(expose_internals).message;
+                if let Some(i) = e.source().and_then(|s| s.downcast_ref::<InternalMessage>()) {
+                    if expose_internals {
+                        message = format!("{message}: {i}");
+                    }
+                }
+                let mut r = CallToolResult::from(vec![TextContent::new(message)]);
+                r.is_error = Some(true);
+                Ok(r)
+            }
+            Self::Protocol(e) => Err(e),
+        }
+    }
+}
+impl From<Error> for ToolError {
+    fn from(e: Error) -> Self {
+        Self::Execution(e)
+    }
+}
+
+/// Conversion of errors returned by `#[tool]`, `#[prompt]` and `#[resource]` functions into [`Error`]
+///
+/// Functions can return `Result<T, E>` for any `E` implementing this trait.
+/// Implement it for domain error types to choose how they are reported to the client.
+///
+/// ```
+/// use mcp_attr::ErrorCode;
+/// use mcp_attr::server::IntoMcpError;
+/// use serde_json::{Value, json};
+///
+/// enum ServiceError {
+///     NotFound(String),
+///     Database(String),
+/// }
+///
+/// impl IntoMcpError for ServiceError {
+///     fn code(&self) -> ErrorCode {
+///         match self {
+///             ServiceError::NotFound(_) => ErrorCode::INVALID_PARAMS,
+///             ServiceError::Database(_) => ErrorCode::INTERNAL_ERROR,
+///         }
+///     }
+///     fn public_message(&self) -> Option<String> {
+///         match self {
+///             ServiceError::NotFound(id) => Some(format!("`{id}` was not found")),
+///             ServiceError::Database(_) => None,
+///         }
+///     }
+///     fn data(&self) -> Option<Value> {
+///         match self {
+///             ServiceError::NotFound(id) => Some(json!({ "id": id })),
+///             ServiceError::Database(_) => None,
+///         }
+///     }
+///     fn internal_message(&self) -> Option<String> {
+///         match self {
+///             ServiceError::NotFound(_) => None,
+///             ServiceError::Database(e) => Some(e.clone()),
+///         }
+///     }
+/// }
+/// ```
+pub trait IntoMcpError: Sized {
+    /// JSON-RPC error code
+    fn code(&self) -> ErrorCode {
+        ErrorCode::INTERNAL_ERROR
+    }
+
+    /// Message that is always sent to the client
+    fn public_message(&self) -> Option<String> {
+        None
+    }
+
+    /// Value of the `data` field of the error
+    fn data(&self) -> Option<Value> {
+        None
+    }
+
+    /// Details of the error that are sent to the client only if internals are exposed
+    fn internal_message(&self) -> Option<String> {
+        None
+    }
+
+    /// Whether to send [`internal_message`](Self::internal_message) regardless of the session options
+    fn expose_internals(&self) -> bool {
+        false
+    }
+
+    /// Converts into [`Error`]
+    ///
+    /// If both messages are present and internals are not exposed by [`expose_internals`](Self::expose_internals),
+    /// the internal message is kept as the source of the error and is sent only in sessions that expose internals.
+    /// If [`data`](Self::data) is present without a public message, the message is the default message of the code.
+    fn into_mcp_error(self) -> Error {
+        let code = self.code();
+        let e = match self.data() {
+            Some(data) => ErrorObject {
+                code,
+                message: code.message().to_string(),
+                data: Some(data),
+            }
+            .into(),
+            None => Error::new(code),
+        };
+        let public_message = self.public_message();
+        let internal_message = self.internal_message();
+        match (public_message, internal_message) {
+            (Some(p), Some(i)) if self.expose_internals() => {
+                e.with_message(format!("{p}: {i}"), true)
+            }
+            (None, Some(i)) if self.expose_internals() => e.with_message(i, true),
+            (Some(p), Some(i)) => e.with_message(p, true).with_source(InternalMessage(i)),
+            (Some(p), None) => e.with_message(p, true),
+            (None, Some(i)) => e.with_message(i, false),
+            (None, None) => e,
+        }
+    }
+}
+impl IntoMcpError for Error {
+    fn into_mcp_error(self) -> Error {
+        self
+    }
+}
+
+/// Internal message of an error converted by [`IntoMcpError`] that also has a public message
+#[derive(Debug)]
+struct InternalMessage(String);
+
+impl Display for InternalMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
     }
-    .into()
 }
+impl std::error::Error for InternalMessage {}
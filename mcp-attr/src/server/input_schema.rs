@@ -0,0 +1,437 @@
+use std::{collections::BTreeSet, mem::take};
+
+use jsoncall::{Result, bail};
+use schemars::{JsonSchema, r#gen::SchemaGenerator};
+use serde::Serialize;
+use serde_json::{Map, Value, json, to_value};
+
+use crate::schema::ToolInputSchema;
+
+/// Draft of JSON Schema used for tool input schemas
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SchemaDraft {
+    /// [Draft 7](https://json-schema.org/draft-07)
+    Draft07,
+    /// [Draft 2019-09](https://json-schema.org/draft/2019-09)
+    Draft2019_09,
+    /// [Draft 2020-12](https://json-schema.org/draft/2020-12)
+    Draft2020_12,
+}
+impl SchemaDraft {
+    fn meta_schema(self) -> &'static str {
+        match self {
+            Self::Draft07 => "http://json-schema.org/draft-07/schema#",
+            Self::Draft2019_09 => "https://json-schema.org/draft/2019-09/schema",
+            Self::Draft2020_12 => "https://json-schema.org/draft/2020-12/schema",
+        }
+    }
+}
+
+/// How `Option<T>` arguments and fields are described in tool input schemas
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OptionSchema {
+    /// The value may be omitted or `null`
+    #[default]
+    Nullable,
+    /// The value may be omitted, and `null` is not described in the schema
+    Omitted,
+}
+
+/// Settings for generating JSON Schemas of tool inputs
+///
+/// Types used by several arguments of a tool are defined once in `$defs` and referenced with `$ref`.
+///
+/// Use [`McpServer::schema_settings`](crate::server::McpServer::schema_settings) or
+/// [`McpServerBuilder::schema_settings`](crate::server::McpServerBuilder::schema_settings) to apply settings to a server.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SchemaSettings {
+    draft: Option<SchemaDraft>,
+    inline_subschemas: bool,
+    option: OptionSchema,
+    compatible: bool,
+}
+impl SchemaSettings {
+    /// Creates the default settings
+    ///
+    /// `$schema` is omitted, subschemas are placed in `$defs`, and `Option<T>` is [`OptionSchema::Nullable`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates settings for clients that reject some JSON Schema keywords
+    ///
+    /// `format` is removed, `oneOf` is replaced by `anyOf`, and `$ref` is replaced by the referenced schema.
+    /// References to recursive types are replaced by an empty schema.
+    pub fn compatible() -> Self {
+        Self::default().with_compatible(true)
+    }
+
+    /// Sets the draft and adds `$schema` to the schemas
+    pub fn with_draft(mut self, draft: SchemaDraft) -> Self {
+        self.draft = Some(draft);
+        self
+    }
+
+    /// Sets whether to replace `$ref` by the referenced schema
+    ///
+    /// References to recursive types are kept.
+    pub fn with_inline_subschemas(mut self, inline_subschemas: bool) -> Self {
+        self.inline_subschemas = inline_subschemas;
+        self
+    }
+
+    /// Sets how `Option<T>` is described
+    pub fn with_option_schema(mut self, option: OptionSchema) -> Self {
+        self.option = option;
+        self
+    }
+
+    /// Sets whether to remove keywords that some clients reject
+    ///
+    /// See [`compatible`](Self::compatible).
+    pub fn with_compatible(mut self, compatible: bool) -> Self {
+        self.compatible = compatible;
+        self
+    }
+
+    fn definitions_key(&self) -> &'static str {
+        match self.draft {
+            Some(SchemaDraft::Draft07) => "definitions",
+            _ => "$defs",
+        }
+    }
+    fn definitions_path(&self) -> String {
+        format!("#/{}/", self.definitions_key())
+    }
+    fn generator(&self) -> SchemaGenerator {
+        let mut s = schemars::r#gen::SchemaSettings::draft2019_09();
+        s.definitions_path = self.definitions_path();
+        s.meta_schema = None;
+        s.option_nullable = false;
+        s.option_add_null_type = self.option == OptionSchema::Nullable;
+        s.inline_subschemas = false;
+        s.into_generator()
+    }
+
+    /// Returns the schema of an argument of type `T` as it is published in input schemas
+    ///
+    /// The subschemas are placed in the returned schema, so its `$ref` can be resolved against itself.
+    pub(crate) fn tool_arg_schema<T: JsonSchema>(&self) -> Result<Value> {
+        let mut b = ToolInputSchemaBuilder::new(self);
+        b.insert_property::<T>("value", "", true)?;
+        let schema = b.build()?;
+        let mut obj = schema.properties.get("value").cloned().unwrap_or_default();
+        obj.extend(schema.extra);
+        Ok(Value::Object(obj))
+    }
+
+    /// Returns the input schema of the arguments described by the struct `T` for `#[arg(flatten)]`
+    pub(crate) fn tool_args_schema<T: JsonSchema>(&self) -> Result<Value> {
+        let mut b = ToolInputSchemaBuilder::new(self);
+        b.insert_flatten::<T>()?;
+        Ok(to_value(b.build()?)?)
+    }
+}
+
+/// Constraints of a tool argument that are published in the input schema and checked before the tool is called
+///
+/// `min_length` and `max_length` are described as `minItems` and `maxItems` for arrays, and as `minLength` and `maxLength` otherwise.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ArgConstraints {
+    pub minimum: Option<Value>,
+    pub maximum: Option<Value>,
+    pub min_length: Option<u64>,
+    pub max_length: Option<u64>,
+    pub pattern: Option<String>,
+}
+impl ArgConstraints {
+    /// Returns the JSON Schema keywords of the constraints for a value described by `schema`
+    pub(crate) fn to_keywords(&self, schema: &Map<String, Value>) -> Map<String, Value> {
+        let is_array = match schema.get("type") {
+            Some(Value::String(ty)) => ty == "array",
+            Some(Value::Array(tys)) => tys.iter().any(|ty| ty == "array"),
+            _ => false,
+        };
+        let (min_length, max_length) = if is_array {
+            ("minItems", "maxItems")
+        } else {
+            ("minLength", "maxLength")
+        };
+        let mut keywords = Map::new();
+        if let Some(minimum) = &self.minimum {
+            keywords.insert("minimum".to_string(), minimum.clone());
+        }
+        if let Some(maximum) = &self.maximum {
+            keywords.insert("maximum".to_string(), maximum.clone());
+        }
+        if let Some(min) = self.min_length {
+            keywords.insert(min_length.to_string(), min.into());
+        }
+        if let Some(max) = self.max_length {
+            keywords.insert(max_length.to_string(), max.into());
+        }
+        if let Some(pattern) = &self.pattern {
+            keywords.insert("pattern".to_string(), pattern.clone().into());
+        }
+        keywords
+    }
+}
+
+/// Builder of [`ToolInputSchema`] that shares a schema generator among the arguments of a tool
+pub struct ToolInputSchemaBuilder {
+    settings: SchemaSettings,
+    generator: SchemaGenerator,
+    schema: ToolInputSchema,
+}
+impl ToolInputSchemaBuilder {
+    /// Creates a builder with the specified settings
+    pub fn new(settings: &SchemaSettings) -> Self {
+        Self::with_schema(ToolInputSchema::new(), settings)
+    }
+    pub(crate) fn with_schema(schema: ToolInputSchema, settings: &SchemaSettings) -> Self {
+        Self {
+            settings: settings.clone(),
+            generator: settings.generator(),
+            schema,
+        }
+    }
+
+    /// Adds an argument of type `T`
+    pub fn insert_property<T: JsonSchema>(
+        &mut self,
+        name: &str,
+        description: &str,
+        required: bool,
+    ) -> Result<()> {
+        let mut schema = self.generator.subschema_for::<T>().into_object();
+        if !description.is_empty() {
+            schema.metadata().description = Some(description.to_string());
+        }
+        let Value::Object(mut obj) = to_value(schema)? else {
+            bail!("schema for `{name}` is not an object");
+        };
+        if !required && self.settings.option == OptionSchema::Nullable {
+            add_null_type(&mut obj);
+        }
+        self.schema.properties.insert(name.to_string(), obj);
+        if required {
+            self.schema.required.push(name.to_string());
+        }
+        Ok(())
+    }
+
+    /// Sets the keyword `key` of the argument `name`, such as `default` and `examples`
+    pub fn insert_keyword<V: Serialize + ?Sized>(
+        &mut self,
+        name: &str,
+        key: &str,
+        value: &V,
+    ) -> Result<()> {
+        let value = to_value(value)?;
+        self.property_mut(name)?.insert(key.to_string(), value);
+        Ok(())
+    }
+
+    /// Marks the argument `name` as secret
+    ///
+    /// The property is marked as `writeOnly`, and the value of the argument is redacted from logs, errors
+    /// and the `Debug` output of [`CallToolRequestParams`](crate::schema::CallToolRequestParams).
+    pub fn insert_secret(&mut self, name: &str) -> Result<()> {
+        self.insert_keyword(name, "writeOnly", &true)
+    }
+
+    /// Adds the constraints of the argument `name`
+    pub fn insert_constraints(&mut self, name: &str, constraints: &ArgConstraints) -> Result<()> {
+        let property = self.property_mut(name)?;
+        let keywords = constraints.to_keywords(property);
+        property.extend(keywords);
+        Ok(())
+    }
+
+    fn property_mut(&mut self, name: &str) -> Result<&mut Map<String, Value>> {
+        match self.schema.properties.get_mut(name) {
+            Some(property) => Ok(property),
+            None => bail!("argument `{name}` is not defined"),
+        }
+    }
+
+    /// Adds the properties of the struct `T` as arguments
+    ///
+    /// `T` must be described as a JSON Schema object with `properties`.
+    pub fn insert_flatten<T: JsonSchema>(&mut self) -> Result<()> {
+        let schema = T::json_schema(&mut self.generator);
+        let Value::Object(mut obj) = to_value(schema)? else {
+            bail!("schema for `{}` is not an object", T::schema_name());
+        };
+        let Some(Value::Object(properties)) = obj.remove("properties") else {
+            bail!("schema for `{}` has no properties", T::schema_name());
+        };
+        for (name, property) in properties {
+            if let Value::Object(property) = property {
+                self.schema.properties.insert(name, property);
+            }
+        }
+        if let Some(Value::Array(required)) = obj.remove("required") {
+            for name in required {
+                if let Value::String(name) = name {
+                    self.schema.required.push(name);
+                }
+            }
+        }
+        if let Some(additional) = obj.remove("additionalProperties") {
+            self.schema
+                .extra
+                .insert("additionalProperties".to_string(), additional);
+        }
+        Ok(())
+    }
+
+    /// Builds [`ToolInputSchema`] and places the subschemas according to the settings
+    pub fn build(self) -> Result<ToolInputSchema> {
+        let Self {
+            settings,
+            generator,
+            mut schema,
+        } = self;
+        let key = settings.definitions_key();
+        let mut defs = match schema.extra.remove(key) {
+            Some(Value::Object(defs)) => defs,
+            _ => Map::new(),
+        };
+        for (name, s) in generator.definitions() {
+            defs.insert(name.clone(), to_value(s)?);
+        }
+        let mut r = Resolver {
+            settings: &settings,
+            prefix: settings.definitions_path(),
+            defs: &defs,
+            used: BTreeSet::new(),
+        };
+        for property in schema.properties.values_mut() {
+            let mut value = Value::Object(take(property));
+            r.resolve(&mut value, &mut Vec::new());
+            if let Value::Object(obj) = value {
+                *property = obj;
+            }
+        }
+        let mut used_defs = Map::new();
+        while let Some(name) = r.used.iter().find(|n| !used_defs.contains_key(*n)).cloned() {
+            let mut def = defs.get(&name).cloned().unwrap_or(json!({}));
+            r.resolve(&mut def, &mut vec![name.clone()]);
+            used_defs.insert(name, def);
+        }
+        if !used_defs.is_empty() {
+            schema
+                .extra
+                .insert(key.to_string(), Value::Object(used_defs));
+        }
+        if let Some(draft) = settings.draft {
+            schema
+                .extra
+                .insert("$schema".to_string(), draft.meta_schema().into());
+        }
+        Ok(schema)
+    }
+}
+
+struct Resolver<'a> {
+    settings: &'a SchemaSettings,
+    prefix: String,
+    defs: &'a Map<String, Value>,
+    used: BTreeSet<String>,
+}
+impl Resolver<'_> {
+    fn resolve(&mut self, value: &mut Value, stack: &mut Vec<String>) {
+        match value {
+            Value::Object(obj) => {
+                if self.resolve_ref(obj, stack) {
+                    return;
+                }
+                if self.settings.compatible {
+                    if obj.get("format").is_some_and(Value::is_string) {
+                        obj.remove("format");
+                    }
+                    if !obj.contains_key("anyOf") {
+                        if let Some(one_of) = obj.remove("oneOf") {
+                            obj.insert("anyOf".to_string(), one_of);
+                        }
+                    }
+                }
+                for value in obj.values_mut() {
+                    self.resolve(value, stack);
+                }
+            }
+            Value::Array(items) => {
+                for value in items {
+                    self.resolve(value, stack);
+                }
+            }
+            _ => {}
+        }
+    }
+    /// Replaces `$ref` in `obj` by the referenced schema if needed, and returns whether it was replaced
+    fn resolve_ref(&mut self, obj: &mut Map<String, Value>, stack: &mut Vec<String>) -> bool {
+        let Some(name) = obj
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|r| r.strip_prefix(&self.prefix))
+            .map(|name| name.to_string())
+        else {
+            return false;
+        };
+        let inline = self.settings.inline_subschemas || self.settings.compatible;
+        if !inline {
+            self.used.insert(name);
+            return false;
+        }
+        if stack.contains(&name) {
+            if self.settings.compatible {
+                obj.remove("$ref");
+            } else {
+                self.used.insert(name);
+            }
+            return false;
+        }
+        obj.remove("$ref");
+        let Some(Value::Object(def)) = self.defs.get(&name) else {
+            return true;
+        };
+        let mut def = Value::Object(def.clone());
+        stack.push(name);
+        self.resolve(&mut def, stack);
+        stack.pop();
+        if let Value::Object(def) = def {
+            for (k, v) in def {
+                obj.entry(k).or_insert(v);
+            }
+        }
+        true
+    }
+}
+
+fn add_null_type(schema: &mut Map<String, Value>) {
+    match schema.get_mut("type") {
+        Some(Value::String(ty)) => {
+            if ty != "null" {
+                let ty = take(ty);
+                schema.insert("type".to_string(), json!([ty, "null"]));
+            }
+        }
+        Some(Value::Array(tys)) => {
+            if !tys.iter().any(|ty| ty == "null") {
+                tys.push("null".into());
+            }
+        }
+        _ => {
+            let description = schema.remove("description");
+            let s = take(schema);
+            schema.insert(
+                "anyOf".to_string(),
+                json!([Value::Object(s), { "type": "null" }]),
+            );
+            if let Some(description) = description {
+                schema.insert("description".to_string(), description);
+            }
+        }
+    }
+}
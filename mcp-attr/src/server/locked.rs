@@ -0,0 +1,137 @@
+use std::{future::Future, sync::Arc};
+
+use tokio::sync::RwLock;
+
+use crate::{
+    Result,
+    schema::{
+        CallToolRequestParams, CallToolResult, CompleteRequestParams, CompleteResult,
+        GetPromptRequestParams, GetPromptResult, Implementation, ListPromptsRequestParams,
+        ListPromptsResult, ListResourceTemplatesRequestParams, ListResourceTemplatesResult,
+        ListResourcesRequestParams, ListResourcesResult, ListToolsRequestParams, ListToolsResult,
+        ReadResourceRequestParams, ReadResourceResult, ServerCapabilities,
+    },
+    server::{McpServer, RequestContext, SchemaSettings},
+    utils::ProtocolVersion,
+};
+
+/// `McpServer` implemented by `#[mcp_server]` for servers with `&mut self` tools
+pub trait LockedMcpServer: McpServer + Sized {
+    fn tools_call_locked(
+        this: &Arc<RwLock<Self>>,
+        p: CallToolRequestParams,
+        cx: &mut RequestContext,
+    ) -> impl Future<Output = Result<CallToolResult>> + Send;
+}
+
+/// Server that holds a [`LockedMcpServer`] behind a `RwLock`
+///
+/// The results of the synchronous methods of `McpServer` are evaluated once when the server is created.
+pub struct LockedServer<T> {
+    server: Arc<RwLock<T>>,
+    server_info: Implementation,
+    instructions: Option<String>,
+    protocol_versions: Vec<ProtocolVersion>,
+    schema_settings: SchemaSettings,
+    validate_tool_arguments: bool,
+    lenient_tool_arguments: bool,
+    capabilities: ServerCapabilities,
+}
+impl<T: LockedMcpServer> LockedServer<T> {
+    pub fn new(server: T) -> Self {
+        Self {
+            server_info: server.server_info(),
+            instructions: server.instructions(),
+            protocol_versions: server.protocol_versions(),
+            schema_settings: server.schema_settings(),
+            validate_tool_arguments: server.validate_tool_arguments(),
+            lenient_tool_arguments: server.lenient_tool_arguments(),
+            capabilities: server.capabilities(),
+            server: Arc::new(RwLock::new(server)),
+        }
+    }
+}
+
+impl<T: LockedMcpServer> McpServer for LockedServer<T> {
+    fn server_info(&self) -> Implementation {
+        self.server_info.clone()
+    }
+    fn instructions(&self) -> Option<String> {
+        self.instructions.clone()
+    }
+    fn protocol_versions(&self) -> Vec<ProtocolVersion> {
+        self.protocol_versions.clone()
+    }
+    fn schema_settings(&self) -> SchemaSettings {
+        self.schema_settings.clone()
+    }
+    fn validate_tool_arguments(&self) -> bool {
+        self.validate_tool_arguments
+    }
+    fn lenient_tool_arguments(&self) -> bool {
+        self.lenient_tool_arguments
+    }
+    fn capabilities(&self) -> ServerCapabilities {
+        self.capabilities.clone()
+    }
+    async fn prompts_list(
+        &self,
+        p: ListPromptsRequestParams,
+        cx: &mut RequestContext,
+    ) -> Result<ListPromptsResult> {
+        self.server.read().await.prompts_list(p, cx).await
+    }
+    async fn prompts_get(
+        &self,
+        p: GetPromptRequestParams,
+        cx: &mut RequestContext,
+    ) -> Result<GetPromptResult> {
+        self.server.read().await.prompts_get(p, cx).await
+    }
+    async fn resources_list(
+        &self,
+        p: ListResourcesRequestParams,
+        cx: &mut RequestContext,
+    ) -> Result<ListResourcesResult> {
+        self.server.read().await.resources_list(p, cx).await
+    }
+    async fn resources_templates_list(
+        &self,
+        p: ListResourceTemplatesRequestParams,
+        cx: &mut RequestContext,
+    ) -> Result<ListResourceTemplatesResult> {
+        self.server
+            .read()
+            .await
+            .resources_templates_list(p, cx)
+            .await
+    }
+    async fn resources_read(
+        &self,
+        p: ReadResourceRequestParams,
+        cx: &mut RequestContext,
+    ) -> Result<ReadResourceResult> {
+        self.server.read().await.resources_read(p, cx).await
+    }
+    async fn tools_list(
+        &self,
+        p: ListToolsRequestParams,
+        cx: &mut RequestContext,
+    ) -> Result<ListToolsResult> {
+        self.server.read().await.tools_list(p, cx).await
+    }
+    async fn tools_call(
+        &self,
+        p: CallToolRequestParams,
+        cx: &mut RequestContext,
+    ) -> Result<CallToolResult> {
+        T::tools_call_locked(&self.server, p, cx).await
+    }
+    async fn completion_complete(
+        &self,
+        p: CompleteRequestParams,
+        cx: &mut RequestContext,
+    ) -> Result<CompleteResult> {
+        self.server.read().await.completion_complete(p, cx).await
+    }
+}
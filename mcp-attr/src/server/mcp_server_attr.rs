@@ -293,3 +293,31 @@
 /// [`Result<impl Into<CallToolResult>>`]: https://docs.rs/mcp-attr/latest/mcp_attr/schema/struct.CallToolResult.html
 /// [`RequestContext`]: https://docs.rs/mcp-attr/latest/mcp_attr/server/struct.RequestContext.html
 pub use mcp_attr_macros::{complete_fn, mcp_server};
+
+/// Defines tools, prompts and resources in an inherent impl block of a server.
+///
+/// The block is registered with `#[mcp_server(tools = ["name"])]` on the server's `impl McpServer`.
+///
+/// ```rust
+/// use mcp_attr::Result;
+/// use mcp_attr::server::{mcp_server, mcp_tools, McpServer};
+///
+/// struct ExampleServer;
+///
+/// #[mcp_server(tools = ["admin"])]
+/// impl McpServer for ExampleServer {
+///     #[tool]
+///     async fn echo(&self, message: String) -> Result<String> {
+///         Ok(message)
+///     }
+/// }
+///
+/// #[mcp_tools("admin")]
+/// impl ExampleServer {
+///     #[tool]
+///     async fn shutdown(&self) -> Result<String> {
+///         Ok("bye".into())
+///     }
+/// }
+/// ```
+pub use mcp_attr_macros::mcp_tools;
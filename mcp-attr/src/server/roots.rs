@@ -0,0 +1,113 @@
+use std::{
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::schema::Root;
+
+/// Roots of a session cached from the result of `roots/list`
+///
+/// The generation is incremented when `notifications/roots/list_changed` is received,
+/// so a `roots/list` response requested before the notification is not cached.
+#[derive(Default)]
+pub(crate) struct RootsCache {
+    generation: u64,
+    roots: Option<Vec<Root>>,
+}
+impl RootsCache {
+    /// Returns the cached roots, or the current generation if they are not cached
+    pub fn get(&self) -> Result<Vec<Root>, u64> {
+        self.roots.clone().ok_or(self.generation)
+    }
+    /// Caches `roots` if no change has been notified since `generation`
+    pub fn set(&mut self, generation: u64, roots: Vec<Root>) {
+        if self.generation == generation {
+            self.roots = Some(roots);
+        }
+    }
+    pub fn clear(&mut self) {
+        self.generation += 1;
+        self.roots = None;
+    }
+}
+
+/// Maximum number of dangling symbolic links followed by [`canonicalize_lenient`]
+const MAX_SYMLINK_HOPS: usize = 40;
+
+/// Canonicalizes `path` even if its trailing components do not exist yet
+///
+/// The longest existing ancestor is canonicalized (resolving symbolic links) and the remaining components are appended.
+/// A dangling symbolic link among the trailing components is followed with [`fs::read_link`] and resolved again,
+/// so the result is where a file created at `path` would actually be placed.
+/// Returns `None` if the non-existent part contains `..`, since it cannot be resolved without touching the file system,
+/// or if too many dangling symbolic links are followed.
+pub(crate) fn canonicalize_lenient(path: &Path) -> io::Result<Option<PathBuf>> {
+    let mut base = path.to_path_buf();
+    let mut rest = Vec::new();
+    let mut hops = 0;
+    loop {
+        match base.canonicalize() {
+            Ok(mut p) => {
+                for c in rest.into_iter().rev() {
+                    p.push(c);
+                }
+                return Ok(Some(p));
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                if is_symlink(&base) {
+                    hops += 1;
+                    if hops > MAX_SYMLINK_HOPS {
+                        return Ok(None);
+                    }
+                    let target = fs::read_link(&base)?;
+                    base = match base.parent() {
+                        Some(parent) => parent.join(target),
+                        None => target,
+                    };
+                    continue;
+                }
+                let Some(parent) = base.parent() else {
+                    return Err(e);
+                };
+                match base.components().next_back() {
+                    Some(Component::Normal(c)) => rest.push(c.to_os_string()),
+                    Some(Component::CurDir) => {}
+                    Some(_) => return Ok(None),
+                    None => return Err(e),
+                }
+                base = parent.to_path_buf();
+                if base.as_os_str().is_empty() {
+                    return Err(e);
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn is_symlink(path: &Path) -> bool {
+    path.symlink_metadata()
+        .is_ok_and(|m| m.file_type().is_symlink())
+}
+
+/// Resolves `path` and returns it only if it lies inside one of `roots`
+///
+/// Relative paths are resolved against each root in order.
+/// `roots` must already be canonicalized.
+pub(crate) fn resolve_within(path: &Path, roots: &[PathBuf]) -> Option<PathBuf> {
+    if path.is_absolute() {
+        let path = canonicalize_lenient(path).ok()??;
+        return roots
+            .iter()
+            .any(|root| path.starts_with(root))
+            .then_some(path);
+    }
+    for root in roots {
+        if let Ok(Some(p)) = canonicalize_lenient(&root.join(path)) {
+            if p.starts_with(root) {
+                return Some(p);
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,147 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+};
+
+use jsoncall::Result;
+use jsonschema::Validator;
+use serde_json::{Map, Value, to_value};
+
+use crate::{
+    redaction::is_redacted_at,
+    schema::{ListToolsRequestParams, Tool, ToolInputSchema},
+    server::{
+        McpServer, RequestContext,
+        errors::{ArgumentViolation, invalid_arguments},
+    },
+};
+
+/// Finds the tool named `name` in the result of [`McpServer::tools_list`]
+async fn find_tool(
+    server: &impl McpServer,
+    name: &str,
+    cx: &mut RequestContext,
+) -> Result<Option<Tool>> {
+    let mut p = ListToolsRequestParams::default();
+    loop {
+        let r = server.tools_list(p, cx).await?;
+        if let Some(tool) = r.tools.into_iter().find(|t| t.name == name) {
+            return Ok(Some(tool));
+        }
+        let Some(cursor) = r.next_cursor else {
+            return Ok(None);
+        };
+        p = ListToolsRequestParams {
+            cursor: Some(cursor),
+        };
+    }
+}
+
+/// The input schema of a tool and the validator compiled from it on first use
+pub(crate) struct CompiledTool {
+    pub input_schema: Arc<ToolInputSchema>,
+    schema: Value,
+    validator: OnceLock<Validator>,
+}
+impl CompiledTool {
+    fn new(tool: Tool) -> Result<Self> {
+        let schema = to_value(&tool.input_schema)?;
+        Ok(Self {
+            input_schema: Arc::new(tool.input_schema),
+            schema,
+            validator: OnceLock::new(),
+        })
+    }
+    fn validator(&self) -> Result<&Validator> {
+        if let Some(validator) = self.validator.get() {
+            return Ok(validator);
+        }
+        let validator = jsonschema::validator_for(&self.schema)?;
+        Ok(self.validator.get_or_init(|| validator))
+    }
+}
+
+/// Tools of a session, recorded from the results of [`McpServer::tools_list`] and compiled on first use
+///
+/// The server does not advertise `tools.listChanged`, so the tools are assumed not to change during a session.
+#[derive(Default)]
+pub(crate) struct ToolCache(HashMap<String, Arc<CompiledTool>>);
+
+/// Records the tools returned to the client by `tools/list`
+///
+/// Cached tools whose input schema has changed are replaced.
+pub(crate) fn record_tools(tools: &[Tool], cx: &RequestContext) {
+    let mut cache = cx.data.tools.lock().unwrap();
+    for tool in tools {
+        if cache
+            .0
+            .get(&tool.name)
+            .is_some_and(|t| *t.input_schema == tool.input_schema)
+        {
+            continue;
+        }
+        if let Ok(compiled) = CompiledTool::new(tool.clone()) {
+            cache.0.insert(tool.name.clone(), Arc::new(compiled));
+        }
+    }
+}
+
+/// Returns the tool named `name` if it is in the session cache
+pub(crate) fn cached_tool(name: &str, cx: &RequestContext) -> Option<Arc<CompiledTool>> {
+    cx.data.tools.lock().unwrap().0.get(name).cloned()
+}
+
+/// Returns the tool named `name` with its compiled input schema, using the session cache
+///
+/// Returns `None` if [`McpServer::tools_list`] does not contain the tool. Such lookups are not cached.
+pub(crate) async fn compiled_tool(
+    server: &impl McpServer,
+    name: &str,
+    cx: &mut RequestContext,
+) -> Result<Option<Arc<CompiledTool>>> {
+    if let Some(tool) = cached_tool(name, cx) {
+        return Ok(Some(tool));
+    }
+    let Some(tool) = find_tool(server, name, cx).await? else {
+        return Ok(None);
+    };
+    let tool = Arc::new(CompiledTool::new(tool)?);
+    let mut tools = cx.data.tools.lock().unwrap();
+    Ok(Some(
+        tools.0.entry(name.to_string()).or_insert(tool).clone(),
+    ))
+}
+
+/// Validates the arguments of `tools/call` against the input schema of the tool
+///
+/// Arguments that are absent are validated as an empty object.
+/// All violations are reported in a single `INVALID_PARAMS` error,
+/// each with a JSON Pointer into the request params (e.g. `/arguments/count`).
+/// Messages about values of `writeOnly` schemas do not include the values.
+pub(crate) fn validate_tool_arguments(
+    tool: &CompiledTool,
+    arguments: &Option<Map<String, Value>>,
+) -> Result<()> {
+    let arguments = Value::Object(arguments.clone().unwrap_or_default());
+    let violations: Vec<ArgumentViolation> = tool
+        .validator()?
+        .iter_errors(&arguments)
+        .map(|e| {
+            let pointer = e.instance_path.to_string();
+            let message = if is_redacted_at(&arguments, &tool.schema, &tool.schema, &pointer) {
+                format!("value violates `{}`", e.schema_path)
+            } else {
+                e.to_string()
+            };
+            ArgumentViolation {
+                pointer: format!("/arguments{pointer}"),
+                message,
+            }
+        })
+        .collect();
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(invalid_arguments(violations))
+    }
+}
@@ -203,9 +203,9 @@
 //!
 //! ### 状態の管理
 //!
-//! `McpServer` を実装する値は同時に実行される複数のメソッドで共有されるため `&self` のみ使用可能です。 `&mut self` は使用できません。
+//! `McpServer` を実装する値は同時に実行される複数のメソッドで共有されるため、ハンドラは `&self` を受け取ります。
 //!
-//! 状態を持つには `Mutex` などの内部可変性を持つスレッドセーフな型を使用する必要があります。
+//! 状態を持つには `Mutex` などの内部可変性を持つスレッドセーフな型を使用できます。
 //!
 //! ```rust
 //! use std::sync::Mutex;
@@ -228,6 +228,34 @@
 //! }
 //! ```
 //!
+//! ツールは `&mut self` を受け取ることもできます。その場合 `#[mcp_server]` はサーバを `RwLock` の中に保持します。
+//! `read_only` を指定したツールは読み取りロックを、それ以外のツールは書き込みロックを取得します。
+//! プロンプト、リソース、補完は読み取りロックを取得します。
+//! `server_info` や `capabilities` などの同期メソッドの結果はサーバの開始時に一度だけ評価されます。
+//!
+//! ```rust
+//! use mcp_attr::server::{mcp_server, McpServer};
+//! use mcp_attr::Result;
+//!
+//! struct ExampleServer {
+//!   count: u32,
+//! }
+//!
+//! #[mcp_server]
+//! impl McpServer for ExampleServer {
+//!   #[tool]
+//!   fn add_count(&mut self) -> Result<String> {
+//!     self.count += 1;
+//!     Ok(format!("count: {}", self.count))
+//!   }
+//!
+//!   #[tool(read_only)]
+//!   fn get_count(&self) -> Result<String> {
+//!     Ok(format!("count: {}", self.count))
+//!   }
+//! }
+//! ```
+//!
 //! ### エラー処理
 //!
 //! mcp_attr では Rust の標準的なエラー処理方法である `Result` を使用します。
@@ -273,6 +301,13 @@
 //! }
 //! ```
 //!
+//! ツールが返したエラーは `isError` が設定された [`tools/call`] の結果として送信され、モデルがエラーメッセージを確認できるようになります。
+//! 引数が不正な場合や存在しないツール名が指定された場合など、リクエスト自体のエラーのみが JSON-RPC のエラーとして送信されます。
+//! エラーの送信方法を選択するには、ツールから [`ToolError`] を返します。
+//!
+//! `#[tool]`, `#[prompt]`, `#[resource]` の関数は、[`IntoMcpError`] を実装した任意の `E` について `Result<T, E>` を返すこともできます。
+//! 独自のエラー型にこのトレイトを実装すると、クライアントに送信するエラーコード、公開メッセージ、`data` を選択できます。
+//!
 //! ### クライアント機能の呼び出し
 //!
 //! MCP サーバは [`RequestContext`] を使用してクライアント機能([`roots/list`]など)を呼び出すことができます。
@@ -366,18 +401,58 @@
 //!
 //! 補完機能は `#[prompt]` と `#[resource]` の引数でのみ使用可能で、`#[tool]` の引数では使用できません。
 //!
+//! ### サーバの分割 (`#[mcp_tools]`)
+//!
+//! `#[mcp_server]` は `impl<S: Store> McpServer for MyServer<S>` のようなジェネリックな impl にも使用できます。
+//!
+//! ツール、プロンプト、リソースは `#[mcp_tools("name")]` を付けた固有 impl ブロックにも定義できます。別のモジュールに定義することもできます。
+//! ブロックは `#[mcp_server(tools = ["name", ..])]` で名前を指定して登録します。
+//!
+//! ```rust
+//! use mcp_attr::server::{mcp_server, mcp_tools, McpServer};
+//! use mcp_attr::Result;
+//!
+//! struct ExampleServer;
+//!
+//! #[mcp_server(tools = ["admin"])]
+//! impl McpServer for ExampleServer {
+//!     #[tool]
+//!     async fn echo(&self, message: String) -> Result<String> {
+//!         Ok(message)
+//!     }
+//! }
+//!
+//! #[mcp_tools("admin")]
+//! impl ExampleServer {
+//!     #[tool]
+//!     async fn shutdown(&self) -> Result<String> {
+//!         Ok("bye".into())
+//!     }
+//!
+//!     #[prompt]
+//!     async fn admin_help(&self) -> Result<&str> {
+//!         Ok("Use `shutdown` to stop the server.")
+//!     }
+//! }
+//! ```
+//!
+//! `#[mcp_tools]` には `rename_all = ".."` と `client = ".."` を指定できます。`client` を指定すると、`#[mcp_server(client = "..")]` で生成された型付きクライアントにメソッドが追加されます。
+//! `#[mcp_tools]` ブロックのツールは `&mut self` を受け取れません。
+//! 同じ名前のツールを複数のブロックに定義するとコンパイルエラーになります。
+//!
 //! ## 各属性の説明
 //!
 //! ### `#[prompt]`
 //!
 //! ```rust,ignore
-//! #[prompt("name", description = "..", title = "..")]
+//! #[prompt("name", description = "..", title = "..", rename_all = "..")]
 //! async fn func_name(&self) -> Result<GetPromptResult> { }
 //! ```
 //!
 //! - "name" (optional) : プロンプト名。省略した場合は関数名が使用される。
 //! - "description" (optional) : AI向けの関数説明。ドキュメントコメントより優先される。
 //! - "title" (optional) : 人間が読みやすいプロンプトタイトル。
+//! - "rename_all" (optional) : `"camelCase"` などの引数の命名規則。
 //!
 //! 下記のメソッドを実装する。
 //!
@@ -389,7 +464,9 @@
 //! - [`FromStr`] : 文字列から値を復元する為のトレイト
 //!
 //! 引数には `#[arg("name")]` 属性を付与することで名前を指定できる。
-//! 指定しない場合は関数引数名の最初から `_` が取り除かれた名前が使用される。
+//! 指定しない場合は関数引数名の最初から `_` が取り除かれ、`rename_all` で変換された名前が使用される。
+//! `rename_all` には serde と同じ命名規則（`"lowercase"`、`"UPPERCASE"`、`"PascalCase"`、`"camelCase"`、`"snake_case"`、`"SCREAMING_SNAKE_CASE"`、`"kebab-case"`、`"SCREAMING-KEBAB-CASE"`）を指定できる。
+//! `#[mcp_server(rename_all = "..")]` を指定すると impl ブロック内のすべてのプロンプトとツールに適用される。
 //!
 //! 引数には `#[complete(function)]` 属性を付与することで補完機能を追加できる。
 //! 詳細は[補完機能サポート](#補完機能サポート-complete)を参照。
@@ -498,6 +575,11 @@
 //!     idempotent,
 //!     read_only,
 //!     closed_world,
+//!     strict,
+//!     lenient,
+//!     blocking,
+//!     enabled = path::to_fn,
+//!     rename_all = "..",
 //! )]
 //! async fn func_name(&self) -> Result<CallToolResult> { }
 //! ```
@@ -509,6 +591,11 @@
 //! - "idempotent" (optional) : 同じ引数でツールを繰り返し呼び出しても追加の効果がない (MCP仕様: `idempotent = true`)
 //! - "read_only" (optional) : ツールが環境を変更しない (MCP仕様: `read_only = true`)
 //! - "closed_world" (optional) : ツールの相互作用ドメインが閉じている (MCP仕様: `open_world = false`)
+//! - "strict" (optional) : ツールが定義していない名前の引数を含む呼び出しを拒否する
+//! - "lenient" (optional) : `"5"`、`"true"`、JSON エンコードされたオブジェクトなどの引数を、パース前に JSON Schema の型に変換する。すべてのツールで有効にするには `McpServer::lenient_tool_arguments` を使用する
+//! - "blocking" (optional) : `async` でない関数を非同期ランタイムの外で実行する。後述。
+//! - "enabled" (optional) : セッションでツールを使用できるかを決める関数 `fn(&RequestContext) -> bool`。後述。
+//! - "rename_all" (optional) : `"camelCase"` などの引数の命名規則。
 //!
 //! 下記のメソッドを実装する。
 //!
@@ -521,7 +608,59 @@
 //! - [`JsonSchema`]: JSON Schema を生成する為のトレイト（JSON Schema は MCP Client に送信され、AI が引数の構造を理解できるようになる）
 //!
 //! 引数には `#[arg("name")]` 属性を付与することで名前を指定できる。
-//! 指定しない場合は関数引数名の最初から `_` が取り除かれた名前が使用される。
+//! 指定しない場合は関数引数名の最初から `_` が取り除かれ、`rename_all` で変換された名前が使用される。
+//! `rename_all` には serde と同じ命名規則（`"lowercase"`、`"UPPERCASE"`、`"PascalCase"`、`"camelCase"`、`"snake_case"`、`"SCREAMING_SNAKE_CASE"`、`"kebab-case"`、`"SCREAMING-KEBAB-CASE"`）を指定できる。
+//! `#[mcp_server(rename_all = "..")]` を指定すると impl ブロック内のすべてのプロンプトとツールに適用される。
+//!
+//! `#[arg(flatten)]` 属性を付与した引数は、その構造体の JSON Schema が入力スキーマ全体として使用され、`arguments` オブジェクト全体がその構造体に復元される。
+//! 他の引数と組み合わせることはできない。
+//!
+//! `#[arg(default = expr)]` を指定すると引数は省略可能になり、省略された場合や `null` の場合は `expr` が使用される。この値は JSON Schema の `default` として公開される。
+//! `#[arg(example = expr)]` は JSON Schema の `examples` を公開する。
+//! `#[arg(min = .., max = .., min_length = .., max_length = .., pattern = "..")]` は制約を JSON Schema で公開し、ツールを呼び出す前に検査する。
+//! `min_length` と `max_length` は文字列の長さと配列の要素数に適用される。
+//!
+//! `#[arg(secret)]` はパスワードや API トークンなどの引数を JSON Schema で `writeOnly` として公開する。
+//! `#[arg(flatten)]` の構造体のフィールドを含め、`writeOnly` なスキーマの値はログと mcp-attr が生成するエラーから取り除かれる。
+//! `CallToolRequestParams` の `Debug` 出力は、`input_schema` が設定されている場合にそれらの値を取り除く。
+//! サーバーはセッション内で一覧を返したツールについて `McpServer::tools_call` を呼び出す前にこれを設定し、クライアントは一覧を取得したツールのスキーマを `McpClient::tool_input_schema` で提供する。
+//! jsoncall が `info` レベルで出力する生のメッセージ（ターゲット `jsoncall::read_message` と `jsoncall::write_message`）からは取り除かれないため、シークレットをログに残してはならない環境ではこれらのターゲットを無効にすること（例: `RUST_LOG=info,jsoncall::read_message=off`）。
+//!
+//! `McpServer::validate_tool_arguments` が `true` を返す場合（または `McpServerBuilder::validate_tool_arguments(true)` を使用した場合）、ツールを呼び出す前に引数が JSON Schema で検証される。
+//! 不正な引数は、すべての違反とその JSON Pointer を列挙した `INVALID_PARAMS` エラーとして報告される。
+//!
+//! 入力スキーマは `McpServer::schema_settings` が返す [`SchemaSettings`] で生成される（`McpServerBuilder::schema_settings` でも設定できる）。
+//! 複数の引数で使われる型は `$defs` に一度だけ定義され、設定によってドラフト、サブスキーマのインライン化、`Option<T>` の表現方法を選択できる。
+//! `SchemaSettings::compatible()` は `format`、`oneOf`、`$ref` など一部のクライアントが受け付けないキーワードを取り除く。
+//!
+//! 関数は `async fn` ではなく通常の `fn` でもよい。
+//! `#[tool(blocking)]` を指定すると通常の `fn` は `tokio::task::spawn_blocking` で実行されるため、CPU 負荷の高い処理が他のリクエストをブロックしない。
+//! `&self` を受け取るメソッドは代わりに `tokio::task::block_in_place` で実行され、current-thread ランタイムではそのまま実行される。
+//! blocking なツールは `RequestContext` を受け取れない。
+//!
+//! `#[tool(enabled = path::to_fn)]` を指定すると、関数が `true` を返すセッションでのみツールが一覧に含まれ、呼び出せるようになる。
+//! 関数は [`RequestContext`] を受け取るため、クライアント情報やクライアントの機能に応じてツールを切り替えられる。
+//! 無効なツールの呼び出しは、存在しないツールの呼び出しと同じエラーになる。
+//! `McpServerBuilder` で追加するツールには `ToolDefinition::with_enabled` を使用する。
+//!
+//! ```rust
+//! use mcp_attr::Result;
+//! use mcp_attr::server::{mcp_server, McpServer, RequestContext};
+//!
+//! struct ExampleServer;
+//!
+//! fn is_admin_client(cx: &RequestContext) -> bool {
+//!     cx.client_info().name == "admin-console"
+//! }
+//!
+//! #[mcp_server]
+//! impl McpServer for ExampleServer {
+//!     #[tool(enabled = is_admin_client)]
+//!     async fn shutdown(&self) -> Result<String> {
+//!         Ok("bye".into())
+//!     }
+//! }
+//! ```
 //!
 //! 戻り値: [`Result<impl Into<CallToolResult>>`]
 //!
@@ -596,6 +735,33 @@
 //! }
 //! ```
 //!
+//! `#[mcp_server(client = "型名")]` を指定すると、[`McpClient`] をラップした型付きクライアントが生成されます。
+//! 型付きクライアントはツール、プロンプト、リソーステンプレートごとにメソッドを持つため、サーバに対する呼び出しがコンパイル時に検査されます。
+//! `String` 型の引数は `&str` として、それ以外の型の引数は値として受け取ります。
+//!
+//! ```rust
+//! use mcp_attr::client::McpClient;
+//! use mcp_attr::server::{mcp_server, McpServer};
+//! use mcp_attr::Result;
+//!
+//! struct ExampleServer;
+//!
+//! #[mcp_server(client = "ExampleClient")]
+//! impl McpServer for ExampleServer {
+//!     #[tool]
+//!     async fn echo(&self, message: String) -> Result<String> {
+//!         Ok(message)
+//!     }
+//! }
+//!
+//! #[tokio::test]
+//! async fn test_echo() -> Result<()> {
+//!     let client = ExampleClient::new(McpClient::with_server(ExampleServer).await?);
+//!     assert_eq!(client.echo("abc").await.unwrap(), "abc");
+//!     Ok(())
+//! }
+//! ```
+//!
 //! ## License
 //!
 //! This project is dual licensed under Apache-2.0/MIT. See the two LICENSE-\* files for details.
@@ -644,6 +810,9 @@
 //! [`Result<impl Into<ReadResourceResult>>`]: https://docs.rs/mcp-attr/latest/mcp_attr/schema/struct.ReadResourceResult.html
 //! [`Result<impl Into<CallToolResult>>`]: https://docs.rs/mcp-attr/latest/mcp_attr/schema/struct.CallToolResult.html
 //! [`RequestContext`]: https://docs.rs/mcp-attr/latest/mcp_attr/server/struct.RequestContext.html
+//! [`ToolError`]: https://docs.rs/mcp-attr/latest/mcp_attr/server/enum.ToolError.html
+//! [`IntoMcpError`]: https://docs.rs/mcp-attr/latest/mcp_attr/server/trait.IntoMcpError.html
+//! [`SchemaSettings`]: https://docs.rs/mcp-attr/latest/mcp_attr/server/struct.SchemaSettings.html
 //! [`serve_stdio`]: https://docs.rs/mcp-attr/latest/mcp_attr/server/fn.serve_stdio.html
 // #![include_doc("../../README.ja.md", end)]
 // #![include_doc("../../README.md", start)]
@@ -849,9 +1018,9 @@
 //!
 //! ### State Management
 //!
-//! Since values implementing `McpServer` are shared among multiple concurrently executing methods, only `&self` is available. `&mut self` cannot be used.
+//! Since values implementing `McpServer` are shared among multiple concurrently executing methods, handlers take `&self`.
 //!
-//! To maintain state, you need to use thread-safe types with interior mutability like `Mutex`.
+//! To maintain state, you can use thread-safe types with interior mutability like `Mutex`.
 //!
 //! ```rust
 //! use std::sync::Mutex;
@@ -874,6 +1043,34 @@
 //! }
 //! ```
 //!
+//! Tools can also take `&mut self`. In that case `#[mcp_server]` keeps the server in a `RwLock`.
+//! Tools with `read_only` take the read lock, and other tools take the write lock.
+//! Prompts, resources and completions take the read lock.
+//! The results of synchronous methods such as `server_info` and `capabilities` are evaluated once when the server starts.
+//!
+//! ```rust
+//! use mcp_attr::server::{mcp_server, McpServer};
+//! use mcp_attr::Result;
+//!
+//! struct ExampleServer {
+//!   count: u32,
+//! }
+//!
+//! #[mcp_server]
+//! impl McpServer for ExampleServer {
+//!   #[tool]
+//!   fn add_count(&mut self) -> Result<String> {
+//!     self.count += 1;
+//!     Ok(format!("count: {}", self.count))
+//!   }
+//!
+//!   #[tool(read_only)]
+//!   fn get_count(&self) -> Result<String> {
+//!     Ok(format!("count: {}", self.count))
+//!   }
+//! }
+//! ```
+//!
 //! ### Error Handling
 //!
 //! mcp_attr uses `Result`, Rust's standard error handling method.
@@ -919,6 +1116,13 @@
 //! }
 //! ```
 //!
+//! Errors returned by tools are sent as a [`tools/call`] result with `isError` set, so that the model can see the error message.
+//! Only errors in the request itself, such as invalid arguments or an unknown tool name, are sent as JSON-RPC errors.
+//! To choose how an error is reported, return [`ToolError`] from the tool.
+//!
+//! Functions with `#[tool]`, `#[prompt]` and `#[resource]` can also return `Result<T, E>` for any `E` implementing [`IntoMcpError`].
+//! Implement it for your own error types to choose the error code, the public message and the `data` sent to the client.
+//!
 //! ### Calling Client Features
 //!
 //! MCP servers can call client features (such as [`roots/list`]) using [`RequestContext`].
@@ -1012,18 +1216,58 @@
 //!
 //! Completion is only available for `#[prompt]` and `#[resource]` arguments, not for `#[tool]` arguments.
 //!
+//! ### Splitting a Server (`#[mcp_tools]`)
+//!
+//! `#[mcp_server]` can be applied to generic impls such as `impl<S: Store> McpServer for MyServer<S>`.
+//!
+//! Tools, prompts and resources can also be defined in inherent impl blocks with `#[mcp_tools("name")]`, for example in another module.
+//! The blocks are registered by name with `#[mcp_server(tools = ["name", ..])]`.
+//!
+//! ```rust
+//! use mcp_attr::server::{mcp_server, mcp_tools, McpServer};
+//! use mcp_attr::Result;
+//!
+//! struct ExampleServer;
+//!
+//! #[mcp_server(tools = ["admin"])]
+//! impl McpServer for ExampleServer {
+//!     #[tool]
+//!     async fn echo(&self, message: String) -> Result<String> {
+//!         Ok(message)
+//!     }
+//! }
+//!
+//! #[mcp_tools("admin")]
+//! impl ExampleServer {
+//!     #[tool]
+//!     async fn shutdown(&self) -> Result<String> {
+//!         Ok("bye".into())
+//!     }
+//!
+//!     #[prompt]
+//!     async fn admin_help(&self) -> Result<&str> {
+//!         Ok("Use `shutdown` to stop the server.")
+//!     }
+//! }
+//! ```
+//!
+//! `#[mcp_tools]` accepts `rename_all = ".."` and `client = ".."`. With `client`, methods are added to the typed client generated by `#[mcp_server(client = "..")]`.
+//! Tools in `#[mcp_tools]` blocks cannot take `&mut self`.
+//! Defining a tool with the same name in more than one block is a compile error.
+//!
 //! ## Attribute Descriptions
 //!
 //! ### `#[prompt]`
 //!
 //! ```rust,ignore
-//! #[prompt("name", description = "..", title = "..")]
+//! #[prompt("name", description = "..", title = "..", rename_all = "..")]
 //! async fn func_name(&self) -> Result<GetPromptResult> { }
 //! ```
 //!
 //! - "name" (optional): Prompt name. If omitted, the function name is used.
 //! - "description" (optional): Function description for AI. Takes precedence over documentation comments.
 //! - "title" (optional): Human-readable prompt title.
+//! - "rename_all" (optional): Naming convention of arguments, such as `"camelCase"`.
 //!
 //! Implements the following methods:
 //!
@@ -1035,7 +1279,9 @@
 //! - [`FromStr`]: Trait for restoring values from strings
 //!
 //! Arguments can be given names using the `#[arg("name")]` attribute.
-//! If not specified, the name used is the function argument name with leading `_` removed.
+//! If not specified, the name used is the function argument name with leading `_` removed, converted by `rename_all`.
+//! `rename_all` accepts the same conventions as serde (`"lowercase"`, `"UPPERCASE"`, `"PascalCase"`, `"camelCase"`, `"snake_case"`, `"SCREAMING_SNAKE_CASE"`, `"kebab-case"` and `"SCREAMING-KEBAB-CASE"`).
+//! `#[mcp_server(rename_all = "..")]` applies it to every prompt and tool in the impl block.
 //!
 //! Arguments can have completion functionality using the `#[complete(function)]` attribute.
 //! See [Completion Support](#completion-support-complete) for details.
@@ -1144,6 +1390,11 @@
 //!     idempotent,
 //!     read_only,
 //!     closed_world,
+//!     strict,
+//!     lenient,
+//!     blocking,
+//!     enabled = path::to_fn,
+//!     rename_all = "..",
 //! )]
 //! async fn func_name(&self) -> Result<CallToolResult> { }
 //! ```
@@ -1155,6 +1406,11 @@
 //! - "idempotent" (optional): Calling the tool repeatedly with the same arguments has no additional effect (MCP spec: `idempotent = true`)
 //! - "read_only" (optional): Tool does not modify its environment (MCP spec: `read_only = true`)
 //! - "closed_world" (optional): Tool's domain of interaction is closed (MCP spec: `open_world = false`)
+//! - "strict" (optional): Rejects calls with argument names that the tool does not define
+//! - "lenient" (optional): Converts arguments such as `"5"`, `"true"` and JSON-encoded objects to the types in the JSON Schema before parsing. Use `McpServer::lenient_tool_arguments` to enable this for all tools
+//! - "blocking" (optional): Runs a non-`async` function outside the async runtime. See below.
+//! - "enabled" (optional): Function `fn(&RequestContext) -> bool` that decides whether the tool is available in the session. See below.
+//! - "rename_all" (optional): Naming convention of arguments, such as `"camelCase"`.
 //!
 //! Implements the following methods:
 //!
@@ -1167,7 +1423,59 @@
 //! - [`JsonSchema`]: Trait for generating JSON Schema (JSON Schema is sent to MCP Client so AI can understand argument structure)
 //!
 //! Arguments can be given names using the `#[arg("name")]` attribute.
-//! If not specified, the name used is the function argument name with leading `_` removed.
+//! If not specified, the name used is the function argument name with leading `_` removed, converted by `rename_all`.
+//! `rename_all` accepts the same conventions as serde (`"lowercase"`, `"UPPERCASE"`, `"PascalCase"`, `"camelCase"`, `"snake_case"`, `"SCREAMING_SNAKE_CASE"`, `"kebab-case"` and `"SCREAMING-KEBAB-CASE"`).
+//! `#[mcp_server(rename_all = "..")]` applies it to every prompt and tool in the impl block.
+//!
+//! An argument with the `#[arg(flatten)]` attribute uses the JSON Schema of its struct as the whole input schema, and the `arguments` object is deserialized into it.
+//! It cannot be combined with other arguments.
+//!
+//! `#[arg(default = expr)]` makes the argument optional and uses `expr` when it is omitted or `null`. The value is published as `default` in the JSON Schema.
+//! `#[arg(example = expr)]` publishes `examples` in the JSON Schema.
+//! `#[arg(min = .., max = .., min_length = .., max_length = .., pattern = "..")]` publishes the constraints in the JSON Schema and checks them before the tool is called.
+//! `min_length` and `max_length` apply to the length of strings and the number of array items.
+//!
+//! `#[arg(secret)]` marks the argument as `writeOnly` in the JSON Schema for arguments such as passwords and API tokens.
+//! Values of `writeOnly` schemas, including fields of `#[arg(flatten)]` structs, are redacted from logs and from errors produced by mcp-attr.
+//! The `Debug` output of `CallToolRequestParams` redacts them when its `input_schema` is set.
+//! The server sets it before calling `McpServer::tools_call` for tools listed in the session, and the client provides the schemas of listed tools with `McpClient::tool_input_schema`.
+//! The raw messages traced by jsoncall at `info` level (targets `jsoncall::read_message` and `jsoncall::write_message`) are not redacted, so disable these targets where secrets must not be logged (e.g. `RUST_LOG=info,jsoncall::read_message=off`).
+//!
+//! If `McpServer::validate_tool_arguments` returns `true` (or `McpServerBuilder::validate_tool_arguments(true)` is used), arguments are validated against the JSON Schema before the tool is called.
+//! Invalid arguments are reported as an `INVALID_PARAMS` error that lists every violation with its JSON Pointer.
+//!
+//! Input schemas are generated with the [`SchemaSettings`] returned by `McpServer::schema_settings` (or set with `McpServerBuilder::schema_settings`).
+//! Types used by several arguments are defined once in `$defs`, and the settings choose the draft, the inlining of subschemas and how `Option<T>` is described.
+//! `SchemaSettings::compatible()` removes keywords that some clients reject, such as `format`, `oneOf` and `$ref`.
+//!
+//! The function may be a plain `fn` instead of an `async fn`.
+//! With `#[tool(blocking)]`, a plain `fn` is run with `tokio::task::spawn_blocking`, so CPU-heavy work does not block other requests.
+//! Methods taking `&self` are run with `tokio::task::block_in_place` instead, which runs them directly on a current-thread runtime.
+//! Blocking tools cannot take a `RequestContext`.
+//!
+//! With `#[tool(enabled = path::to_fn)]`, the tool is listed and callable only in sessions where the function returns `true`.
+//! The function receives the [`RequestContext`], so tools can depend on the client information and the client capabilities.
+//! Calls to a disabled tool fail in the same way as calls to an unknown tool.
+//! For tools added with `McpServerBuilder`, use `ToolDefinition::with_enabled`.
+//!
+//! ```rust
+//! use mcp_attr::Result;
+//! use mcp_attr::server::{mcp_server, McpServer, RequestContext};
+//!
+//! struct ExampleServer;
+//!
+//! fn is_admin_client(cx: &RequestContext) -> bool {
+//!     cx.client_info().name == "admin-console"
+//! }
+//!
+//! #[mcp_server]
+//! impl McpServer for ExampleServer {
+//!     #[tool(enabled = is_admin_client)]
+//!     async fn shutdown(&self) -> Result<String> {
+//!         Ok("bye".into())
+//!     }
+//! }
+//! ```
 //!
 //! Return value: [`Result<impl Into<CallToolResult>>`]
 //!
@@ -1242,6 +1550,33 @@
 //! }
 //! ```
 //!
+//! By specifying `#[mcp_server(client = "TypeName")]`, a typed client that wraps [`McpClient`] is generated.
+//! The typed client has one method per tool, prompt and resource template, so calls are checked against the server at compile time.
+//! Arguments of type `String` are taken as `&str`, and arguments of other types are taken by value.
+//!
+//! ```rust
+//! use mcp_attr::client::McpClient;
+//! use mcp_attr::server::{mcp_server, McpServer};
+//! use mcp_attr::Result;
+//!
+//! struct ExampleServer;
+//!
+//! #[mcp_server(client = "ExampleClient")]
+//! impl McpServer for ExampleServer {
+//!     #[tool]
+//!     async fn echo(&self, message: String) -> Result<String> {
+//!         Ok(message)
+//!     }
+//! }
+//!
+//! #[tokio::test]
+//! async fn test_echo() -> Result<()> {
+//!     let client = ExampleClient::new(McpClient::with_server(ExampleServer).await?);
+//!     assert_eq!(client.echo("abc").await.unwrap(), "abc");
+//!     Ok(())
+//! }
+//! ```
+//!
 //! ## License
 //!
 //! This project is dual licensed under Apache-2.0/MIT. See the two LICENSE-\* files for details.
@@ -1290,5 +1625,8 @@
 //! [`Result<impl Into<ReadResourceResult>>`]: https://docs.rs/mcp-attr/latest/mcp_attr/schema/struct.ReadResourceResult.html
 //! [`Result<impl Into<CallToolResult>>`]: https://docs.rs/mcp-attr/latest/mcp_attr/schema/struct.CallToolResult.html
 //! [`RequestContext`]: https://docs.rs/mcp-attr/latest/mcp_attr/server/struct.RequestContext.html
+//! [`ToolError`]: https://docs.rs/mcp-attr/latest/mcp_attr/server/enum.ToolError.html
+//! [`IntoMcpError`]: https://docs.rs/mcp-attr/latest/mcp_attr/server/trait.IntoMcpError.html
+//! [`SchemaSettings`]: https://docs.rs/mcp-attr/latest/mcp_attr/server/struct.SchemaSettings.html
 //! [`serve_stdio`]: https://docs.rs/mcp-attr/latest/mcp_attr/server/fn.serve_stdio.html
 // #![include_doc("../../README.md", end)]
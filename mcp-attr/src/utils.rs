@@ -154,6 +154,7 @@ impl ProtocolVersion {
     pub const V_2024_11_05: Self = Self("2024-11-05");
     pub const V_2025_03_26: Self = Self("2025-03-26");
     pub const V_2025_06_18: Self = Self("2025-06-18");
+    pub const ALL: &'static [Self] = &[Self::V_2024_11_05, Self::V_2025_03_26, Self::V_2025_06_18];
 
     pub fn as_str(&self) -> &'static str {
         self.0
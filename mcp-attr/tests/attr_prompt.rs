@@ -294,7 +294,7 @@ async fn get_name_mismatch() -> Result<()> {
     let a = client
         .prompts_get(GetPromptRequestParams::new("unknown"))
         .await;
-    assert_error(a, ErrorCode::METHOD_NOT_FOUND);
+    assert_error(a, ErrorCode::INVALID_PARAMS);
     Ok(())
 }
 
@@ -0,0 +1,309 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use pretty_assertions::assert_eq;
+use serde_json::{Value, json};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream, Lines},
+    test,
+    time::sleep,
+};
+
+use mcp_attr::{
+    ErrorCode, Result,
+    client::{BatchRequest, McpClient, McpClientBuilder, RequestTimeout},
+    jsoncall::SessionOptions,
+    schema::{CallToolRequestParams, CallToolResult, ContentBlock},
+    server::{McpServer, mcp_server, serve},
+    utils::ProtocolVersion,
+};
+
+struct MyMcpServer;
+
+#[mcp_server]
+impl McpServer for MyMcpServer {
+    #[tool]
+    async fn echo(&self, text: String) -> Result<String> {
+        Ok(text)
+    }
+
+    #[tool]
+    async fn slow(&self) -> Result<String> {
+        sleep(Duration::from_secs(10)).await;
+        Ok("slow".into())
+    }
+}
+
+/// Spawns a server and records the lines written by the client
+fn spawn_recorded_server() -> (
+    BufReader<DuplexStream>,
+    DuplexStream,
+    Arc<Mutex<Vec<Value>>>,
+) {
+    let (reader, mut server_writer) = spawn_server();
+    let (client_writer, proxy_reader) = tokio::io::duplex(1024 * 64);
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let recorded = lines.clone();
+    tokio::spawn(async move {
+        let mut proxy_lines = BufReader::new(proxy_reader).lines();
+        while let Ok(Some(line)) = proxy_lines.next_line().await {
+            recorded
+                .lock()
+                .unwrap()
+                .push(serde_json::from_str(&line).unwrap());
+            let line = format!("{line}\n");
+            if server_writer.write_all(line.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    });
+    (reader, client_writer, lines)
+}
+
+fn spawn_server() -> (BufReader<DuplexStream>, DuplexStream) {
+    let (client_writer, server_reader) = tokio::io::duplex(1024 * 64);
+    let (server_writer, client_reader) = tokio::io::duplex(1024 * 64);
+    tokio::spawn(async move {
+        serve(
+            MyMcpServer,
+            BufReader::new(server_reader),
+            server_writer,
+            &SessionOptions::default(),
+        )
+        .await
+    });
+    (BufReader::new(client_reader), client_writer)
+}
+
+struct RawClient {
+    lines: Lines<BufReader<DuplexStream>>,
+    writer: DuplexStream,
+}
+impl RawClient {
+    async fn new(protocol_version: &str) -> Result<Self> {
+        let (reader, writer) = spawn_server();
+        let mut c = Self {
+            lines: reader.lines(),
+            writer,
+        };
+        c.send(json!({
+            "jsonrpc": "2.0",
+            "id": 0,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": protocol_version,
+                "capabilities": {},
+                "clientInfo": { "name": "raw", "version": "0.0.0" },
+            },
+        }))
+        .await?;
+        c.recv().await?;
+        c.send(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+            .await?;
+        Ok(c)
+    }
+    async fn send(&mut self, m: Value) -> Result<()> {
+        self.writer.write_all(format!("{m}\n").as_bytes()).await?;
+        Ok(())
+    }
+    async fn recv(&mut self) -> Result<Value> {
+        let line = self.lines.next_line().await?.unwrap();
+        Ok(serde_json::from_str(&line)?)
+    }
+}
+
+fn call_echo(id: u64, text: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": "tools/call",
+        "params": { "name": "echo", "arguments": { "text": text } },
+    })
+}
+
+#[test]
+async fn server_answers_batch_with_batch() -> Result<()> {
+    let mut c = RawClient::new("2025-03-26").await?;
+    c.send(json!([
+        call_echo(1, "a"),
+        { "jsonrpc": "2.0", "method": "notifications/roots/list_changed" },
+        call_echo(2, "b"),
+    ]))
+    .await?;
+    let a = c.recv().await?;
+    let mut ids: Vec<u64> = a
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["id"].as_u64().unwrap())
+        .collect();
+    ids.sort();
+    assert_eq!(ids, vec![1, 2]);
+    Ok(())
+}
+
+#[test]
+async fn server_answers_batch_one_by_one_for_other_versions() -> Result<()> {
+    for v in ["2024-11-05", "2025-06-18"] {
+        let mut c = RawClient::new(v).await?;
+        c.send(json!([call_echo(1, "a")])).await?;
+        let a = c.recv().await?;
+        assert_eq!(a["id"], json!(1), "{v}");
+    }
+    Ok(())
+}
+
+#[test]
+async fn server_rejects_batch_before_initialize() -> Result<()> {
+    let (reader, mut writer) = spawn_server();
+    writer
+        .write_all(format!("{}\n", json!([call_echo(1, "a")])).as_bytes())
+        .await?;
+    let line = reader.lines().next_line().await?.unwrap();
+    let a: Value = serde_json::from_str(&line)?;
+    assert_eq!(a["id"], Value::Null);
+    assert_eq!(a["error"]["code"], json!(ErrorCode::INVALID_REQUEST));
+    Ok(())
+}
+
+#[test]
+async fn server_rejects_empty_batch() -> Result<()> {
+    let mut c = RawClient::new("2025-03-26").await?;
+    c.send(json!([])).await?;
+    let a = c.recv().await?;
+    assert_eq!(a["error"]["code"], json!(ErrorCode::INVALID_REQUEST));
+    Ok(())
+}
+
+async fn client(version: ProtocolVersion) -> Result<McpClient> {
+    let (reader, writer) = spawn_server();
+    Ok(McpClientBuilder::new()
+        .with_protocol_versions(&[version])
+        .build(reader, writer)
+        .await?)
+}
+
+fn echo_request(text: &str) -> Result<BatchRequest> {
+    let mut p = CallToolRequestParams::new("echo");
+    p.arguments = Some(json!({ "text": text }).as_object().unwrap().clone());
+    Ok(BatchRequest::new("tools/call").with_params(p)?)
+}
+
+#[test]
+async fn client_batch() -> Result<()> {
+    let client = client(ProtocolVersion::V_2025_03_26).await?;
+    let results = client
+        .batch(vec![echo_request("a")?, echo_request("b")?])
+        .await?;
+    let mut a = Vec::new();
+    for r in results {
+        a.push(serde_json::from_value::<CallToolResult>(r?)?);
+    }
+    let e: Vec<CallToolResult> = vec![
+        ContentBlock::from("a").into(),
+        ContentBlock::from("b").into(),
+    ];
+    assert_eq!(a, e);
+    Ok(())
+}
+
+#[test]
+async fn client_batch_with_concurrent_request() -> Result<()> {
+    let (reader, writer, lines) = spawn_recorded_server();
+    let client = McpClientBuilder::new()
+        .with_protocol_versions(&[ProtocolVersion::V_2025_03_26])
+        .build(reader, writer)
+        .await?;
+    let mut p = CallToolRequestParams::new("echo");
+    p.arguments = Some(json!({ "text": "c" }).as_object().unwrap().clone());
+    let (results, a) = tokio::join!(
+        client.batch(vec![echo_request("a")?, echo_request("b")?]),
+        client.tools_call(p),
+    );
+    let e: CallToolResult = ContentBlock::from("c").into();
+    assert_eq!(a?, e);
+    let mut a = Vec::new();
+    for r in results? {
+        a.push(serde_json::from_value::<CallToolResult>(r?)?);
+    }
+    let e: Vec<CallToolResult> = vec![
+        ContentBlock::from("a").into(),
+        ContentBlock::from("b").into(),
+    ];
+    assert_eq!(a, e);
+
+    let lines = lines.lock().unwrap();
+    let batches: Vec<Vec<&Value>> = lines
+        .iter()
+        .filter_map(|m| m.as_array())
+        .map(|ms| {
+            ms.iter()
+                .map(|m| &m["params"]["arguments"]["text"])
+                .collect()
+        })
+        .collect();
+    assert_eq!(batches, vec![vec!["a", "b"]]);
+    let single = lines
+        .iter()
+        .filter(|m| m["params"]["arguments"]["text"] == "c")
+        .count();
+    assert_eq!(single, 1);
+
+    let mut ids: Vec<&Value> = lines
+        .iter()
+        .flat_map(|m| m.as_array().map_or(vec![m], |ms| ms.iter().collect()))
+        .filter(|m| m.get("method").is_some())
+        .filter_map(|m| m.get("id"))
+        .collect();
+    let len = ids.len();
+    ids.sort_by_key(|id| id.to_string());
+    ids.dedup();
+    assert_eq!(ids.len(), len);
+    Ok(())
+}
+
+#[test]
+async fn client_batch_timeout() -> Result<()> {
+    let (reader, writer) = spawn_server();
+    let client = McpClientBuilder::new()
+        .with_protocol_versions(&[ProtocolVersion::V_2025_03_26])
+        .with_timeout(Duration::from_millis(200))
+        .build(reader, writer)
+        .await?;
+    let slow = BatchRequest::new("tools/call").with_params(CallToolRequestParams::new("slow"))?;
+    let results = client.batch(vec![echo_request("a")?, slow]).await?;
+    for r in &results {
+        assert!(RequestTimeout::from_error(r.as_ref().unwrap_err()).is_some());
+    }
+    let results = client.batch(vec![echo_request("b")?]).await?;
+    let e: CallToolResult = ContentBlock::from("b").into();
+    assert_eq!(
+        serde_json::from_value::<CallToolResult>(results[0].clone()?)?,
+        e
+    );
+    Ok(())
+}
+
+#[test]
+async fn client_batch_requires_2025_03_26() -> Result<()> {
+    let client = client(ProtocolVersion::V_2025_06_18).await?;
+    let e = client.batch(vec![echo_request("a")?]).await.unwrap_err();
+    assert!(
+        e.error_object()
+            .is_some_and(|e| e.code == ErrorCode::INVALID_REQUEST)
+    );
+    Ok(())
+}
+
+#[test]
+async fn client_batch_in_process() -> Result<()> {
+    let client = McpClientBuilder::new()
+        .with_protocol_versions(&[ProtocolVersion::V_2025_03_26])
+        .build_with_server(MyMcpServer)
+        .await?;
+    let results = client.batch(vec![echo_request("a")?]).await?;
+    assert_eq!(results.len(), 1);
+    Ok(())
+}
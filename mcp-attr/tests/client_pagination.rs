@@ -0,0 +1,71 @@
+use futures::{StreamExt, TryStreamExt};
+use pretty_assertions::assert_eq;
+use tokio::test;
+
+use mcp_attr::{
+    Result,
+    client::McpClient,
+    schema::{ListToolsRequestParams, ListToolsResult, Tool, ToolInputSchema},
+    server::{McpServer, RequestContext},
+};
+
+struct PagedServer;
+
+impl McpServer for PagedServer {
+    async fn tools_list(
+        &self,
+        p: ListToolsRequestParams,
+        _cx: &mut RequestContext,
+    ) -> Result<ListToolsResult> {
+        let page: usize = p.cursor.as_deref().unwrap_or("0").parse()?;
+        let mut r: ListToolsResult = vec![
+            Tool::new(&format!("tool_{page}_a"), ToolInputSchema::new()),
+            Tool::new(&format!("tool_{page}_b"), ToolInputSchema::new()),
+        ]
+        .into();
+        if page < 2 {
+            r.next_cursor = Some((page + 1).to_string());
+        }
+        Ok(r)
+    }
+}
+
+struct RepeatedCursorServer;
+
+impl McpServer for RepeatedCursorServer {
+    async fn tools_list(
+        &self,
+        _p: ListToolsRequestParams,
+        _cx: &mut RequestContext,
+    ) -> Result<ListToolsResult> {
+        let mut r: ListToolsResult = vec![Tool::new("tool", ToolInputSchema::new())].into();
+        r.next_cursor = Some("same".to_string());
+        Ok(r)
+    }
+}
+
+#[test]
+async fn tools_list_all() -> Result<()> {
+    let client = McpClient::with_server(PagedServer).await?;
+    let a: Vec<String> = client
+        .tools_list_all()
+        .map_ok(|t| t.name)
+        .try_collect()
+        .await?;
+    let e = [
+        "tool_0_a", "tool_0_b", "tool_1_a", "tool_1_b", "tool_2_a", "tool_2_b",
+    ];
+    assert_eq!(a, e);
+    Ok(())
+}
+
+#[test]
+async fn tools_list_all_repeated_cursor() -> Result<()> {
+    let client = McpClient::with_server(RepeatedCursorServer).await?;
+    let a: Vec<_> = client.tools_list_all().collect().await;
+    assert_eq!(a.len(), 3);
+    assert!(a[0].is_ok());
+    assert!(a[1].is_ok());
+    assert!(a[2].is_err());
+    Ok(())
+}
@@ -0,0 +1,68 @@
+#![cfg(unix)]
+
+use std::time::Duration;
+
+use tokio::{process::Command, test};
+
+use mcp_attr::Result;
+use mcp_attr::client::{McpClientBuilder, StderrMode};
+
+const INITIALIZE: &str = r#"read line
+id=$(echo "$line" | sed 's/.*"id":\([^,}]*\).*/\1/')
+echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"protocolVersion\":\"2025-06-18\",\"capabilities\":{},\"serverInfo\":{\"name\":\"sh\",\"version\":\"0.0.0\"}}}"
+echo "started" >&2
+"#;
+
+fn command(after_initialize: &str) -> Command {
+    let mut c = Command::new("sh");
+    c.arg("-c").arg(format!("{INITIALIZE}{after_initialize}"));
+    c
+}
+
+#[test]
+async fn shutdown_closes_stdin() -> Result<()> {
+    let client = McpClientBuilder::new()
+        .with_stderr(StderrMode::Tracing)
+        .build_with_command(&mut command("cat > /dev/null"))
+        .await?;
+    assert!(client.child().is_some());
+    let status = client.shutdown(Duration::from_secs(10)).await?.unwrap();
+    assert!(status.success());
+    Ok(())
+}
+
+#[test]
+async fn shutdown_kills_after_timeout() -> Result<()> {
+    let client = McpClientBuilder::new()
+        .with_stderr(StderrMode::Null)
+        .build_with_command(&mut command("exec sleep 30"))
+        .await?;
+    let status = client.shutdown(Duration::from_millis(100)).await?.unwrap();
+    assert!(!status.success());
+    Ok(())
+}
+
+#[test]
+async fn restart() -> Result<()> {
+    let mut client = McpClientBuilder::new()
+        .with_stderr(StderrMode::Null)
+        .build_with_command(&mut command("cat > /dev/null"))
+        .await?;
+    let pid = client.child().unwrap().id();
+    client.restart(Duration::from_secs(10)).await?;
+    assert_eq!(client.server_info().name, "sh");
+    assert_ne!(client.child().unwrap().id(), pid);
+    assert!(client.child_mut().unwrap().try_wait()?.is_none());
+    Ok(())
+}
+
+#[test]
+async fn shutdown_without_process() -> Result<()> {
+    struct MyMcpServer;
+    impl mcp_attr::server::McpServer for MyMcpServer {}
+
+    let client = mcp_attr::client::McpClient::with_server(MyMcpServer).await?;
+    assert!(client.child().is_none());
+    assert_eq!(client.shutdown(Duration::from_secs(1)).await?, None);
+    Ok(())
+}
@@ -0,0 +1,285 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+use std::time::Duration;
+
+use pretty_assertions::assert_eq;
+use tokio::{test, time::sleep};
+
+use mcp_attr::{
+    Result, SessionResult, bail,
+    client::{CallOptions, McpClient, McpClientBuilder, RequestTimeout, RetryPolicy},
+    schema::{
+        CallToolRequestParams, CallToolResult, ContentBlock, ListPromptsRequestParams,
+        ListPromptsResult, ListToolsRequestParams, ListToolsResult, Tool, ToolAnnotations,
+        ToolInputSchema,
+    },
+    server::{McpServer, RequestContext},
+};
+
+#[derive(Clone, Default)]
+struct SlowServer {
+    calls: Arc<AtomicUsize>,
+    list_calls: Arc<AtomicUsize>,
+    fail_list: bool,
+    slow_calls: usize,
+    cancelled: Arc<AtomicBool>,
+}
+
+struct SetOnDrop(Arc<AtomicBool>);
+
+impl Drop for SetOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+impl McpServer for SlowServer {
+    async fn tools_list(
+        &self,
+        _p: ListToolsRequestParams,
+        _cx: &mut RequestContext,
+    ) -> Result<ListToolsResult> {
+        self.list_calls.fetch_add(1, Ordering::SeqCst);
+        if self.fail_list {
+            bail!("list failed");
+        }
+        Ok(vec![
+            Tool::new("idempotent", ToolInputSchema::new()).with_annotations(ToolAnnotations {
+                idempotent_hint: Some(true),
+                ..Default::default()
+            }),
+            Tool::new("read_only", ToolInputSchema::new()).with_annotations(ToolAnnotations {
+                read_only_hint: Some(true),
+                ..Default::default()
+            }),
+            Tool::new("other", ToolInputSchema::new()),
+            Tool::new("failing", ToolInputSchema::new()).with_annotations(ToolAnnotations {
+                idempotent_hint: Some(true),
+                ..Default::default()
+            }),
+        ]
+        .into())
+    }
+
+    async fn tools_call(
+        &self,
+        p: CallToolRequestParams,
+        _cx: &mut RequestContext,
+    ) -> Result<CallToolResult> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if p.name == "failing" {
+            bail!("tool failed");
+        }
+        self.wait().await;
+        Ok(ContentBlock::from(p.name.as_str()).into())
+    }
+
+    async fn prompts_list(
+        &self,
+        _p: ListPromptsRequestParams,
+        _cx: &mut RequestContext,
+    ) -> Result<ListPromptsResult> {
+        self.wait().await;
+        Ok(ListPromptsResult::default())
+    }
+}
+impl SlowServer {
+    async fn wait(&self) {
+        if self.calls.load(Ordering::SeqCst) <= self.slow_calls {
+            let _guard = SetOnDrop(self.cancelled.clone());
+            sleep(Duration::from_secs(60)).await;
+        }
+    }
+}
+
+fn server(slow_calls: usize) -> SlowServer {
+    SlowServer {
+        slow_calls,
+        ..Default::default()
+    }
+}
+
+fn retry() -> RetryPolicy {
+    RetryPolicy::new().with_initial_backoff(Duration::from_millis(1))
+}
+
+#[test]
+async fn timeout_cancels_request() -> Result<()> {
+    let server = server(1);
+    let client = McpClientBuilder::new()
+        .with_timeout(Duration::from_millis(50))
+        .build_with_server(server.clone())
+        .await?;
+    let a = client.tools_call(CallToolRequestParams::new("other")).await;
+    assert_timeout(a, "tools/call", Duration::from_millis(50));
+    sleep(Duration::from_millis(100)).await;
+    assert!(server.cancelled.load(Ordering::SeqCst));
+    Ok(())
+}
+
+#[test]
+async fn timeout_per_call() -> Result<()> {
+    let client = McpClient::with_server(server(1)).await?;
+    let options = CallOptions::new().with_timeout(Duration::from_millis(50));
+    let a = client
+        .tools_call_with(CallToolRequestParams::new("other"), &options)
+        .await;
+    assert_timeout(a, "tools/call", Duration::from_millis(50));
+    Ok(())
+}
+
+#[test]
+async fn timeout_per_call_for_other_requests() -> Result<()> {
+    let client = McpClient::with_server(server(1)).await?;
+    let options = CallOptions::new().with_timeout(Duration::from_millis(50));
+    let a = client.prompts_list_with(None, &options).await;
+    assert_timeout(a, "prompts/list", Duration::from_millis(50));
+    Ok(())
+}
+
+#[test]
+async fn retry_idempotent_tool() -> Result<()> {
+    let server = server(2);
+    let client = McpClientBuilder::new()
+        .with_timeout(Duration::from_millis(50))
+        .with_retry(retry())
+        .build_with_server(server.clone())
+        .await?;
+    client.tools_list(None).await?;
+    for name in ["idempotent", "read_only"] {
+        server.calls.store(0, Ordering::SeqCst);
+        let a = client.tools_call(CallToolRequestParams::new(name)).await?;
+        let e: CallToolResult = ContentBlock::from(name).into();
+        assert_eq!(a, e);
+        assert_eq!(server.calls.load(Ordering::SeqCst), 3);
+    }
+    Ok(())
+}
+
+#[test]
+async fn no_retry_for_other_tool() -> Result<()> {
+    let server = server(2);
+    let client = McpClientBuilder::new()
+        .with_timeout(Duration::from_millis(50))
+        .with_retry(retry())
+        .build_with_server(server.clone())
+        .await?;
+    client.tools_list(None).await?;
+    let a = client.tools_call(CallToolRequestParams::new("other")).await;
+    assert!(a.is_err());
+    assert_eq!(server.calls.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[test]
+async fn retry_unlisted_tool() -> Result<()> {
+    let server = server(2);
+    let client = McpClientBuilder::new()
+        .with_timeout(Duration::from_millis(50))
+        .with_retry(retry())
+        .build_with_server(server.clone())
+        .await?;
+    let a = client
+        .tools_call(CallToolRequestParams::new("idempotent"))
+        .await?;
+    let e: CallToolResult = ContentBlock::from("idempotent").into();
+    assert_eq!(a, e);
+    assert_eq!(server.calls.load(Ordering::SeqCst), 3);
+    Ok(())
+}
+
+#[test]
+async fn no_retry_for_unlisted_other_tool() -> Result<()> {
+    let server = server(2);
+    let client = McpClientBuilder::new()
+        .with_timeout(Duration::from_millis(50))
+        .with_retry(retry())
+        .build_with_server(server.clone())
+        .await?;
+    let a = client.tools_call(CallToolRequestParams::new("other")).await;
+    assert!(a.is_err());
+    assert_eq!(server.calls.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[test]
+async fn no_retry_for_server_error() -> Result<()> {
+    let server = server(0);
+    let client = McpClientBuilder::new()
+        .with_retry(retry())
+        .build_with_server(server.clone())
+        .await?;
+    client.tools_list(None).await?;
+    let a = client
+        .tools_call(CallToolRequestParams::new("failing"))
+        .await;
+    assert!(a.is_err());
+    assert!(RequestTimeout::from_error(&a.unwrap_err()).is_none());
+    assert_eq!(server.calls.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[test]
+async fn unknown_tool_is_listed_once() -> Result<()> {
+    let server = server(0);
+    let client = McpClientBuilder::new()
+        .with_retry(retry())
+        .build_with_server(server.clone())
+        .await?;
+    for _ in 0..2 {
+        let a = client
+            .tools_call(CallToolRequestParams::new("unknown"))
+            .await?;
+        let e: CallToolResult = ContentBlock::from("unknown").into();
+        assert_eq!(a, e);
+    }
+    assert_eq!(server.list_calls.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[test]
+async fn no_retry_if_tools_list_fails() -> Result<()> {
+    let server = SlowServer {
+        slow_calls: 2,
+        fail_list: true,
+        ..Default::default()
+    };
+    let client = McpClientBuilder::new()
+        .with_timeout(Duration::from_millis(50))
+        .with_retry(retry())
+        .build_with_server(server.clone())
+        .await?;
+    let a = client
+        .tools_call(CallToolRequestParams::new("idempotent"))
+        .await;
+    assert_timeout(a, "tools/call", Duration::from_millis(50));
+    assert_eq!(server.calls.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[test]
+async fn retry_gives_up() -> Result<()> {
+    let server = server(usize::MAX);
+    let client = McpClientBuilder::new()
+        .with_timeout(Duration::from_millis(20))
+        .with_retry(retry().with_max_retries(2))
+        .build_with_server(server.clone())
+        .await?;
+    client.tools_list(None).await?;
+    let a = client
+        .tools_call(CallToolRequestParams::new("idempotent"))
+        .await;
+    assert!(a.is_err());
+    assert_eq!(server.calls.load(Ordering::SeqCst), 3);
+    Ok(())
+}
+
+#[track_caller]
+fn assert_timeout<T: std::fmt::Debug>(a: SessionResult<T>, method: &str, timeout: Duration) {
+    let e = a.expect_err("expected timeout");
+    let a = RequestTimeout::from_error(&e).unwrap_or_else(|| panic!("not a timeout: {e:#?}"));
+    assert_eq!(a.method, method);
+    assert_eq!(a.timeout, timeout);
+}
@@ -0,0 +1,79 @@
+use pretty_assertions::assert_eq;
+use serde::{Deserialize, Serialize};
+use tokio::test;
+
+use mcp_attr::Result;
+use mcp_attr::client::{McpClient, TypedCallError};
+use mcp_attr::schema::{CallToolResult, ContentBlock};
+use mcp_attr::server::{McpServer, mcp_server};
+use mcp_attr::utils::Json;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+struct MyMcpServer;
+
+#[mcp_server]
+impl McpServer for MyMcpServer {
+    #[tool]
+    async fn add(&self, x: i32, y: i32) -> Result<Json<Point>> {
+        Ok(Json::from(&Point { x: x + 1, y: y + 1 })?)
+    }
+
+    #[tool]
+    async fn fail(&self) -> Result<CallToolResult> {
+        let mut r = CallToolResult::from(ContentBlock::from("something went wrong"));
+        r.is_error = Some(true);
+        Ok(r)
+    }
+
+    #[resource("my_app://point")]
+    async fn point(&self) -> Result<String> {
+        Ok(serde_json::to_string(&Point { x: 1, y: 2 })?)
+    }
+}
+
+#[test]
+async fn call_tool_typed() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a: Point = client
+        .call_tool_typed("add", &Point { x: 1, y: 2 })
+        .await
+        .unwrap();
+    assert_eq!(a, Point { x: 2, y: 3 });
+    Ok(())
+}
+
+#[test]
+async fn call_tool_typed_is_error() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client.call_tool_typed::<_, ()>("fail", &()).await;
+    let Err(e) = a else {
+        panic!("expected error");
+    };
+    assert!(matches!(e, TypedCallError::Tool(_)));
+    assert_eq!(
+        e.tool_error_message(),
+        Some("something went wrong".to_string())
+    );
+    Ok(())
+}
+
+#[test]
+async fn call_tool_typed_not_object() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client.call_tool_typed::<_, Point>("add", &1).await;
+    assert!(matches!(a, Err(TypedCallError::ArgumentsNotObject)));
+    Ok(())
+}
+
+#[test]
+async fn read_resource_typed() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a: Point = client.read_resource_typed("my_app://point").await.unwrap();
+    assert_eq!(a, Point { x: 1, y: 2 });
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use mcp_attr::{
+    server::{mcp_server, mcp_tools, McpServer},
+    Result,
+};
+
+struct MyServer;
+
+#[mcp_server(tools = ["extra"])]
+impl McpServer for MyServer {
+    #[tool]
+    async fn f(&self) -> Result<String> {
+        todo!()
+    }
+}
+
+#[mcp_tools("extra")]
+impl MyServer {
+    #[tool("f")]
+    async fn g(&self) -> Result<String> {
+        todo!()
+    }
+}
+
+fn main() {
+    let _ = MyServer.into_handler();
+}
@@ -0,0 +1,29 @@
+use mcp_attr::{
+    server::{mcp_server, mcp_tools, McpServer},
+    Result,
+};
+
+struct MyServer;
+
+#[mcp_server(tools = ["a", "b"])]
+impl McpServer for MyServer {}
+
+#[mcp_tools("a")]
+impl MyServer {
+    #[tool]
+    async fn f(&self) -> Result<String> {
+        todo!()
+    }
+}
+
+#[mcp_tools("b")]
+impl MyServer {
+    #[tool("f")]
+    async fn g(&self) -> Result<String> {
+        todo!()
+    }
+}
+
+fn main() {
+    let _ = MyServer.into_handler();
+}
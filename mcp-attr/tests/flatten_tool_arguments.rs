@@ -0,0 +1,160 @@
+use jsoncall::{ErrorCode, SessionResult};
+use pretty_assertions::assert_eq;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tokio::test;
+
+use mcp_attr::Result;
+use mcp_attr::client::McpClient;
+use mcp_attr::schema::{CallToolRequestParams, CallToolResult, ContentBlock};
+use mcp_attr::server::{McpServer, McpServerBuilder, mcp_server, route, tool};
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+struct SearchArgs {
+    /// Search query
+    query: String,
+    limit: Option<u32>,
+}
+
+struct MyMcpServer;
+
+#[mcp_server(client = "MyMcpClient")]
+impl McpServer for MyMcpServer {
+    #[tool]
+    async fn search(&self, #[arg(flatten)] args: SearchArgs) -> Result<String> {
+        Ok(format!("{} {:?}", args.query, args.limit))
+    }
+
+    #[tool(strict, lenient)]
+    async fn search_strict(&self, #[arg(flatten)] args: SearchArgs) -> Result<String> {
+        Ok(format!("{} {:?}", args.query, args.limit))
+    }
+}
+
+#[tool]
+async fn route_search(#[arg(flatten)] args: SearchArgs) -> Result<String> {
+    Ok(format!("{} {:?}", args.query, args.limit))
+}
+
+fn call(name: &str, arguments: Value) -> CallToolRequestParams {
+    let mut p = CallToolRequestParams::new(name);
+    p.arguments = Some(arguments.as_object().unwrap().clone());
+    p
+}
+
+fn text(s: &str) -> CallToolResult {
+    ContentBlock::from(s).into()
+}
+
+#[track_caller]
+fn assert_pointer<T: std::fmt::Debug>(a: SessionResult<T>, pointer: &str) {
+    let e = a.unwrap_err();
+    let e = e.error_object().expect("no error object");
+    assert_eq!(e.code, ErrorCode::INVALID_PARAMS, "{e:#?}");
+    let data = e.data.as_ref().unwrap();
+    let a = data
+        .get("pointer")
+        .or_else(|| data["violations"][0].get("pointer"));
+    assert_eq!(a, Some(&json!(pointer)), "{e:#?}");
+}
+
+#[test]
+async fn input_schema() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let tools = client.tools_list(None).await?;
+    let tool = tools
+        .tools
+        .into_iter()
+        .find(|t| t.name == "search")
+        .unwrap();
+    let a = serde_json::to_value(tool.input_schema)?;
+    assert_eq!(
+        a,
+        json!({
+            "type": "object",
+            "properties": {
+                "query": { "type": "string", "description": "Search query" },
+                "limit": { "type": ["integer", "null"], "format": "uint32", "minimum": 0.0 },
+            },
+            "required": ["query"],
+        })
+    );
+    Ok(())
+}
+
+#[test]
+async fn call_flatten() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(call("search", json!({ "query": "rust", "limit": 3 })))
+        .await?;
+    assert_eq!(a, text("rust Some(3)"));
+
+    let a = client
+        .tools_call(call("search", json!({ "query": "rust" })))
+        .await?;
+    assert_eq!(a, text("rust None"));
+    Ok(())
+}
+
+#[test]
+async fn invalid_field() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(call("search", json!({ "query": "rust", "limit": -1 })))
+        .await;
+    assert_pointer(a, "/arguments/limit");
+
+    let a = client
+        .tools_call(CallToolRequestParams::new("search"))
+        .await;
+    assert_pointer(a, "/arguments");
+    Ok(())
+}
+
+#[test]
+async fn strict_and_lenient() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(call(
+            "search_strict",
+            json!({ "query": "rust", "limit": "3" }),
+        ))
+        .await?;
+    assert_eq!(a, text("rust Some(3)"));
+
+    let a = client
+        .tools_call(call(
+            "search_strict",
+            json!({ "query": "rust", "other": 1 }),
+        ))
+        .await;
+    assert_pointer(a, "/arguments/other");
+    Ok(())
+}
+
+#[test]
+async fn route_flatten() -> Result<()> {
+    let server = McpServerBuilder::new().route(route![route_search]).build();
+    let client = McpClient::with_server(server).await?;
+    let a = client
+        .tools_call(call("route_search", json!({ "query": "rust" })))
+        .await?;
+    assert_eq!(a, text("rust None"));
+    Ok(())
+}
+
+#[test]
+async fn typed_client() -> Result<()> {
+    let client = MyMcpClient::new(McpClient::with_server(MyMcpServer).await?);
+    let a = client
+        .search(SearchArgs {
+            query: "rust".to_string(),
+            limit: Some(2),
+        })
+        .await
+        .unwrap();
+    assert_eq!(a, "rust Some(2)");
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use jsoncall::{ErrorCode, ErrorObject};
+use pretty_assertions::assert_eq;
+use serde_json::{Value, json};
+use tokio::test;
+
+use mcp_attr::Result;
+use mcp_attr::client::{McpClient, McpClientBuilder};
+use mcp_attr::schema::{
+    CallToolRequestParams, CallToolResult, ContentBlock, GetPromptRequestParams,
+    ReadResourceRequestParams,
+};
+use mcp_attr::server::{IntoMcpError, McpServer, mcp_server};
+
+enum ServiceError {
+    NotFound(String),
+    Database(String),
+}
+
+impl IntoMcpError for ServiceError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            ServiceError::NotFound(_) => ErrorCode::INVALID_PARAMS,
+            ServiceError::Database(_) => ErrorCode::INTERNAL_ERROR,
+        }
+    }
+    fn public_message(&self) -> Option<String> {
+        match self {
+            ServiceError::NotFound(id) => Some(format!("`{id}` was not found")),
+            ServiceError::Database(_) => Some("database error".to_string()),
+        }
+    }
+    fn data(&self) -> Option<Value> {
+        match self {
+            ServiceError::NotFound(id) => Some(json!({ "id": id })),
+            ServiceError::Database(_) => None,
+        }
+    }
+    fn internal_message(&self) -> Option<String> {
+        match self {
+            ServiceError::NotFound(_) => None,
+            ServiceError::Database(e) => Some(e.clone()),
+        }
+    }
+    fn expose_internals(&self) -> bool {
+        true
+    }
+}
+
+/// Error that leaves exposing the internal message to the session
+struct QueryError {
+    with_data: bool,
+}
+
+impl IntoMcpError for QueryError {
+    fn public_message(&self) -> Option<String> {
+        (!self.with_data).then(|| "query failed".to_string())
+    }
+    fn data(&self) -> Option<Value> {
+        self.with_data.then(|| json!({ "retry": true }))
+    }
+    fn internal_message(&self) -> Option<String> {
+        Some("timeout".to_string())
+    }
+}
+
+struct MyMcpServer;
+
+#[mcp_server]
+impl McpServer for MyMcpServer {
+    #[prompt]
+    async fn prompt(&self, id: String) -> std::result::Result<String, ServiceError> {
+        Err(ServiceError::NotFound(id))
+    }
+
+    #[resource("my_app://items/{id}")]
+    async fn item(&self, id: String) -> std::result::Result<String, ServiceError> {
+        Err(ServiceError::NotFound(id))
+    }
+
+    #[tool]
+    async fn tool(&self) -> std::result::Result<String, ServiceError> {
+        Err(ServiceError::Database("connection refused".to_string()))
+    }
+
+    #[tool]
+    async fn query(&self) -> std::result::Result<String, QueryError> {
+        Err(QueryError { with_data: false })
+    }
+
+    #[prompt]
+    async fn query_prompt(&self) -> std::result::Result<String, QueryError> {
+        Err(QueryError { with_data: false })
+    }
+
+    #[prompt]
+    async fn query_data(&self) -> std::result::Result<String, QueryError> {
+        Err(QueryError { with_data: true })
+    }
+}
+
+async fn client(expose_internals: bool) -> Result<McpClient> {
+    Ok(McpClientBuilder::new()
+        .with_expose_internals(expose_internals)
+        .build_with_server(MyMcpServer)
+        .await?)
+}
+
+fn assert_not_found(e: Option<&ErrorObject>, id: &str) {
+    let e = e.expect("no error object");
+    assert_eq!(e.code, ErrorCode::INVALID_PARAMS);
+    assert_eq!(e.message, format!("`{id}` was not found"));
+    assert_eq!(e.data, Some(json!({ "id": id })));
+}
+
+#[test]
+async fn prompt_error() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let e = client
+        .prompts_get(GetPromptRequestParams::new("prompt").with_arguments([("id", "a")]))
+        .await
+        .unwrap_err();
+    assert_not_found(e.error_object(), "a");
+    Ok(())
+}
+
+#[test]
+async fn resource_error() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let e = client
+        .resources_read(ReadResourceRequestParams::new("my_app://items/b"))
+        .await
+        .unwrap_err();
+    assert_not_found(e.error_object(), "b");
+    Ok(())
+}
+
+#[test]
+async fn tool_error() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(CallToolRequestParams::new("tool"))
+        .await?;
+    let mut e = CallToolResult::from(vec![ContentBlock::from(
+        "database error: connection refused",
+    )]);
+    e.is_error = Some(true);
+    assert_eq!(a, e);
+    Ok(())
+}
+
+#[test]
+async fn internal_message_follows_session() -> Result<()> {
+    for (expose_internals, e) in [(false, "query failed"), (true, "query failed: timeout")] {
+        let client = client(expose_internals).await?;
+        let a = client
+            .tools_call(CallToolRequestParams::new("query"))
+            .await?;
+        let mut e = CallToolResult::from(vec![ContentBlock::from(e)]);
+        e.is_error = Some(true);
+        assert_eq!(a, e, "expose_internals: {expose_internals}");
+
+        let a = client
+            .prompts_get(GetPromptRequestParams::new("query_prompt"))
+            .await
+            .unwrap_err();
+        let a = a.error_object().expect("no error object");
+        assert_eq!(a.message, "query failed");
+        let source = a.data.as_ref().and_then(|d| d.get("source"));
+        assert_eq!(
+            source,
+            expose_internals.then(|| json!("timeout")).as_ref(),
+            "expose_internals: {expose_internals}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+async fn data_without_public_message() -> Result<()> {
+    for (expose_internals, e) in [
+        (false, ErrorCode::INTERNAL_ERROR.message()),
+        (true, "timeout"),
+    ] {
+        let client = client(expose_internals).await?;
+        let a = client
+            .prompts_get(GetPromptRequestParams::new("query_data"))
+            .await
+            .unwrap_err();
+        let a = a.error_object().expect("no error object");
+        assert_eq!(a.code, ErrorCode::INTERNAL_ERROR);
+        assert_eq!(a.message, e, "expose_internals: {expose_internals}");
+        assert_eq!(a.data, Some(json!({ "retry": true })));
+    }
+    Ok(())
+}
@@ -0,0 +1,193 @@
+use jsoncall::{ErrorCode, SessionResult};
+use pretty_assertions::assert_eq;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::test;
+
+use mcp_attr::Result;
+use mcp_attr::client::McpClient;
+use mcp_attr::schema::{CallToolRequestParams, CallToolResult, ContentBlock};
+use mcp_attr::server::{
+    McpServer, McpServerBuilder, SchemaDraft, SchemaSettings, mcp_server, route, tool,
+};
+
+#[derive(Deserialize, JsonSchema)]
+struct Item {
+    name: String,
+    count: u32,
+}
+
+struct MyMcpServer;
+
+#[mcp_server]
+impl McpServer for MyMcpServer {
+    #[tool(lenient)]
+    async fn lenient(
+        &self,
+        count: u32,
+        ratio: f64,
+        enabled: bool,
+        item: Item,
+        items: Vec<Item>,
+        limit: Option<u32>,
+    ) -> Result<String> {
+        Ok(format!(
+            "{count} {ratio} {enabled} {}:{} {} {limit:?}",
+            item.name,
+            item.count,
+            items.len()
+        ))
+    }
+
+    #[tool]
+    async fn strict(&self, count: u32) -> Result<String> {
+        Ok(count.to_string())
+    }
+}
+
+struct LenientServer;
+
+#[mcp_server]
+impl McpServer for LenientServer {
+    fn lenient_tool_arguments(&self) -> bool {
+        true
+    }
+
+    #[tool]
+    async fn add(&self, a: u32, item: Item) -> Result<String> {
+        Ok(format!("{} {}", a + item.count, item.name))
+    }
+}
+
+struct DraftServer;
+
+#[mcp_server]
+impl McpServer for DraftServer {
+    fn schema_settings(&self) -> SchemaSettings {
+        SchemaSettings::new().with_draft(SchemaDraft::Draft2020_12)
+    }
+
+    #[tool(lenient)]
+    async fn add(&self, items: Vec<Item>) -> Result<String> {
+        Ok(items.iter().map(|i| i.count).sum::<u32>().to_string())
+    }
+}
+
+#[tool]
+async fn route_add(a: u32, b: u32) -> Result<String> {
+    Ok((a + b).to_string())
+}
+
+fn call(name: &str, arguments: Value) -> CallToolRequestParams {
+    let mut p = CallToolRequestParams::new(name);
+    p.arguments = Some(arguments.as_object().unwrap().clone());
+    p
+}
+
+fn text(s: &str) -> CallToolResult {
+    ContentBlock::from(s).into()
+}
+
+#[track_caller]
+fn assert_error<T: std::fmt::Debug>(a: SessionResult<T>, code: ErrorCode) {
+    match a {
+        Ok(_) => panic!("expected error.\n{a:#?}"),
+        Err(e) => {
+            if let Some(e) = e.error_object() {
+                assert_eq!(e.code, code, "{e:#?}");
+            } else {
+                panic!("no error object\n{e:#?}");
+            }
+        }
+    }
+}
+
+#[test]
+async fn lenient_tool() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(call(
+            "lenient",
+            json!({
+                "count": "5",
+                "ratio": "0.5",
+                "enabled": "true",
+                "item": "{\"name\":\"a\",\"count\":\"2\"}",
+                "items": "[{\"name\":\"b\",\"count\":1}]",
+                "limit": "3",
+            }),
+        ))
+        .await?;
+    assert_eq!(a, text("5 0.5 true a:2 1 Some(3)"));
+    Ok(())
+}
+
+#[test]
+async fn lenient_tool_keeps_invalid_values() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(call(
+            "lenient",
+            json!({
+                "count": "five",
+                "ratio": 0.5,
+                "enabled": true,
+                "item": { "name": "a", "count": 2 },
+                "items": [],
+            }),
+        ))
+        .await;
+    assert_error(a, ErrorCode::INVALID_PARAMS);
+    Ok(())
+}
+
+#[test]
+async fn not_lenient_by_default() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(call("strict", json!({ "count": "5" })))
+        .await;
+    assert_error(a, ErrorCode::INVALID_PARAMS);
+    Ok(())
+}
+
+#[test]
+async fn lenient_server() -> Result<()> {
+    let client = McpClient::with_server(LenientServer).await?;
+    let a = client
+        .tools_call(call(
+            "add",
+            json!({ "a": "1", "item": { "name": "x", "count": "2" } }),
+        ))
+        .await?;
+    assert_eq!(a, text("3 x"));
+    Ok(())
+}
+
+#[test]
+async fn lenient_builder() -> Result<()> {
+    let server = McpServerBuilder::new()
+        .route(route![route_add])
+        .lenient_tool_arguments(true)
+        .build();
+    let client = McpClient::with_server(server).await?;
+    let a = client
+        .tools_call(call("route_add", json!({ "a": "1", "b": 2 })))
+        .await?;
+    assert_eq!(a, text("3"));
+    Ok(())
+}
+
+#[test]
+async fn lenient_follows_schema_settings() -> Result<()> {
+    let client = McpClient::with_server(DraftServer).await?;
+    let a = client
+        .tools_call(call(
+            "add",
+            json!({ "items": [{ "name": "a", "count": "2" }, { "name": "b", "count": "3" }] }),
+        ))
+        .await?;
+    assert_eq!(a, text("5"));
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use pretty_assertions::assert_eq;
+use tokio::test;
+
+use mcp_attr::client::{McpClient, McpClientBuilder};
+use mcp_attr::schema::{
+    CallToolRequestParams, CallToolResult, CompleteRequestParams, CompleteRequestParamsArgument,
+    ContentBlock, PromptReference, ReadResourceRequestParams, Root,
+};
+use mcp_attr::server::{
+    McpServer, McpServerBuilder, RequestContext, complete_fn, mcp_server, prompt, route,
+};
+use mcp_attr::{CapabilityNotSupported, Result};
+
+struct MyMcpServer;
+
+#[mcp_server]
+impl McpServer for MyMcpServer {
+    #[tool]
+    async fn roots(&self, cx: &RequestContext) -> Result<String> {
+        Ok(match cx.roots_list().await {
+            Ok(roots) => format!("{}", roots.len()),
+            Err(e) => format!("{:?}", CapabilityNotSupported::from_error(&e)),
+        })
+    }
+}
+
+#[complete_fn]
+async fn complete_name(_value: &str) -> Result<Vec<&'static str>> {
+    Ok(vec!["a"])
+}
+
+#[prompt]
+async fn greet(#[complete(complete_name)] name: String) -> Result<String> {
+    Ok(format!("Hello, {name}!"))
+}
+
+#[test]
+async fn client_checks_server_capabilities() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    client.tools_list(None).await?;
+
+    let e = client.prompts_list(None).await.unwrap_err();
+    let a = CapabilityNotSupported::from_error(&e);
+    let e = CapabilityNotSupported {
+        method: "prompts/list".to_string(),
+        capability: "prompts".to_string(),
+    };
+    assert_eq!(a, Some(e));
+
+    let e = client
+        .resources_read(ReadResourceRequestParams::new("my_app://a"))
+        .await
+        .unwrap_err();
+    let a = CapabilityNotSupported::from_error(&e).map(|e| e.capability);
+    assert_eq!(a, Some("resources".to_string()));
+    Ok(())
+}
+
+#[test]
+async fn server_checks_client_capabilities() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(CallToolRequestParams::new("roots"))
+        .await?;
+    let e = CapabilityNotSupported {
+        method: "roots/list".to_string(),
+        capability: "roots".to_string(),
+    };
+    let e: CallToolResult = vec![ContentBlock::from(format!("{:?}", Some(e)).as_str())].into();
+    assert_eq!(a, e);
+
+    let client = McpClientBuilder::new()
+        .with_roots(vec![Root::new("file:///")])
+        .build_with_server(MyMcpServer)
+        .await?;
+    let a = client
+        .tools_call(CallToolRequestParams::new("roots"))
+        .await?;
+    let e: CallToolResult = vec![ContentBlock::from("1")].into();
+    assert_eq!(a, e);
+    Ok(())
+}
+
+#[test]
+async fn completions_capability_is_advertised_only_when_defined() -> Result<()> {
+    assert!(MyMcpServer.capabilities().completions.is_none());
+
+    let server = McpServerBuilder::new().route(route![greet]).build();
+    assert!(server.capabilities().completions.is_some());
+    let client = McpClient::with_server(server).await?;
+    let a = client
+        .completion_complete(CompleteRequestParams::new(
+            PromptReference::new("greet"),
+            CompleteRequestParamsArgument::new("name", ""),
+        ))
+        .await?;
+    assert_eq!(a.completion.values, vec!["a"]);
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use pretty_assertions::assert_eq;
+use serde::{Deserialize, Serialize};
+use tokio::test;
+
+use mcp_attr::Result;
+use mcp_attr::client::McpClient;
+use mcp_attr::schema::{GetPromptResult, ReadResourceResult};
+use mcp_attr::server::{McpServer, mcp_server};
+use mcp_attr::utils::Json;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Sum {
+    value: i32,
+}
+
+struct MyMcpServer;
+
+#[mcp_server(client = "MyMcpClient")]
+impl McpServer for MyMcpServer {
+    #[tool]
+    async fn echo(&self, message: String, suffix: Option<String>) -> Result<String> {
+        Ok(format!("{message}{}", suffix.unwrap_or_default()))
+    }
+
+    #[tool("add_numbers")]
+    async fn add(&self, a: i32, #[arg("b_value")] b: i32) -> Result<Json<Sum>> {
+        Ok(Json::from(&Sum { value: a + b })?)
+    }
+
+    #[prompt]
+    async fn greet(&self, name: String) -> Result<String> {
+        Ok(format!("Hello, {name}!"))
+    }
+
+    #[resource("my_app://files/{name}.txt")]
+    async fn read_file(&self, name: String) -> Result<String> {
+        Ok(format!("Content of {name}.txt"))
+    }
+}
+
+async fn client() -> Result<MyMcpClient> {
+    Ok(MyMcpClient::new(McpClient::with_server(MyMcpServer).await?))
+}
+
+#[test]
+async fn call_tool_text() -> Result<()> {
+    let client = client().await?;
+    assert_eq!(client.echo("abc", None).await.unwrap(), "abc");
+    assert_eq!(client.echo("abc", Some("!")).await.unwrap(), "abc!");
+    Ok(())
+}
+
+#[test]
+async fn call_tool_json() -> Result<()> {
+    let client = client().await?;
+    assert_eq!(client.add(1, 2).await.unwrap(), Sum { value: 3 });
+    Ok(())
+}
+
+#[test]
+async fn get_prompt() -> Result<()> {
+    let client = client().await?;
+    let a = client.greet("world").await?;
+    let e: GetPromptResult = "Hello, world!".into();
+    assert_eq!(a, e);
+    Ok(())
+}
+
+#[test]
+async fn read_resource() -> Result<()> {
+    let client = client().await?;
+    let a = client.read_file("a b").await?;
+    let e: ReadResourceResult = "Content of a b.txt".into();
+    assert_eq!(a, e);
+    Ok(())
+}
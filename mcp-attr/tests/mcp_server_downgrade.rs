@@ -0,0 +1,116 @@
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tokio::test;
+
+use mcp_attr::{
+    Result,
+    client::{McpClient, McpClientBuilder},
+    jsoncall::Session,
+    schema::{
+        CallToolRequestParams, CallToolResult, ContentBlock, ListToolsRequestParams,
+        ListToolsResult, ResourceLink, TextContent, Tool, ToolAnnotations, ToolInputSchema,
+    },
+    server::{McpServer, RequestContext},
+};
+
+struct MyMcpServer;
+
+impl McpServer for MyMcpServer {
+    async fn tools_list(
+        &self,
+        _p: ListToolsRequestParams,
+        _cx: &mut RequestContext,
+    ) -> Result<ListToolsResult> {
+        Ok(vec![tool()].into())
+    }
+
+    async fn tools_call(
+        &self,
+        _p: CallToolRequestParams,
+        _cx: &mut RequestContext,
+    ) -> Result<CallToolResult> {
+        let mut r = CallToolResult::from(vec![ContentBlock::ResourceLink(ResourceLink {
+            annotations: None,
+            description: None,
+            meta: Default::default(),
+            mime_type: None,
+            name: "a".to_string(),
+            size: None,
+            title: None,
+            type_: "resource_link".to_string(),
+            uri: "my_app://a".to_string(),
+        })]);
+        r.structured_content = json!({ "value": 1 }).as_object().unwrap().clone();
+        Ok(r)
+    }
+}
+
+fn tool() -> Tool {
+    Tool::new("tool", ToolInputSchema::new())
+        .with_title("Tool")
+        .with_annotations(ToolAnnotations {
+            read_only_hint: Some(true),
+            ..Default::default()
+        })
+}
+
+async fn client(protocol_version: &str) -> Result<(McpClient, Session)> {
+    let (handler, options, mut p) = McpClientBuilder::new().build_raw();
+    p.protocol_version = protocol_version.to_string();
+    let (client, server) = Session::new_channel(handler, MyMcpServer.into_handler(), &options);
+    Ok((McpClient::initialize(client, p).await?, server))
+}
+
+#[test]
+async fn tools_list_2024_11_05() -> Result<()> {
+    let (client, _server) = client("2024-11-05").await?;
+    let a = client.tools_list(None).await?;
+    let mut e = tool();
+    e.title = None;
+    e.annotations = None;
+    assert_eq!(a.tools, vec![e]);
+    Ok(())
+}
+
+#[test]
+async fn tools_list_2025_03_26() -> Result<()> {
+    let (client, _server) = client("2025-03-26").await?;
+    let a = client.tools_list(None).await?;
+    let mut e = tool();
+    e.title = None;
+    assert_eq!(a.tools, vec![e]);
+    Ok(())
+}
+
+#[test]
+async fn tools_list_latest() -> Result<()> {
+    let (client, _server) = client("2025-06-18").await?;
+    let a = client.tools_list(None).await?;
+    assert_eq!(a.tools, vec![tool()]);
+    Ok(())
+}
+
+#[test]
+async fn tools_call_2025_03_26() -> Result<()> {
+    let (client, _server) = client("2025-03-26").await?;
+    let a = client
+        .tools_call(CallToolRequestParams::new("tool"))
+        .await?;
+    let e: CallToolResult = vec![TextContent::new("my_app://a")].into();
+    assert_eq!(a, e);
+    Ok(())
+}
+
+#[test]
+async fn tools_call_latest() -> Result<()> {
+    let (client, _server) = client("2025-06-18").await?;
+    let a = client
+        .tools_call(CallToolRequestParams::new("tool"))
+        .await?;
+    assert!(matches!(a.content[0], ContentBlock::ResourceLink(_)));
+    assert_eq!(
+        a.structured_content,
+        *json!({ "value": 1 }).as_object().unwrap()
+    );
+    Ok(())
+}
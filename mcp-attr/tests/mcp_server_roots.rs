@@ -1,12 +1,23 @@
-use std::env::current_dir;
+use std::env::{current_dir, temp_dir};
+use std::fs::{create_dir_all, remove_dir_all};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
+use jsoncall::{
+    Handler, NO_PARAMS, Params, RequestContext as JsonRpcRequestContext, Response, Session,
+    SessionOptions,
+};
 use pretty_assertions::assert_eq;
 use tokio::test;
 
-use mcp_attr::Result;
-use mcp_attr::client::McpClientBuilder;
-use mcp_attr::schema::{CallToolRequestParams, CallToolResult, ContentBlock, Root};
+use mcp_attr::client::{McpClient, McpClientBuilder};
+use mcp_attr::schema::{
+    CallToolRequestParams, CallToolResult, ClientCapabilities, ClientCapabilitiesRoots,
+    ContentBlock, Implementation, InitializeRequestParams, ListRootsResult, Root,
+};
 use mcp_attr::server::{McpServer, RequestContext, mcp_server};
+use mcp_attr::utils::ProtocolVersion;
+use mcp_attr::{Result, SessionResult};
 
 struct MyMcpServer;
 
@@ -25,6 +36,11 @@ impl McpServer for MyMcpServer {
         }
         Ok(res)
     }
+
+    #[tool]
+    async fn resolve(&self, path: String, cx: &RequestContext) -> Result<String> {
+        Ok(cx.resolve_within_roots(path).await?.display().to_string())
+    }
 }
 
 #[test]
@@ -48,3 +64,207 @@ async fn list_roots() -> Result<()> {
     assert_eq!(a, e);
     Ok(())
 }
+
+#[test]
+async fn resolve_within_roots_inside() -> Result<()> {
+    let root = TempRoot::new("inside");
+    let root = root.path();
+    create_dir_all(root.join("a")).unwrap();
+    let client = client_with_root(root).await?;
+
+    let a = call_resolve(
+        &client,
+        &root.join("a").join("new.txt").display().to_string(),
+    )
+    .await?;
+    let e: CallToolResult = vec![ContentBlock::from(
+        root.join("a")
+            .join("new.txt")
+            .display()
+            .to_string()
+            .as_str(),
+    )]
+    .into();
+    assert_eq!(a, e);
+
+    let a = call_resolve(&client, "a/new.txt").await?;
+    assert_eq!(a, e);
+    Ok(())
+}
+
+#[test]
+async fn resolve_within_roots_parent_dir() -> Result<()> {
+    let root = TempRoot::new("parent_dir");
+    let root = root.path();
+    let client = client_with_root(root).await?;
+    let a = call_resolve(&client, &root.join("..").display().to_string()).await?;
+    assert_outside_of_roots(a);
+    let a = call_resolve(&client, "../x.txt").await?;
+    assert_outside_of_roots(a);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+async fn resolve_within_roots_symlink_escape() -> Result<()> {
+    let root = TempRoot::new("symlink");
+    let outside = TempRoot::new("symlink_outside");
+    std::os::unix::fs::symlink(outside.path(), root.path().join("link")).unwrap();
+    let client = client_with_root(root.path()).await?;
+    let a = call_resolve(&client, "link/secret.txt").await?;
+    assert_outside_of_roots(a);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+async fn resolve_within_roots_dangling_symlink_escape() -> Result<()> {
+    let root = TempRoot::new("dangling");
+    let outside = TempRoot::new("dangling_outside");
+    std::os::unix::fs::symlink(outside.path().join("new.txt"), root.path().join("link")).unwrap();
+    let client = client_with_root(root.path()).await?;
+    let a = call_resolve(&client, "link").await?;
+    assert_outside_of_roots(a);
+    let a = call_resolve(&client, &root.path().join("link").display().to_string()).await?;
+    assert_outside_of_roots(a);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+async fn resolve_within_roots_dangling_symlink_inside() -> Result<()> {
+    let root = TempRoot::new("dangling_inside");
+    let root = root.path();
+    std::os::unix::fs::symlink("new.txt", root.join("link")).unwrap();
+    let client = client_with_root(root).await?;
+    let a = call_resolve(&client, "link").await?;
+    let e: CallToolResult = vec![ContentBlock::from(
+        root.join("new.txt").display().to_string().as_str(),
+    )]
+    .into();
+    assert_eq!(a, e);
+    Ok(())
+}
+
+#[test]
+async fn roots_cache_cleared_by_list_changed() -> Result<()> {
+    let roots = Arc::new(Mutex::new(vec![Root::new("file:///a")]));
+    let count = Arc::new(Mutex::new(0));
+    let handler = RootsHandler {
+        roots: roots.clone(),
+        count: count.clone(),
+    };
+    let (client, _server) = Session::new_channel(
+        handler,
+        MyMcpServer.into_handler(),
+        &SessionOptions::default(),
+    );
+    let p = InitializeRequestParams {
+        capabilities: ClientCapabilities {
+            roots: Some(ClientCapabilitiesRoots {
+                list_changed: Some(true),
+            }),
+            ..Default::default()
+        },
+        client_info: Implementation::new("test", "0.0.0"),
+        protocol_version: ProtocolVersion::LATEST.to_string(),
+    };
+    let client = McpClient::initialize(client, p).await?;
+
+    for _ in 0..2 {
+        let a = client
+            .tools_call(CallToolRequestParams::new("echo_roots"))
+            .await?;
+        assert_eq!(a, roots_result(&roots.lock().unwrap()));
+    }
+    assert_eq!(*count.lock().unwrap(), 1);
+
+    *roots.lock().unwrap() = vec![Root::new("file:///a"), Root::new("file:///b")];
+    client
+        .session()
+        .notification("notifications/roots/list_changed", NO_PARAMS)?;
+    let a = client
+        .tools_call(CallToolRequestParams::new("echo_roots"))
+        .await?;
+    assert_eq!(a, roots_result(&roots.lock().unwrap()));
+    assert_eq!(*count.lock().unwrap(), 2);
+    Ok(())
+}
+
+fn roots_result(roots: &[Root]) -> CallToolResult {
+    let e: Vec<ContentBlock> = roots
+        .iter()
+        .map(|r| {
+            r.to_file_path()
+                .unwrap()
+                .display()
+                .to_string()
+                .as_str()
+                .into()
+        })
+        .collect();
+    e.into()
+}
+
+struct RootsHandler {
+    roots: Arc<Mutex<Vec<Root>>>,
+    count: Arc<Mutex<usize>>,
+}
+impl Handler for RootsHandler {
+    fn request(
+        &mut self,
+        method: &str,
+        _params: Params,
+        cx: JsonRpcRequestContext,
+    ) -> jsoncall::Result<Response> {
+        match method {
+            "roots/list" => {
+                *self.count.lock().unwrap() += 1;
+                let roots = self.roots.lock().unwrap().clone();
+                cx.handle(Ok(ListRootsResult::from(roots)))
+            }
+            _ => cx.method_not_found(),
+        }
+    }
+}
+
+struct TempRoot(PathBuf);
+
+impl TempRoot {
+    fn new(name: &str) -> Self {
+        let root = temp_dir().join(format!("mcp_attr_roots_{name}_{}", std::process::id()));
+        let _ = remove_dir_all(&root);
+        create_dir_all(&root).unwrap();
+        Self(root.canonicalize().unwrap())
+    }
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+impl Drop for TempRoot {
+    fn drop(&mut self) {
+        let _ = remove_dir_all(&self.0);
+    }
+}
+
+async fn client_with_root(root: &Path) -> Result<McpClient> {
+    Ok(McpClientBuilder::new()
+        .with_roots(vec![Root::from_file_path(root).unwrap()])
+        .build_with_server(MyMcpServer)
+        .await?)
+}
+
+async fn call_resolve(client: &McpClient, path: &str) -> SessionResult<CallToolResult> {
+    let p = CallToolRequestParams::new("resolve")
+        .with_argument("path", path)
+        .map_err(|e| e.to_error_object(true))?;
+    client.tools_call(p).await
+}
+
+fn assert_outside_of_roots(a: CallToolResult) {
+    assert_eq!(a.is_error, Some(true), "{a:#?}");
+    let ContentBlock::TextContent(text) = &a.content[0] else {
+        panic!("expected text content.\n{a:#?}");
+    };
+    assert!(text.text.contains("outside of the roots"), "{a:#?}");
+}
@@ -266,7 +266,7 @@ async fn call_name_mismatch() -> Result<()> {
     let a = client
         .tools_call(CallToolRequestParams::new("unknown"))
         .await;
-    assert_error(a, ErrorCode::METHOD_NOT_FOUND);
+    assert_error(a, ErrorCode::INVALID_PARAMS);
     Ok(())
 }
 
@@ -0,0 +1,150 @@
+use pretty_assertions::assert_eq;
+use serde_json::{Value, json};
+use tokio::test;
+
+use mcp_attr::Result;
+use mcp_attr::client::McpClient;
+use mcp_attr::schema::{
+    CallToolRequestParams, CallToolResult, ContentBlock, GetPromptRequestParams, GetPromptResult,
+    ReadResourceRequestParams, ReadResourceResult,
+};
+use mcp_attr::server::{McpServer, mcp_server, mcp_tools};
+
+trait Store: Send + Sync + 'static {
+    fn get(&self, key: &str) -> Option<String>;
+}
+
+struct FixedStore;
+
+impl Store for FixedStore {
+    fn get(&self, key: &str) -> Option<String> {
+        Some(format!("value of {key}"))
+    }
+}
+
+struct MyMcpServer<S> {
+    store: S,
+}
+
+#[mcp_server(tools = ["extra"], client = "MyMcpClient")]
+impl<S: Store> McpServer for MyMcpServer<S> {
+    #[tool]
+    async fn get(&self, key: String) -> Result<String> {
+        Ok(self.store.get(&key).unwrap_or_default())
+    }
+}
+
+#[mcp_tools("extra", client = "MyMcpClient")]
+impl<S: Store> MyMcpServer<S> {
+    #[tool]
+    async fn get_upper(&self, key: String) -> Result<String> {
+        Ok(self.store.get(&key).unwrap_or_default().to_uppercase())
+    }
+
+    #[prompt]
+    async fn describe(&self, key: String) -> Result<String> {
+        Ok(format!("Describe {key}"))
+    }
+
+    #[resource("store://{key}")]
+    async fn read_key(&self, key: String) -> Result<String> {
+        Ok(self.store.get(&key).unwrap_or_default())
+    }
+}
+
+struct ToolsOnlyServer;
+
+#[mcp_server(tools = ["only"])]
+impl McpServer for ToolsOnlyServer {}
+
+#[mcp_tools("only")]
+impl ToolsOnlyServer {
+    #[tool]
+    async fn ping(&self) -> Result<String> {
+        Ok("pong".into())
+    }
+}
+
+fn server() -> MyMcpServer<FixedStore> {
+    MyMcpServer { store: FixedStore }
+}
+
+fn call(name: &str, arguments: Value) -> CallToolRequestParams {
+    let mut p = CallToolRequestParams::new(name);
+    p.arguments = Some(arguments.as_object().unwrap().clone());
+    p
+}
+
+fn text(s: &str) -> CallToolResult {
+    ContentBlock::from(s).into()
+}
+
+#[test]
+async fn list() -> Result<()> {
+    let client = McpClient::with_server(server()).await?;
+    let tools = client.tools_list(None).await?;
+    let names: Vec<_> = tools.tools.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, vec!["get", "get_upper"]);
+    let prompts = client.prompts_list(None).await?;
+    assert_eq!(prompts.prompts[0].name, "describe");
+    let templates = client.resources_templates_list(None).await?;
+    assert_eq!(
+        templates.resource_templates[0].uri_template,
+        "store://{key}"
+    );
+    Ok(())
+}
+
+#[test]
+async fn call_tools() -> Result<()> {
+    let client = McpClient::with_server(server()).await?;
+    let a = client
+        .tools_call(call("get", json!({ "key": "a" })))
+        .await?;
+    assert_eq!(a, text("value of a"));
+    let a = client
+        .tools_call(call("get_upper", json!({ "key": "a" })))
+        .await?;
+    assert_eq!(a, text("VALUE OF A"));
+    let a = client.tools_call(call("unknown", json!({}))).await;
+    assert!(a.is_err());
+    Ok(())
+}
+
+#[test]
+async fn prompt_and_resource() -> Result<()> {
+    let client = McpClient::with_server(server()).await?;
+    let a = client
+        .prompts_get(GetPromptRequestParams::new("describe").with_arguments(vec![("key", "a")]))
+        .await?;
+    let e: GetPromptResult = "Describe a".into();
+    assert_eq!(a, e);
+    let a = client
+        .resources_read(ReadResourceRequestParams::new("store://b"))
+        .await?;
+    let e: ReadResourceResult = "value of b".into();
+    assert_eq!(a, e);
+    Ok(())
+}
+
+#[test]
+async fn capabilities() -> Result<()> {
+    let server = ToolsOnlyServer;
+    let capabilities = server.capabilities();
+    assert!(capabilities.tools.is_some());
+    assert!(capabilities.prompts.is_none());
+    assert!(capabilities.resources.is_none());
+
+    let client = McpClient::with_server(server).await?;
+    let a = client.tools_call(call("ping", json!({}))).await?;
+    assert_eq!(a, text("pong"));
+    Ok(())
+}
+
+#[test]
+async fn typed_client() -> Result<()> {
+    let client = MyMcpClient::new(McpClient::with_server(server()).await?);
+    assert_eq!(client.get("x").await.unwrap(), "value of x");
+    assert_eq!(client.get_upper("x").await.unwrap(), "VALUE OF X");
+    Ok(())
+}
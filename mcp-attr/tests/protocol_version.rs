@@ -0,0 +1,137 @@
+use pretty_assertions::assert_eq;
+use tokio::test;
+
+use mcp_attr::{
+    ErrorCode, Result,
+    client::{McpClient, McpClientBuilder, UnsupportedProtocolVersion},
+    jsoncall::Session,
+    server::{McpServer, McpServerBuilder},
+    utils::ProtocolVersion,
+};
+
+const V1: ProtocolVersion = ProtocolVersion::V_2024_11_05;
+const V2: ProtocolVersion = ProtocolVersion::V_2025_03_26;
+const V3: ProtocolVersion = ProtocolVersion::V_2025_06_18;
+
+fn subsets() -> Vec<Vec<ProtocolVersion>> {
+    let all = ProtocolVersion::ALL;
+    (1..1 << all.len())
+        .map(|mask| {
+            all.iter()
+                .enumerate()
+                .filter(|(i, _)| mask & (1 << i) != 0)
+                .map(|(_, v)| *v)
+                .collect()
+        })
+        .collect()
+}
+
+async fn negotiate(
+    client_versions: &[ProtocolVersion],
+    server_versions: &[ProtocolVersion],
+) -> Option<ProtocolVersion> {
+    let server = McpServerBuilder::new()
+        .protocol_versions(server_versions)
+        .build();
+    let client = McpClientBuilder::new()
+        .with_protocol_versions(client_versions)
+        .build_with_server(server)
+        .await;
+    match client {
+        Ok(client) => Some(client.protocol_version()),
+        Err(e) => {
+            assert_eq!(
+                e.error_object().map(|o| o.code),
+                Some(UnsupportedProtocolVersion::CODE)
+            );
+            let a = UnsupportedProtocolVersion::from_error(&e).expect("unexpected error");
+            let e = UnsupportedProtocolVersion {
+                version: server_versions.iter().max().unwrap().to_string(),
+                supported: client_versions.iter().map(|v| v.to_string()).collect(),
+            };
+            assert_eq!(a, e);
+            None
+        }
+    }
+}
+
+#[test]
+async fn negotiation_table() -> Result<()> {
+    let cases: &[(
+        &[ProtocolVersion],
+        &[ProtocolVersion],
+        Option<ProtocolVersion>,
+    )] = &[
+        (&[V1, V2, V3], &[V1, V2, V3], Some(V3)),
+        (&[V1, V2, V3], &[V1, V2], Some(V2)),
+        (&[V1, V2, V3], &[V1], Some(V1)),
+        (&[V1, V2], &[V1, V2, V3], Some(V2)),
+        (&[V1], &[V1, V2, V3], Some(V1)),
+        (&[V1, V3], &[V2], None),
+        (&[V2], &[V1, V3], None),
+        (&[V3], &[V1], None),
+    ];
+    for (client_versions, server_versions, e) in cases {
+        let a = negotiate(client_versions, server_versions).await;
+        assert_eq!(
+            a, *e,
+            "client: {client_versions:?}, server: {server_versions:?}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+async fn negotiation_all_combinations() -> Result<()> {
+    for client_versions in subsets() {
+        for server_versions in subsets() {
+            let requested = *client_versions.iter().max().unwrap();
+            let responded = if server_versions.contains(&requested) {
+                requested
+            } else {
+                *server_versions.iter().max().unwrap()
+            };
+            let e = client_versions.contains(&responded).then_some(responded);
+            let a = negotiate(&client_versions, &server_versions).await;
+            assert_eq!(
+                a, e,
+                "client: {client_versions:?}, server: {server_versions:?}"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+async fn unknown_requested_version() -> Result<()> {
+    for (server_versions, e) in [
+        (&[V1, V2, V3][..], V3),
+        (&[V1, V2][..], V2),
+        (&[V1][..], V1),
+    ] {
+        let server = McpServerBuilder::new()
+            .protocol_versions(server_versions)
+            .build();
+        let (handler, options, mut p) = McpClientBuilder::new().build_raw();
+        p.protocol_version = "1999-01-01".to_string();
+        let (client, _server) = Session::new_channel(handler, server.into_handler(), &options);
+        let client = McpClient::initialize(client, p).await?;
+        assert_eq!(client.protocol_version(), e);
+    }
+    Ok(())
+}
+
+#[test]
+async fn empty_client_versions() -> Result<()> {
+    let server = McpServerBuilder::new().build();
+    let a = McpClientBuilder::new()
+        .with_protocol_versions(&[])
+        .build_with_server(server)
+        .await;
+    let e = a.err().expect("expected error");
+    assert_eq!(
+        e.error_object().map(|o| o.code),
+        Some(ErrorCode::INVALID_PARAMS)
+    );
+    Ok(())
+}
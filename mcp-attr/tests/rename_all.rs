@@ -0,0 +1,136 @@
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tokio::test;
+
+use mcp_attr::Result;
+use mcp_attr::client::McpClient;
+use mcp_attr::schema::{
+    CallToolRequestParams, CallToolResult, CompleteRequestParams, CompleteRequestParamsArgument,
+    ContentBlock, GetPromptRequestParams, GetPromptResult, PromptReference,
+};
+use mcp_attr::server::{McpServer, McpServerBuilder, mcp_server, prompt, route, tool};
+
+struct MyMcpServer;
+
+#[mcp_server(rename_all = "camelCase", client = "MyMcpClient")]
+impl McpServer for MyMcpServer {
+    #[tool]
+    async fn search(&self, search_query: String, max_results: Option<u32>) -> Result<String> {
+        Ok(format!("{search_query} {max_results:?}"))
+    }
+
+    #[tool(rename_all = "kebab-case")]
+    async fn kebab(&self, first_name: String, #[arg("LAST")] last_name: String) -> Result<String> {
+        Ok(format!("{first_name} {last_name}"))
+    }
+
+    #[prompt]
+    async fn greet(&self, #[complete(.complete_name)] user_name: String) -> Result<String> {
+        Ok(format!("Hello, {user_name}!"))
+    }
+
+    #[complete_fn]
+    async fn complete_name(&self, _value: &str) -> Result<Vec<&'static str>> {
+        Ok(vec!["alice", "bob"])
+    }
+}
+
+#[tool(rename_all = "SCREAMING_SNAKE_CASE")]
+async fn route_tool(item_count: u32) -> Result<String> {
+    Ok(item_count.to_string())
+}
+
+#[prompt(rename_all = "PascalCase")]
+async fn route_prompt(user_name: String) -> Result<String> {
+    Ok(format!("Hi, {user_name}!"))
+}
+
+fn text(s: &str) -> CallToolResult {
+    ContentBlock::from(s).into()
+}
+
+#[test]
+async fn tool_arguments() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let tools = client.tools_list(None).await?;
+    let search = tools.tools.iter().find(|t| t.name == "search").unwrap();
+    assert_eq!(
+        search.input_schema.properties.keys().collect::<Vec<_>>(),
+        vec!["maxResults", "searchQuery"]
+    );
+    let kebab = tools.tools.iter().find(|t| t.name == "kebab").unwrap();
+    assert_eq!(
+        kebab.input_schema.properties.keys().collect::<Vec<_>>(),
+        vec!["LAST", "first-name"]
+    );
+
+    let mut p = CallToolRequestParams::new("search");
+    p.arguments = Some(
+        json!({ "searchQuery": "rust", "maxResults": 3 })
+            .as_object()
+            .unwrap()
+            .clone(),
+    );
+    let a = client.tools_call(p).await?;
+    assert_eq!(a, text("rust Some(3)"));
+    Ok(())
+}
+
+#[test]
+async fn prompt_arguments() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let prompts = client.prompts_list(None).await?;
+    assert_eq!(prompts.prompts[0].arguments[0].name, "userName");
+
+    let a = client
+        .prompts_get(
+            GetPromptRequestParams::new("greet").with_arguments(vec![("userName", "alice")]),
+        )
+        .await?;
+    let e: GetPromptResult = "Hello, alice!".into();
+    assert_eq!(a, e);
+    Ok(())
+}
+
+#[test]
+async fn completion_argument() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .completion_complete(CompleteRequestParams::new(
+            PromptReference::new("greet"),
+            CompleteRequestParamsArgument::new("userName", ""),
+        ))
+        .await?;
+    assert_eq!(a.completion.values, vec!["alice", "bob"]);
+    Ok(())
+}
+
+#[test]
+async fn typed_client() -> Result<()> {
+    let client = MyMcpClient::new(McpClient::with_server(MyMcpServer).await?);
+    let a = client.search("rust", None).await.unwrap();
+    assert_eq!(a, "rust None");
+    Ok(())
+}
+
+#[test]
+async fn free_functions() -> Result<()> {
+    let server = McpServerBuilder::new()
+        .route(route![route_tool, route_prompt])
+        .build();
+    let client = McpClient::with_server(server).await?;
+
+    let mut p = CallToolRequestParams::new("route_tool");
+    p.arguments = Some(json!({ "ITEM_COUNT": 2 }).as_object().unwrap().clone());
+    let a = client.tools_call(p).await?;
+    assert_eq!(a, text("2"));
+
+    let a = client
+        .prompts_get(
+            GetPromptRequestParams::new("route_prompt").with_arguments(vec![("UserName", "bob")]),
+        )
+        .await?;
+    let e: GetPromptResult = "Hi, bob!".into();
+    assert_eq!(a, e);
+    Ok(())
+}
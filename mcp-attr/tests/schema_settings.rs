@@ -0,0 +1,163 @@
+use pretty_assertions::assert_eq;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::test;
+
+use mcp_attr::Result;
+use mcp_attr::client::McpClient;
+use mcp_attr::schema::{CallToolRequestParams, CallToolResult, ContentBlock};
+use mcp_attr::server::{
+    McpServer, McpServerBuilder, OptionSchema, SchemaDraft, SchemaSettings, mcp_server, route, tool,
+};
+
+#[derive(Deserialize, JsonSchema)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct Node {
+    name: String,
+    children: Vec<Node>,
+}
+
+struct DefaultServer;
+
+#[mcp_server]
+impl McpServer for DefaultServer {
+    #[tool]
+    async fn line(&self, from: Point, to: Point) -> Result<String> {
+        Ok(format!("{} {} {} {}", from.x, from.y, to.x, to.y))
+    }
+
+    #[tool]
+    async fn tree(&self, root: Node, depth: Option<u32>) -> Result<String> {
+        Ok(format!("{} {} {depth:?}", root.name, root.children.len()))
+    }
+}
+
+struct CompatibleServer;
+
+#[mcp_server]
+impl McpServer for CompatibleServer {
+    fn schema_settings(&self) -> SchemaSettings {
+        SchemaSettings::compatible()
+    }
+
+    #[tool]
+    async fn line(&self, from: Point, to: Point) -> Result<String> {
+        Ok(format!("{} {} {} {}", from.x, from.y, to.x, to.y))
+    }
+
+    #[tool]
+    async fn tree(&self, root: Node) -> Result<String> {
+        Ok(root.name)
+    }
+}
+
+#[tool]
+async fn route_count(count: Option<u32>) -> Result<String> {
+    Ok(format!("{count:?}"))
+}
+
+async fn input_schema(client: &McpClient, name: &str) -> Result<Value> {
+    let tools = client.tools_list(None).await?;
+    let tool = tools.tools.into_iter().find(|t| t.name == name).unwrap();
+    Ok(serde_json::to_value(tool.input_schema)?)
+}
+
+#[test]
+async fn shared_defs() -> Result<()> {
+    let client = McpClient::with_server(DefaultServer).await?;
+    let a = input_schema(&client, "line").await?;
+    assert_eq!(a["properties"]["from"], json!({ "$ref": "#/$defs/Point" }));
+    assert_eq!(a["properties"]["to"], json!({ "$ref": "#/$defs/Point" }));
+    assert_eq!(
+        a["$defs"].as_object().unwrap().keys().collect::<Vec<_>>(),
+        vec!["Point"]
+    );
+    Ok(())
+}
+
+#[test]
+async fn recursive_type() -> Result<()> {
+    let client = McpClient::with_server(DefaultServer).await?;
+    let a = input_schema(&client, "tree").await?;
+    assert_eq!(
+        a["$defs"]["Node"]["properties"]["children"]["items"],
+        json!({ "$ref": "#/$defs/Node" })
+    );
+    let mut p = CallToolRequestParams::new("tree");
+    p.arguments = Some(
+        json!({ "root": { "name": "a", "children": [{ "name": "b", "children": [] }] } })
+            .as_object()
+            .unwrap()
+            .clone(),
+    );
+    let a = client.tools_call(p).await?;
+    let e: CallToolResult = ContentBlock::from("a 1 None").into();
+    assert_eq!(a, e);
+    Ok(())
+}
+
+#[test]
+async fn option_is_nullable_by_default() -> Result<()> {
+    let client = McpClient::with_server(DefaultServer).await?;
+    let a = input_schema(&client, "tree").await?;
+    assert_eq!(a["properties"]["depth"]["type"], json!(["integer", "null"]));
+    assert_eq!(a["required"], json!(["root"]));
+    Ok(())
+}
+
+#[test]
+async fn compatible() -> Result<()> {
+    let client = McpClient::with_server(CompatibleServer).await?;
+    let a = input_schema(&client, "line").await?;
+    assert_eq!(a.get("$defs"), None);
+    assert_eq!(
+        a["properties"]["from"],
+        json!({
+            "type": "object",
+            "required": ["x", "y"],
+            "properties": {
+                "x": { "type": "integer" },
+                "y": { "type": "integer" },
+            },
+        })
+    );
+
+    let a = input_schema(&client, "tree").await?;
+    assert_eq!(a.get("$defs"), None);
+    assert_eq!(
+        a["properties"]["root"]["properties"]["children"]["items"],
+        json!({})
+    );
+    Ok(())
+}
+
+#[test]
+async fn builder_settings() -> Result<()> {
+    let server = McpServerBuilder::new()
+        .route(route![route_count])
+        .schema_settings(
+            SchemaSettings::new()
+                .with_draft(SchemaDraft::Draft2020_12)
+                .with_option_schema(OptionSchema::Omitted),
+        )
+        .build();
+    let client = McpClient::with_server(server).await?;
+    let a = input_schema(&client, "route_count").await?;
+    assert_eq!(
+        a,
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "count": { "type": "integer", "format": "uint32", "minimum": 0.0 },
+            },
+        })
+    );
+    Ok(())
+}
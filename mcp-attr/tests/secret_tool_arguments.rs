@@ -0,0 +1,234 @@
+use jsoncall::{ErrorCode, SessionResult};
+use pretty_assertions::assert_eq;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::test;
+
+use mcp_attr::Result;
+use mcp_attr::client::McpClient;
+use mcp_attr::schema::{
+    CallToolRequestParams, CallToolResult, ContentBlock, ListToolsRequestParams, ListToolsResult,
+    Tool,
+};
+use mcp_attr::server::{McpServer, RequestContext, mcp_server};
+
+struct MyMcpServer;
+
+#[mcp_server]
+impl McpServer for MyMcpServer {
+    #[tool]
+    async fn login(
+        &self,
+        user: String,
+        #[arg(secret, min_length = 8)] password: String,
+        #[arg(secret)] pin: Option<u32>,
+    ) -> Result<String> {
+        Ok(format!("{user} {} {pin:?}", password.len()))
+    }
+}
+
+struct ValidatingServer;
+
+#[mcp_server]
+impl McpServer for ValidatingServer {
+    fn validate_tool_arguments(&self) -> bool {
+        true
+    }
+
+    #[tool]
+    async fn unlock(&self, #[arg(secret, max = 9999)] pin: u32) -> Result<String> {
+        Ok(pin.to_string())
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct ConnectArgs {
+    host: String,
+    // Fields that are never serialized are described as `writeOnly` by schemars.
+    #[serde(skip_serializing)]
+    token: u32,
+}
+
+struct FlattenServer;
+
+#[mcp_server]
+impl McpServer for FlattenServer {
+    #[tool]
+    async fn connect(&self, #[arg(flatten)] args: ConnectArgs) -> Result<String> {
+        Ok(format!("{} {}", args.host, args.token))
+    }
+}
+
+/// A server that returns the `Debug` output of the params of `tools/call`
+struct DebugServer;
+
+impl McpServer for DebugServer {
+    async fn tools_list(
+        &self,
+        _p: ListToolsRequestParams,
+        _cx: &mut RequestContext,
+    ) -> Result<ListToolsResult> {
+        let input_schema = serde_json::from_value(json!({
+            "type": "object",
+            "properties": {
+                "user": { "type": "string" },
+                "password": { "type": "string", "writeOnly": true },
+            },
+        }))?;
+        Ok(vec![Tool::new("login", input_schema)].into())
+    }
+
+    async fn tools_call(
+        &self,
+        p: CallToolRequestParams,
+        _cx: &mut RequestContext,
+    ) -> Result<CallToolResult> {
+        Ok(ContentBlock::from(format!("{p:?}")).into())
+    }
+}
+
+fn call(name: &str, arguments: Value) -> CallToolRequestParams {
+    let mut p = CallToolRequestParams::new(name);
+    p.arguments = Some(arguments.as_object().unwrap().clone());
+    p
+}
+
+#[track_caller]
+fn error_text<T: std::fmt::Debug>(a: SessionResult<T>) -> String {
+    let e = a.unwrap_err();
+    let e = e.error_object().expect("no error object");
+    assert_eq!(e.code, ErrorCode::INVALID_PARAMS, "{e:#?}");
+    format!("{} {:?}", e.message, e.data)
+}
+
+#[test]
+async fn write_only() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let tools = client.tools_list(None).await?;
+    let a = serde_json::to_value(&tools.tools[0].input_schema)?;
+    assert_eq!(a["properties"]["password"]["writeOnly"], json!(true));
+    assert_eq!(a["properties"]["pin"]["writeOnly"], json!(true));
+    assert_eq!(a["properties"]["user"].get("writeOnly"), None);
+    Ok(())
+}
+
+#[test]
+async fn call_secret() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(call(
+            "login",
+            json!({ "user": "alice", "password": "correct horse", "pin": 1234 }),
+        ))
+        .await?;
+    let e: CallToolResult = ContentBlock::from("alice 13 Some(1234)").into();
+    assert_eq!(a, e);
+    Ok(())
+}
+
+#[test]
+async fn invalid_value_is_not_echoed() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(call(
+            "login",
+            json!({ "user": "alice", "password": "correct horse", "pin": "s3cr3t-pin" }),
+        ))
+        .await;
+    let e = error_text(a);
+    assert!(e.contains("argument `pin`"), "{e}");
+    assert!(!e.contains("s3cr3t-pin"), "{e}");
+    Ok(())
+}
+
+#[test]
+async fn constraint_violation_is_not_echoed() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(call(
+            "login",
+            json!({ "user": "alice", "password": "hunter2" }),
+        ))
+        .await;
+    let e = error_text(a);
+    assert!(e.contains("/arguments/password"), "{e}");
+    assert!(!e.contains("hunter2"), "{e}");
+    Ok(())
+}
+
+#[test]
+async fn validation_is_not_echoed() -> Result<()> {
+    let client = McpClient::with_server(ValidatingServer).await?;
+    let a = client
+        .tools_call(call("unlock", json!({ "pin": 123456789 })))
+        .await;
+    let e = error_text(a);
+    assert!(e.contains("/arguments/pin"), "{e}");
+    assert!(!e.contains("123456789"), "{e}");
+    Ok(())
+}
+
+#[test]
+async fn debug_is_redacted() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    client.tools_list(None).await?;
+    let p = call(
+        "login",
+        json!({ "user": "alice", "password": "correct horse" }),
+    );
+    let a = format!("{p:?}");
+    assert!(a.contains("correct horse"), "{a}");
+
+    let p = p.with_input_schema(client.tool_input_schema("login").unwrap());
+    let a = format!("{p:?}");
+    assert!(a.contains("alice"), "{a}");
+    assert!(a.contains("<redacted>"), "{a}");
+    assert!(!a.contains("correct horse"), "{a}");
+    Ok(())
+}
+
+#[test]
+async fn debug_is_redacted_in_tools_call() -> Result<()> {
+    let client = McpClient::with_server(DebugServer).await?;
+    client.tools_list(None).await?;
+    let a = client
+        .tools_call(call(
+            "login",
+            json!({ "user": "alice", "password": "correct horse" }),
+        ))
+        .await?;
+    let a = format!("{a:?}");
+    assert!(a.contains("alice"), "{a}");
+    assert!(a.contains("<redacted>"), "{a}");
+    assert!(!a.contains("correct horse"), "{a}");
+    Ok(())
+}
+
+#[test]
+async fn flatten_write_only_is_not_echoed() -> Result<()> {
+    let client = McpClient::with_server(FlattenServer).await?;
+    let tools = client.tools_list(None).await?;
+    let a = serde_json::to_value(&tools.tools[0].input_schema)?;
+    assert_eq!(a["properties"]["token"]["writeOnly"], json!(true));
+
+    let a = client
+        .tools_call(call(
+            "connect",
+            json!({ "host": "example.com", "token": "s3cr3t-token" }),
+        ))
+        .await;
+    let e = error_text(a);
+    assert!(e.contains("/arguments/token"), "{e}");
+    assert!(!e.contains("s3cr3t-token"), "{e}");
+
+    let p = call(
+        "connect",
+        json!({ "host": "example.com", "token": "s3cr3t-token" }),
+    )
+    .with_input_schema(client.tool_input_schema("connect").unwrap());
+    let a = format!("{p:?}");
+    assert!(a.contains("example.com"), "{a}");
+    assert!(!a.contains("s3cr3t-token"), "{a}");
+    Ok(())
+}
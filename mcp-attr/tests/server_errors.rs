@@ -0,0 +1,113 @@
+use jsoncall::{ErrorCode, ErrorObject, SessionResult};
+use pretty_assertions::assert_eq;
+use serde_json::{Value, json};
+use tokio::test;
+
+use mcp_attr::Result;
+use mcp_attr::client::McpClient;
+use mcp_attr::schema::{CallToolRequestParams, GetPromptRequestParams, ReadResourceRequestParams};
+use mcp_attr::server::errors::{RESOURCE_NOT_FOUND, argument_pointer};
+use mcp_attr::server::{McpServer, mcp_server};
+
+struct MyMcpServer;
+
+#[mcp_server]
+impl McpServer for MyMcpServer {
+    #[prompt]
+    async fn prompt(&self, value: u32) -> Result<String> {
+        Ok(value.to_string())
+    }
+
+    #[resource("my_app://items/{id}")]
+    async fn item(&self, id: String) -> Result<String> {
+        Ok(id)
+    }
+
+    #[tool]
+    async fn tool(&self, value: u32) -> Result<String> {
+        Ok(value.to_string())
+    }
+}
+
+#[track_caller]
+fn assert_error<T: std::fmt::Debug>(a: SessionResult<T>, code: ErrorCode, data: Value) {
+    let e = a.unwrap_err();
+    let e: &ErrorObject = e.error_object().expect("no error object");
+    assert_eq!(e.code, code, "{e:#?}");
+    assert_eq!(e.data, Some(data), "{e:#?}");
+}
+
+#[test]
+async fn unknown_tool() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(CallToolRequestParams::new("unknown"))
+        .await;
+    assert_error(a, ErrorCode::INVALID_PARAMS, json!({ "name": "unknown" }));
+    Ok(())
+}
+
+#[test]
+async fn unknown_prompt() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .prompts_get(GetPromptRequestParams::new("unknown"))
+        .await;
+    assert_error(a, ErrorCode::INVALID_PARAMS, json!({ "name": "unknown" }));
+    Ok(())
+}
+
+#[test]
+async fn resource_not_found() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .resources_read(ReadResourceRequestParams::new("my_app://unknown"))
+        .await;
+    assert_error(a, RESOURCE_NOT_FOUND, json!({ "uri": "my_app://unknown" }));
+    Ok(())
+}
+
+#[test]
+async fn missing_tool_argument() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client.tools_call(CallToolRequestParams::new("tool")).await;
+    assert_error(
+        a,
+        ErrorCode::INVALID_PARAMS,
+        json!({ "pointer": "/arguments/value" }),
+    );
+    Ok(())
+}
+
+#[test]
+async fn invalid_tool_argument() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let mut p = CallToolRequestParams::new("tool");
+    p.arguments = Some(json!({ "value": "abc" }).as_object().unwrap().clone());
+    let a = client.tools_call(p).await;
+    assert_error(
+        a,
+        ErrorCode::INVALID_PARAMS,
+        json!({ "pointer": "/arguments/value" }),
+    );
+    Ok(())
+}
+
+#[test]
+async fn invalid_prompt_argument() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .prompts_get(GetPromptRequestParams::new("prompt").with_arguments([("value", "abc")]))
+        .await;
+    assert_error(
+        a,
+        ErrorCode::INVALID_PARAMS,
+        json!({ "pointer": "/arguments/value" }),
+    );
+    Ok(())
+}
+
+#[test]
+async fn argument_pointer_escape() {
+    assert_eq!(argument_pointer("a/b~c"), "/arguments/a~1b~0c");
+}
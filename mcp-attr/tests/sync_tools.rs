@@ -0,0 +1,169 @@
+use pretty_assertions::assert_eq;
+use serde_json::{Value, json};
+use tokio::test;
+
+use mcp_attr::Result;
+use mcp_attr::client::McpClient;
+use mcp_attr::schema::{
+    CallToolRequestParams, CallToolResult, ContentBlock, GetPromptRequestParams, GetPromptResult,
+};
+use mcp_attr::server::{McpServer, McpServerBuilder, mcp_server, route, tool};
+
+struct SyncServer;
+
+#[mcp_server]
+impl McpServer for SyncServer {
+    #[tool]
+    fn add(&self, a: u32, b: u32) -> Result<String> {
+        Ok((a + b).to_string())
+    }
+
+    #[tool(blocking)]
+    fn sum(&self, values: Vec<u32>) -> Result<String> {
+        Ok(values.iter().sum::<u32>().to_string())
+    }
+}
+
+#[tool(blocking)]
+fn route_sum(values: Vec<u32>) -> Result<String> {
+    Ok(values.iter().sum::<u32>().to_string())
+}
+
+#[tool]
+fn route_add(a: u32, b: u32) -> Result<String> {
+    Ok((a + b).to_string())
+}
+
+struct CounterServer {
+    count: u32,
+}
+
+#[mcp_server(client = "CounterClient")]
+impl McpServer for CounterServer {
+    #[tool]
+    async fn increment(&mut self, by: Option<u32>) -> Result<String> {
+        self.count += by.unwrap_or(1);
+        Ok(self.count.to_string())
+    }
+
+    #[tool(blocking)]
+    fn reset(&mut self) -> Result<String> {
+        self.count = 0;
+        Ok(self.count.to_string())
+    }
+
+    #[tool(read_only)]
+    fn get(&self) -> Result<String> {
+        Ok(self.count.to_string())
+    }
+
+    #[prompt]
+    async fn status(&self) -> Result<String> {
+        Ok(format!("count is {}", self.count))
+    }
+}
+
+fn call(name: &str, arguments: Value) -> CallToolRequestParams {
+    let mut p = CallToolRequestParams::new(name);
+    p.arguments = Some(arguments.as_object().unwrap().clone());
+    p
+}
+
+fn text(s: &str) -> CallToolResult {
+    ContentBlock::from(s).into()
+}
+
+#[test]
+async fn sync_tool() -> Result<()> {
+    let client = McpClient::with_server(SyncServer).await?;
+    let a = client
+        .tools_call(call("add", json!({ "a": 1, "b": 2 })))
+        .await?;
+    assert_eq!(a, text("3"));
+    Ok(())
+}
+
+#[test]
+async fn blocking_tool() -> Result<()> {
+    let client = McpClient::with_server(SyncServer).await?;
+    let a = client
+        .tools_call(call("sum", json!({ "values": [1, 2, 3] })))
+        .await?;
+    assert_eq!(a, text("6"));
+    Ok(())
+}
+
+#[test(flavor = "multi_thread")]
+async fn blocking_tool_multi_thread() -> Result<()> {
+    let client = McpClient::with_server(SyncServer).await?;
+    let a = client
+        .tools_call(call("sum", json!({ "values": [4, 5] })))
+        .await?;
+    assert_eq!(a, text("9"));
+    Ok(())
+}
+
+#[test]
+async fn route_tools() -> Result<()> {
+    let server = McpServerBuilder::new()
+        .route(route![route_sum, route_add])
+        .build();
+    let client = McpClient::with_server(server).await?;
+    let a = client
+        .tools_call(call("route_sum", json!({ "values": [1, 2] })))
+        .await?;
+    assert_eq!(a, text("3"));
+    let a = client
+        .tools_call(call("route_add", json!({ "a": 2, "b": 3 })))
+        .await?;
+    assert_eq!(a, text("5"));
+    Ok(())
+}
+
+#[test]
+async fn mut_self_tool() -> Result<()> {
+    let client = McpClient::with_server(CounterServer { count: 0 }).await?;
+    let a = client.tools_call(call("increment", json!({}))).await?;
+    assert_eq!(a, text("1"));
+    let a = client
+        .tools_call(call("increment", json!({ "by": 2 })))
+        .await?;
+    assert_eq!(a, text("3"));
+    let a = client.tools_call(call("get", json!({}))).await?;
+    assert_eq!(a, text("3"));
+    let a = client.tools_call(call("reset", json!({}))).await?;
+    assert_eq!(a, text("0"));
+    let a = client.tools_call(call("get", json!({}))).await?;
+    assert_eq!(a, text("0"));
+    Ok(())
+}
+
+#[test]
+async fn mut_self_tool_list() -> Result<()> {
+    let client = McpClient::with_server(CounterServer { count: 0 }).await?;
+    let tools = client.tools_list(None).await?;
+    let mut names: Vec<_> = tools.tools.iter().map(|t| t.name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, vec!["get", "increment", "reset"]);
+    Ok(())
+}
+
+#[test]
+async fn mut_self_prompt() -> Result<()> {
+    let client = McpClient::with_server(CounterServer { count: 5 }).await?;
+    client.tools_call(call("increment", json!({}))).await?;
+    let a = client
+        .prompts_get(GetPromptRequestParams::new("status"))
+        .await?;
+    let e: GetPromptResult = "count is 6".into();
+    assert_eq!(a, e);
+    Ok(())
+}
+
+#[test]
+async fn mut_self_typed_client() -> Result<()> {
+    let client = CounterClient::new(McpClient::with_server(CounterServer { count: 0 }).await?);
+    assert_eq!(client.increment(Some(4)).await.unwrap(), "4");
+    assert_eq!(client.get().await.unwrap(), "4");
+    Ok(())
+}
@@ -0,0 +1,165 @@
+use jsoncall::{ErrorCode, SessionResult};
+use pretty_assertions::assert_eq;
+use serde_json::{Value, json};
+use tokio::test;
+
+use mcp_attr::Result;
+use mcp_attr::client::McpClient;
+use mcp_attr::schema::{CallToolRequestParams, CallToolResult, ContentBlock};
+use mcp_attr::server::{McpServer, McpServerBuilder, mcp_server, route, tool};
+
+struct MyMcpServer;
+
+#[mcp_server(client = "MyMcpClient")]
+impl McpServer for MyMcpServer {
+    #[tool]
+    async fn search(
+        &self,
+        #[arg(min_length = 1, max_length = 20, pattern = "^[a-z ]+$", example = "rust".to_string())]
+        query: String,
+        #[arg(default = 10, min = 1, max = 100)] limit: u32,
+        #[arg(max_length = 2)] tags: Option<Vec<String>>,
+    ) -> Result<String> {
+        Ok(format!("{query} {limit} {tags:?}"))
+    }
+
+    #[tool(lenient)]
+    async fn ratio(&self, #[arg(min = 0.0, max = 1.0)] value: f64) -> Result<String> {
+        Ok(value.to_string())
+    }
+}
+
+#[tool]
+async fn route_limit(#[arg(default = 5, max = 10)] limit: u32) -> Result<String> {
+    Ok(limit.to_string())
+}
+
+fn call(name: &str, arguments: Value) -> CallToolRequestParams {
+    let mut p = CallToolRequestParams::new(name);
+    p.arguments = Some(arguments.as_object().unwrap().clone());
+    p
+}
+
+fn text(s: &str) -> CallToolResult {
+    ContentBlock::from(s).into()
+}
+
+#[track_caller]
+fn assert_violation<T: std::fmt::Debug>(a: SessionResult<T>, pointer: &str) {
+    let e = a.unwrap_err();
+    let e = e.error_object().expect("no error object");
+    assert_eq!(e.code, ErrorCode::INVALID_PARAMS, "{e:#?}");
+    assert_eq!(
+        e.data.as_ref().unwrap()["violations"][0]["pointer"],
+        json!(pointer),
+        "{e:#?}"
+    );
+}
+
+#[test]
+async fn input_schema() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let tools = client.tools_list(None).await?;
+    let tool = tools
+        .tools
+        .into_iter()
+        .find(|t| t.name == "search")
+        .unwrap();
+    let a = serde_json::to_value(tool.input_schema)?;
+    assert_eq!(
+        a["properties"]["query"],
+        json!({
+            "type": "string",
+            "minLength": 1,
+            "maxLength": 20,
+            "pattern": "^[a-z ]+$",
+            "examples": ["rust"],
+        })
+    );
+    assert_eq!(a["properties"]["limit"]["default"], json!(10));
+    assert_eq!(a["properties"]["limit"]["minimum"], json!(1));
+    assert_eq!(a["properties"]["limit"]["maximum"], json!(100));
+    assert_eq!(a["properties"]["tags"]["maxItems"], json!(2));
+    assert_eq!(a["required"], json!(["query"]));
+    Ok(())
+}
+
+#[test]
+async fn default_is_applied() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(call("search", json!({ "query": "rust" })))
+        .await?;
+    assert_eq!(a, text("rust 10 None"));
+
+    let a = client
+        .tools_call(call("search", json!({ "query": "rust", "limit": 3 })))
+        .await?;
+    assert_eq!(a, text("rust 3 None"));
+    Ok(())
+}
+
+#[test]
+async fn constraints_are_checked() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(call("search", json!({ "query": "rust", "limit": 0 })))
+        .await;
+    assert_violation(a, "/arguments/limit");
+
+    let a = client
+        .tools_call(call("search", json!({ "query": "Rust" })))
+        .await;
+    assert_violation(a, "/arguments/query");
+
+    let a = client
+        .tools_call(call("search", json!({ "query": "" })))
+        .await;
+    assert_violation(a, "/arguments/query");
+
+    let a = client
+        .tools_call(call(
+            "search",
+            json!({ "query": "rust", "tags": ["a", "b", "c"] }),
+        ))
+        .await;
+    assert_violation(a, "/arguments/tags");
+    Ok(())
+}
+
+#[test]
+async fn constraints_with_lenient() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(call("ratio", json!({ "value": "0.5" })))
+        .await?;
+    assert_eq!(a, text("0.5"));
+
+    let a = client
+        .tools_call(call("ratio", json!({ "value": "1.5" })))
+        .await;
+    assert_violation(a, "/arguments/value");
+    Ok(())
+}
+
+#[test]
+async fn route_default() -> Result<()> {
+    let server = McpServerBuilder::new().route(route![route_limit]).build();
+    let client = McpClient::with_server(server).await?;
+    let a = client.tools_call(call("route_limit", json!({}))).await?;
+    assert_eq!(a, text("5"));
+
+    let a = client
+        .tools_call(call("route_limit", json!({ "limit": 11 })))
+        .await;
+    assert_violation(a, "/arguments/limit");
+    Ok(())
+}
+
+#[test]
+async fn typed_client_default() -> Result<()> {
+    let client = MyMcpClient::new(McpClient::with_server(MyMcpServer).await?);
+    let a = client.search("rust", None, None).await.unwrap();
+    assert_eq!(a, "rust 10 None");
+    Ok(())
+}
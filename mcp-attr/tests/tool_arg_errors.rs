@@ -0,0 +1,200 @@
+use jsoncall::{ErrorCode, SessionResult};
+use pretty_assertions::assert_eq;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::test;
+
+use mcp_attr::Result;
+use mcp_attr::client::McpClient;
+use mcp_attr::schema::{CallToolRequestParams, CallToolResult, ContentBlock};
+use mcp_attr::server::{McpServer, McpServerBuilder, SchemaSettings, mcp_server, route, tool};
+
+#[derive(Deserialize, JsonSchema)]
+struct Item {
+    count: u32,
+}
+
+struct MyMcpServer;
+
+#[mcp_server]
+impl McpServer for MyMcpServer {
+    #[tool]
+    async fn count(&self, value: u32) -> Result<String> {
+        Ok(value.to_string())
+    }
+
+    #[tool]
+    async fn items(&self, items: Vec<Item>) -> Result<String> {
+        Ok(items.iter().map(|i| i.count).sum::<u32>().to_string())
+    }
+
+    #[tool(strict)]
+    async fn strict(&self, value: u32) -> Result<String> {
+        Ok(value.to_string())
+    }
+}
+
+struct CompatibleServer;
+
+#[mcp_server]
+impl McpServer for CompatibleServer {
+    fn schema_settings(&self) -> SchemaSettings {
+        SchemaSettings::compatible()
+    }
+
+    #[tool]
+    async fn count(&self, value: u32) -> Result<String> {
+        Ok(value.to_string())
+    }
+}
+
+#[tool]
+async fn route_count(value: u32) -> Result<String> {
+    Ok(value.to_string())
+}
+
+#[tool(strict)]
+async fn route_strict(value: u32) -> Result<String> {
+    Ok(value.to_string())
+}
+
+fn call(name: &str, arguments: Value) -> CallToolRequestParams {
+    let mut p = CallToolRequestParams::new(name);
+    p.arguments = Some(arguments.as_object().unwrap().clone());
+    p
+}
+
+struct InvalidParams {
+    message: String,
+    data: Option<Value>,
+}
+
+#[track_caller]
+fn invalid_params<T: std::fmt::Debug>(a: SessionResult<T>) -> InvalidParams {
+    let e = a.unwrap_err();
+    let e = e.error_object().expect("no error object");
+    assert_eq!(e.code, ErrorCode::INVALID_PARAMS, "{e:#?}");
+    InvalidParams {
+        message: e.message.clone(),
+        data: e.data.clone(),
+    }
+}
+
+#[test]
+async fn invalid_type() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let e = invalid_params(
+        client
+            .tools_call(call("count", json!({ "value": "x" })))
+            .await,
+    );
+    assert!(e.message.contains("argument `value`"), "{}", e.message);
+    assert!(e.message.contains("integer (uint32)"), "{}", e.message);
+    assert_eq!(e.data, Some(json!({ "pointer": "/arguments/value" })));
+    Ok(())
+}
+
+#[test]
+async fn missing() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let e = invalid_params(client.tools_call(CallToolRequestParams::new("count")).await);
+    assert!(
+        e.message.contains("argument `value` is required"),
+        "{}",
+        e.message
+    );
+    assert!(e.message.contains("integer (uint32)"), "{}", e.message);
+    Ok(())
+}
+
+#[test]
+async fn nested_path() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let e = invalid_params(
+        client
+            .tools_call(call(
+                "items",
+                json!({ "items": [{ "count": 1 }, { "count": -1 }] }),
+            ))
+            .await,
+    );
+    assert!(e.message.contains("`[1].count`"), "{}", e.message);
+    assert_eq!(
+        e.data,
+        Some(json!({ "pointer": "/arguments/items/1/count" }))
+    );
+    Ok(())
+}
+
+#[test]
+async fn unknown_args_are_ignored_by_default() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(call("count", json!({ "value": 1, "other": 2 })))
+        .await?;
+    let e: CallToolResult = ContentBlock::from("1").into();
+    assert_eq!(a, e);
+    Ok(())
+}
+
+#[test]
+async fn strict_rejects_unknown_args() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let e = invalid_params(
+        client
+            .tools_call(call("strict", json!({ "value": 1, "other": 2 })))
+            .await,
+    );
+    assert_eq!(
+        e.data.unwrap()["violations"][0]["pointer"],
+        json!("/arguments/other")
+    );
+    Ok(())
+}
+
+#[test]
+async fn strict_route() -> Result<()> {
+    let server = McpServerBuilder::new().route(route![route_strict]).build();
+    let client = McpClient::with_server(server).await?;
+    let a = client
+        .tools_call(call("route_strict", json!({ "value": 1 })))
+        .await?;
+    let e: CallToolResult = ContentBlock::from("1").into();
+    assert_eq!(a, e);
+
+    invalid_params(
+        client
+            .tools_call(call("route_strict", json!({ "value": 1, "other": 2 })))
+            .await,
+    );
+    Ok(())
+}
+
+#[test]
+async fn expected_type_follows_schema_settings() -> Result<()> {
+    let client = McpClient::with_server(CompatibleServer).await?;
+    let e = invalid_params(
+        client
+            .tools_call(call("count", json!({ "value": "x" })))
+            .await,
+    );
+    assert!(e.message.contains("(expected integer)"), "{}", e.message);
+    Ok(())
+}
+
+#[test]
+async fn expected_type_follows_schema_settings_route() -> Result<()> {
+    let server = McpServerBuilder::new()
+        .route(route![route_count])
+        .schema_settings(SchemaSettings::compatible())
+        .build();
+    let client = McpClient::with_server(server).await?;
+    let e = invalid_params(
+        client
+            .tools_call(call("route_count", json!({ "value": "x" })))
+            .await,
+    );
+    assert!(e.message.contains("(expected integer)"), "{}", e.message);
+    Ok(())
+}
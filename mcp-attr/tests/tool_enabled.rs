@@ -0,0 +1,131 @@
+use jsoncall::SessionResult;
+use pretty_assertions::assert_eq;
+use tokio::test;
+
+use mcp_attr::Result;
+use mcp_attr::client::{McpClient, McpClientBuilder};
+use mcp_attr::schema::{CallToolRequestParams, CallToolResult, ContentBlock};
+use mcp_attr::server::{McpServer, McpServerBuilder, RequestContext, mcp_server, route, tool};
+
+fn supports_roots(cx: &RequestContext) -> bool {
+    cx.client_capabilities().roots.is_some()
+}
+
+struct MyMcpServer;
+
+#[mcp_server]
+impl McpServer for MyMcpServer {
+    #[tool]
+    async fn echo(&self, message: String) -> Result<String> {
+        Ok(message)
+    }
+
+    #[tool(enabled = supports_roots)]
+    async fn list_roots(&self, cx: &RequestContext) -> Result<String> {
+        Ok(cx.roots_list().await?.len().to_string())
+    }
+
+    #[tool]
+    async fn ping(&self) -> Result<String> {
+        Ok("pong".into())
+    }
+}
+
+#[tool(enabled = supports_roots)]
+async fn route_tool() -> Result<String> {
+    Ok("route".into())
+}
+
+async fn client_without_roots(server: impl McpServer) -> Result<McpClient> {
+    Ok(McpClientBuilder::new().build_with_server(server).await?)
+}
+
+async fn client_with_roots(server: impl McpServer) -> Result<McpClient> {
+    Ok(McpClientBuilder::new()
+        .with_roots(Vec::new())
+        .build_with_server(server)
+        .await?)
+}
+
+async fn tool_names(client: &McpClient) -> Result<Vec<String>> {
+    let tools = client.tools_list(None).await?;
+    Ok(tools.tools.into_iter().map(|t| t.name).collect())
+}
+
+#[track_caller]
+fn error_message<T: std::fmt::Debug>(a: SessionResult<T>) -> String {
+    let e = a.unwrap_err();
+    let e = e.error_object().expect("no error object");
+    format!("{:?} {}", e.code, e.message)
+}
+
+fn text(s: &str) -> CallToolResult {
+    ContentBlock::from(s).into()
+}
+
+#[test]
+async fn list_enabled() -> Result<()> {
+    let client = client_with_roots(MyMcpServer).await?;
+    assert_eq!(
+        tool_names(&client).await?,
+        vec!["echo", "list_roots", "ping"]
+    );
+    Ok(())
+}
+
+#[test]
+async fn list_disabled() -> Result<()> {
+    let client = client_without_roots(MyMcpServer).await?;
+    assert_eq!(tool_names(&client).await?, vec!["echo", "ping"]);
+    Ok(())
+}
+
+#[test]
+async fn call_enabled() -> Result<()> {
+    let client = client_with_roots(MyMcpServer).await?;
+    let a = client
+        .tools_call(CallToolRequestParams::new("list_roots"))
+        .await?;
+    assert_eq!(a, text("0"));
+    Ok(())
+}
+
+#[test]
+async fn call_disabled() -> Result<()> {
+    let client = client_without_roots(MyMcpServer).await?;
+    let a = client
+        .tools_call(CallToolRequestParams::new("list_roots"))
+        .await;
+    let e = client
+        .tools_call(CallToolRequestParams::new("unknown"))
+        .await;
+    assert_eq!(
+        error_message(a),
+        error_message(e).replace("unknown", "list_roots")
+    );
+    Ok(())
+}
+
+#[test]
+async fn route_enabled() -> Result<()> {
+    let server = McpServerBuilder::new().route(route![route_tool]).build();
+    let client = client_with_roots(server).await?;
+    assert_eq!(tool_names(&client).await?, vec!["route_tool"]);
+    let a = client
+        .tools_call(CallToolRequestParams::new("route_tool"))
+        .await?;
+    assert_eq!(a, text("route"));
+    Ok(())
+}
+
+#[test]
+async fn route_disabled() -> Result<()> {
+    let server = McpServerBuilder::new().route(route![route_tool]).build();
+    let client = client_without_roots(server).await?;
+    assert_eq!(tool_names(&client).await?, Vec::<String>::new());
+    let a = client
+        .tools_call(CallToolRequestParams::new("route_tool"))
+        .await;
+    assert!(a.is_err());
+    Ok(())
+}
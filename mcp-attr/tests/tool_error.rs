@@ -0,0 +1,173 @@
+use jsoncall::{ErrorCode, SessionResult};
+use pretty_assertions::assert_eq;
+use tokio::test;
+
+use mcp_attr::client::{McpClient, McpClientBuilder};
+use mcp_attr::schema::{CallToolRequestParams, CallToolResult, ContentBlock};
+use mcp_attr::server::{McpServer, McpServerBuilder, ToolError, mcp_server, route, tool};
+use mcp_attr::{Result, bail_public};
+
+struct MyMcpServer;
+
+#[mcp_server]
+impl McpServer for MyMcpServer {
+    #[tool]
+    async fn fail(&self) -> Result<String> {
+        bail_public!(ErrorCode::INTERNAL_ERROR, "something went wrong")
+    }
+
+    #[tool]
+    async fn fail_execution(&self) -> std::result::Result<String, ToolError> {
+        Err(ToolError::execution(
+            mcp_attr::Error::new(ErrorCode::INTERNAL_ERROR).with_message("execution failed", true),
+        ))
+    }
+
+    #[tool]
+    async fn fail_protocol(&self) -> std::result::Result<String, ToolError> {
+        Err(ToolError::protocol(
+            mcp_attr::Error::new(ErrorCode::INVALID_PARAMS).with_message("bad request", true),
+        ))
+    }
+
+    #[tool]
+    async fn fail_internal(&self) -> Result<String> {
+        Err(mcp_attr::Error::new(ErrorCode::INTERNAL_ERROR).with_message("internal detail", false))
+    }
+
+    #[tool]
+    async fn arg(&self, value: u32) -> std::result::Result<String, ToolError> {
+        Ok(value.to_string())
+    }
+}
+
+#[tool]
+async fn route_fail() -> Result<String> {
+    bail_public!(ErrorCode::INTERNAL_ERROR, "something went wrong")
+}
+
+#[tool]
+async fn route_fail_protocol() -> std::result::Result<String, ToolError> {
+    Err(ToolError::protocol(
+        mcp_attr::Error::new(ErrorCode::INVALID_PARAMS).with_message("bad request", true),
+    ))
+}
+
+#[tool]
+async fn route_fail_internal() -> Result<String> {
+    Err(mcp_attr::Error::new(ErrorCode::INTERNAL_ERROR).with_message("internal detail", false))
+}
+
+#[tool]
+async fn route_arg(value: u32) -> Result<String> {
+    Ok(value.to_string())
+}
+
+fn tool_error(text: &str) -> CallToolResult {
+    let mut r = CallToolResult::from(vec![ContentBlock::from(text)]);
+    r.is_error = Some(true);
+    r
+}
+
+fn assert_error<T: std::fmt::Debug>(a: SessionResult<T>, code: ErrorCode) {
+    match a {
+        Ok(_) => panic!("expected error.\n{a:#?}"),
+        Err(e) => {
+            if let Some(e) = e.error_object() {
+                assert_eq!(e.code, code, "{e:#?}");
+            } else {
+                panic!("no error object\n{e:#?}");
+            }
+        }
+    }
+}
+
+#[test]
+async fn error_is_tool_result() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(CallToolRequestParams::new("fail"))
+        .await?;
+    assert_eq!(a, tool_error("something went wrong"));
+    Ok(())
+}
+
+#[test]
+async fn tool_error_execution() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(CallToolRequestParams::new("fail_execution"))
+        .await?;
+    assert_eq!(a, tool_error("execution failed"));
+    Ok(())
+}
+
+#[test]
+async fn tool_error_protocol() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(CallToolRequestParams::new("fail_protocol"))
+        .await;
+    assert_error(a, ErrorCode::INVALID_PARAMS);
+    Ok(())
+}
+
+#[test]
+async fn invalid_arg_is_protocol_error() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client.tools_call(CallToolRequestParams::new("arg")).await;
+    assert_error(a, ErrorCode::INVALID_PARAMS);
+    Ok(())
+}
+
+#[test]
+async fn route_error_is_tool_result() -> Result<()> {
+    let server = McpServerBuilder::new()
+        .route(route![route_fail, route_fail_protocol, route_arg])
+        .build();
+    let client = McpClient::with_server(server).await?;
+    let a = client
+        .tools_call(CallToolRequestParams::new("route_fail"))
+        .await?;
+    assert_eq!(a, tool_error("something went wrong"));
+
+    let a = client
+        .tools_call(CallToolRequestParams::new("route_fail_protocol"))
+        .await;
+    assert_error(a, ErrorCode::INVALID_PARAMS);
+
+    let a = client
+        .tools_call(CallToolRequestParams::new("route_arg"))
+        .await;
+    assert_error(a, ErrorCode::INVALID_PARAMS);
+    Ok(())
+}
+
+#[test]
+async fn execution_error_follows_expose_internals() -> Result<()> {
+    for expose_internals in [false, true] {
+        let server = McpServerBuilder::new()
+            .route(route![route_fail_internal])
+            .build();
+        let clients = [
+            McpClientBuilder::new()
+                .with_expose_internals(expose_internals)
+                .build_with_server(MyMcpServer)
+                .await?,
+            McpClientBuilder::new()
+                .with_expose_internals(expose_internals)
+                .build_with_server(server)
+                .await?,
+        ];
+        for (client, name) in clients.iter().zip(["fail_internal", "route_fail_internal"]) {
+            let a = client.tools_call(CallToolRequestParams::new(name)).await?;
+            let e = if expose_internals {
+                tool_error("internal detail")
+            } else {
+                tool_error(ErrorCode::INTERNAL_ERROR.message())
+            };
+            assert_eq!(a, e, "{name}, expose_internals: {expose_internals}");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use jsoncall::{ErrorCode, SessionResult};
+use pretty_assertions::assert_eq;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use tokio::test;
+
+use mcp_attr::Result;
+use mcp_attr::client::McpClient;
+use mcp_attr::schema::{
+    CallToolRequestParams, CallToolResult, ContentBlock, ListToolsRequestParams, ListToolsResult,
+    Tool, ToolInputSchema,
+};
+use mcp_attr::server::{McpServer, McpServerBuilder, RequestContext, mcp_server, route, tool};
+
+#[derive(Deserialize, JsonSchema)]
+struct Item {
+    #[schemars(regex(pattern = "^[a-z]+$"))]
+    name: String,
+    #[schemars(range(min = 1, max = 10))]
+    count: u32,
+}
+
+struct MyMcpServer;
+
+#[mcp_server]
+impl McpServer for MyMcpServer {
+    fn validate_tool_arguments(&self) -> bool {
+        true
+    }
+
+    #[tool]
+    async fn add(&self, item: Item) -> Result<String> {
+        Ok(format!("{} x {}", item.name, item.count))
+    }
+}
+
+struct NoValidationServer;
+
+#[mcp_server]
+impl McpServer for NoValidationServer {
+    #[tool]
+    async fn add(&self, item: Item) -> Result<String> {
+        Ok(format!("{} x {}", item.name, item.count))
+    }
+}
+
+struct CountingServer(Arc<AtomicUsize>);
+
+impl McpServer for CountingServer {
+    fn validate_tool_arguments(&self) -> bool {
+        true
+    }
+
+    async fn tools_list(
+        &self,
+        _p: ListToolsRequestParams,
+        _cx: &mut RequestContext,
+    ) -> Result<ListToolsResult> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        Ok(vec![Tool::new("echo", ToolInputSchema::new())].into())
+    }
+
+    async fn tools_call(
+        &self,
+        _p: CallToolRequestParams,
+        _cx: &mut RequestContext,
+    ) -> Result<CallToolResult> {
+        Ok(ContentBlock::from("ok").into())
+    }
+}
+
+#[tool]
+async fn route_add(item: Item) -> Result<String> {
+    Ok(format!("{} x {}", item.name, item.count))
+}
+
+fn call_add(name: &str, item: Value) -> CallToolRequestParams {
+    let mut p = CallToolRequestParams::new(name);
+    let mut arguments = Map::new();
+    arguments.insert("item".to_string(), item);
+    p.arguments = Some(arguments);
+    p
+}
+
+#[track_caller]
+fn assert_violations<T: std::fmt::Debug>(a: SessionResult<T>, pointers: &[&str]) {
+    let e = a.unwrap_err();
+    let e = e.error_object().expect("no error object");
+    assert_eq!(e.code, ErrorCode::INVALID_PARAMS, "{e:#?}");
+    let mut a: Vec<&str> = e.data.as_ref().unwrap()["violations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v["pointer"].as_str().unwrap())
+        .collect();
+    a.sort();
+    assert_eq!(a, pointers);
+}
+
+#[test]
+async fn valid_arguments() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(call_add("add", json!({ "name": "apple", "count": 3 })))
+        .await?;
+    let e: CallToolResult = ContentBlock::from("apple x 3").into();
+    assert_eq!(a, e);
+    Ok(())
+}
+
+#[test]
+async fn all_violations_are_reported() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client
+        .tools_call(call_add("add", json!({ "name": "Apple", "count": 20 })))
+        .await;
+    assert_violations(a, &["/arguments/item/count", "/arguments/item/name"]);
+    Ok(())
+}
+
+#[test]
+async fn missing_argument() -> Result<()> {
+    let client = McpClient::with_server(MyMcpServer).await?;
+    let a = client.tools_call(CallToolRequestParams::new("add")).await;
+    assert_violations(a, &["/arguments"]);
+    Ok(())
+}
+
+#[test]
+async fn validation_is_disabled_by_default() -> Result<()> {
+    let client = McpClient::with_server(NoValidationServer).await?;
+    let a = client
+        .tools_call(call_add("add", json!({ "name": "Apple", "count": 20 })))
+        .await?;
+    let e: CallToolResult = ContentBlock::from("Apple x 20").into();
+    assert_eq!(a, e);
+    Ok(())
+}
+
+#[test]
+async fn builder() -> Result<()> {
+    let server = McpServerBuilder::new()
+        .route(route![route_add])
+        .validate_tool_arguments(true)
+        .build();
+    let client = McpClient::with_server(server).await?;
+    let a = client
+        .tools_call(call_add(
+            "route_add",
+            json!({ "name": "apple", "count": 0 }),
+        ))
+        .await;
+    assert_violations(a, &["/arguments/item/count"]);
+    Ok(())
+}
+
+#[test]
+async fn tools_list_is_called_once_per_session() -> Result<()> {
+    let count = Arc::new(AtomicUsize::new(0));
+    let client = McpClient::with_server(CountingServer(count.clone())).await?;
+    for _ in 0..3 {
+        client
+            .tools_call(CallToolRequestParams::new("echo"))
+            .await?;
+    }
+    assert_eq!(count.load(Ordering::SeqCst), 1);
+    Ok(())
+}